@@ -1,4 +1,6 @@
-#![feature(portable_simd)]
+// `portable_simd` requires a nightly toolchain: enable it with the `simd`
+// feature only, as in the library.
+#![cfg_attr(feature = "simd", feature(portable_simd), allow(unused_features))]
 use std::num::NonZeroU16;
 
 use criterion::{
@@ -6,7 +8,13 @@ use criterion::{
     criterion_main,
     Criterion,
 };
-use qn::System;
+use qn::{
+    Circuit,
+    Gate,
+    Operation,
+    Schedule,
+    System,
+};
 
 fn bench(c: &mut Criterion) {
     const NUM_QUBITS: u16 = 20;
@@ -21,6 +29,41 @@ fn bench(c: &mut Criterion) {
 
     group.bench_function("1. binary state", |b| b.iter(|| qubit.measure()));
 
+    // Layers of rotations followed by a ladder of entangling gates.
+    let mut circuit = Circuit::new(num_qubits);
+    for layer in 0..4 {
+        for k in 0..NUM_QUBITS {
+            let theta = f64::from(layer * NUM_QUBITS + k) / 10.;
+            circuit.apply(Gate::H(k));
+            circuit.apply(Gate::Rz(k, theta));
+            circuit.apply(Gate::Rx(k, theta));
+        }
+        for k in 1..NUM_QUBITS {
+            circuit.apply(Gate::CNOT(k - 1, k));
+        }
+    }
+    let optimized = circuit.optimize(2);
+    let mut stm = System::<f64>::new(num_qubits, 349_812);
+
+    group.bench_function("2. circuit", |b| b.iter(|| stm.run(&circuit)));
+    group.bench_function("3. optimized circuit", |b| {
+        b.iter(|| stm.run(&optimized))
+    });
+
+    let gates: Vec<_> = optimized
+        .operations()
+        .iter()
+        .filter_map(|op| match op {
+            Operation::Gate(gate) => Some(*gate),
+            Operation::Measure(_) => None,
+        })
+        .collect();
+    let schedule = Schedule::new(num_qubits, &gates);
+
+    group.bench_function("4. scheduled circuit", |b| {
+        b.iter(|| stm.apply_schedule(&schedule))
+    });
+
     group.finish();
 }
 
@@ -0,0 +1,444 @@
+use std::{
+    marker::PhantomData,
+    num::NonZeroU16,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use crate::{
+    Bit,
+    DecisionDiagramSystem,
+    DensityMatrixSystem,
+    Float,
+    Gate,
+    Layout,
+    MpsSystem,
+    SparseSystem,
+    StabilizerSystem,
+    System,
+};
+
+/// Quantum register that gates can be applied to and that can be measured.
+///
+/// Implemented for all simulators in this crate: [`System`] (with either
+/// layout, e.g. [`SoaSystem`]), [`DensityMatrixSystem`], [`SparseSystem`],
+/// [`StabilizerSystem`], [`MpsSystem`] and [`DecisionDiagramSystem`].  Code
+/// generic over this trait, e.g. [`Circuit::run()`], runs unchanged on any of
+/// them.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Backend, Bit, Gate, MpsSystem, StabilizerSystem, System};
+/// fn bell_pair<B: Backend<f64>>(stm: &mut B) -> (Bit, Bit) {
+///     stm.reset();
+///     stm.apply(&Gate::H(0));
+///     stm.apply(&Gate::CNOT(0, 1));
+///     (stm.measure(0).unwrap(), stm.measure(1).unwrap())
+/// }
+///
+/// let num_qubits = NonZeroU16::new(2).unwrap();
+/// let (a, b) = bell_pair(&mut System::new(num_qubits, 123));
+/// assert_eq!(a, b);
+/// let (a, b) = bell_pair(&mut StabilizerSystem::new(num_qubits, 123));
+/// assert_eq!(a, b);
+/// let (a, b) = bell_pair(&mut MpsSystem::new(num_qubits, 123));
+/// assert_eq!(a, b);
+/// ```
+///
+/// [`Circuit::run()`]: crate::Circuit::run
+/// [`SoaSystem`]: crate::SoaSystem
+pub trait Backend<T>
+where
+    T: Float,
+{
+    /// Get the number of qubits.
+    fn num_qubits(&self) -> NonZeroU16;
+
+    /// Apply a gate to the system.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the gate is not valid for this system, see
+    /// [`Gate::is_valid()`], or if the backend does not support the gate.
+    fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    );
+
+    /// Measure qubit `index` and collapse the state.
+    ///
+    /// Returns the reported outcome, subject to readout errors, if the
+    /// backend supports them.
+    ///
+    /// Returns `None`, if index is larger or equal than `self.num_qubits()`
+    fn measure(
+        &mut self,
+        index: u16,
+    ) -> Option<Bit>;
+
+    /// Probability of measuring qubit `index` in the state `ONE`.
+    ///
+    /// Returns `None`, if index is larger or equal than `self.num_qubits()`
+    fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T>;
+
+    /// Reset all qubits to the zero state.
+    fn reset(&mut self);
+}
+
+impl<T, L> Backend<T> for System<T, L>
+where
+    T: Float,
+    L: Layout,
+{
+    fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits()
+    }
+
+    fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        self.apply(gate);
+    }
+
+    fn measure(
+        &mut self,
+        index: u16,
+    ) -> Option<Bit> {
+        (index < self.num_qubits().get()).then(|| {
+            let outcome = self.measure(index);
+            self.read_out_bit(index, outcome).into()
+        })
+    }
+
+    fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T> {
+        self.probability(index)
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+}
+
+impl<T> Backend<T> for DensityMatrixSystem<T>
+where
+    T: Float,
+{
+    fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits()
+    }
+
+    fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        self.apply(gate);
+    }
+
+    fn measure(
+        &mut self,
+        index: u16,
+    ) -> Option<Bit> {
+        (index < self.num_qubits().get()).then(|| self.measure(index))
+    }
+
+    fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T> {
+        self.probability(index)
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+}
+
+impl<T> Backend<T> for SparseSystem<T>
+where
+    T: Float,
+{
+    fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits()
+    }
+
+    fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        self.apply(gate);
+    }
+
+    fn measure(
+        &mut self,
+        index: u16,
+    ) -> Option<Bit> {
+        (index < self.num_qubits().get()).then(|| self.measure(index).into())
+    }
+
+    fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T> {
+        self.probability(index)
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+}
+
+impl<T> Backend<T> for StabilizerSystem<T>
+where
+    T: Float,
+{
+    fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits()
+    }
+
+    fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        self.apply(gate);
+    }
+
+    fn measure(
+        &mut self,
+        index: u16,
+    ) -> Option<Bit> {
+        (index < self.num_qubits().get()).then(|| self.measure(index).into())
+    }
+
+    fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T> {
+        self.probability(index)
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+}
+
+impl<T> Backend<T> for MpsSystem<T>
+where
+    T: Float,
+{
+    fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits()
+    }
+
+    fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        self.apply(gate);
+    }
+
+    fn measure(
+        &mut self,
+        index: u16,
+    ) -> Option<Bit> {
+        (index < self.num_qubits().get()).then(|| self.measure(index).into())
+    }
+
+    fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T> {
+        self.probability(index)
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+}
+
+impl<T> Backend<T> for DecisionDiagramSystem<T>
+where
+    T: Float,
+{
+    fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits()
+    }
+
+    fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        self.apply(gate);
+    }
+
+    fn measure(
+        &mut self,
+        index: u16,
+    ) -> Option<Bit> {
+        (index < self.num_qubits().get()).then(|| self.measure(index).into())
+    }
+
+    fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T> {
+        self.probability(index)
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+}
+
+/// A representation of a qubit in a quantum system simulated by a
+/// [`Backend`].
+///
+/// Qubits of the same system can be moved to different threads.  Each
+/// operation locks the system, so operations are applied in the order the
+/// locks are acquired.  [`System`] has its own handle, [`Qubit`], which does
+/// not wait for the lock to apply single-qubit gates.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Bit, Gate, StabilizerSystem};
+/// let num_qubits = NonZeroU16::new(2).unwrap();
+/// let mut stm: StabilizerSystem<f64> = StabilizerSystem::new(num_qubits, 123);
+///
+/// std::thread::scope(|s| {
+///     for mut qubit in stm.qubit_iter() {
+///         s.spawn(move || {
+///             let k = qubit.index();
+///             qubit.apply(&Gate::X(k));
+///             assert_eq!(qubit.measure(), Bit::ONE);
+///         });
+///     }
+/// });
+/// ```
+///
+/// [`Qubit`]: crate::Qubit
+pub struct BackendQubit<'a, T, B>
+where
+    T: Float,
+    B: Backend<T>,
+{
+    stm:     Arc<Mutex<&'a mut B>>,
+    index:   u16,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<'a, T, B> BackendQubit<'a, T, B>
+where
+    T: Float,
+    B: Backend<T>,
+{
+    /// Derive a single qubit from a quantum system.
+    ///
+    /// Returns `None`, if index is larger or equal than `stm.num_qubits()`
+    pub fn new(
+        stm: &'a mut B,
+        index: u16,
+    ) -> Option<Self> {
+        Self::new_iter(stm).nth(usize::from(index))
+    }
+
+    /// Get a pair of qubits from the same system.
+    ///
+    /// # Result
+    ///
+    /// Returns `None`
+    /// - if any of indices is larger or equal than `stm.num_qubits()`
+    /// - if indices are equal
+    pub(crate) fn new_pair(
+        stm: &'a mut B,
+        index1: u16,
+        index2: u16,
+    ) -> Option<(Self, Self)> {
+        if index1 >= stm.num_qubits().get()
+            || index2 >= stm.num_qubits().get()
+            || index1 == index2
+        {
+            return None;
+        }
+
+        let lock = Arc::new(Mutex::new(stm));
+        let qb1 = Self {
+            stm:     lock.clone(),
+            index:   index1,
+            phantom: PhantomData,
+        };
+        let qb2 = Self {
+            stm:     lock,
+            index:   index2,
+            phantom: PhantomData,
+        };
+        Some((qb1, qb2))
+    }
+
+    /// Get iterator over all qubits in system
+    pub(crate) fn new_iter(stm: &'a mut B) -> impl Iterator<Item = Self> {
+        let num_qubits = stm.num_qubits().get();
+        let lock = Arc::new(Mutex::new(stm));
+
+        (0..num_qubits).map(move |i| Self {
+            stm:     lock.clone(),
+            index:   i,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Get index of this qubit in the underlying system
+    #[must_use]
+    pub fn index(&self) -> u16 {
+        self.index
+    }
+
+    /// Check if other qubit belongs to the same system
+    #[must_use]
+    pub fn is_from_same_stm(
+        &self,
+        other_qubit: &Self,
+    ) -> bool {
+        Arc::<_>::as_ptr(&self.stm) == Arc::<_>::as_ptr(&other_qubit.stm)
+    }
+
+    /// Apply a gate acting on the qubit.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the gate does not act on this qubit, or if it is not valid
+    /// for the system, see [`Backend::apply()`].
+    pub fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        assert!(
+            gate.qubits().contains(&self.index),
+            "invalid gate: {gate:?}"
+        );
+        self.stm.lock().unwrap().apply(gate);
+    }
+
+    /// Measure the qubit.
+    ///
+    /// Returns the reported outcome, see [`Backend::measure()`].
+    #[must_use]
+    pub fn measure(&mut self) -> Bit {
+        self.stm
+            .lock()
+            .unwrap()
+            .measure(self.index)
+            .expect("valid qubit index")
+    }
+}
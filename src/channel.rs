@@ -0,0 +1,449 @@
+use std::fmt;
+
+use num::{
+    Complex,
+    One,
+    Zero,
+};
+
+use crate::{
+    gate::{
+        Gate,
+        Kernel,
+    },
+    Float,
+    Matrix2,
+    Matrix4,
+};
+
+/// Error type returned when constructing a quantum channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelError {
+    /// A probability parameter is outside of the interval `[0, 1]`.
+    InvalidProbability,
+    /// Relaxation times are not positive, or `t2 > 2 * t1`.
+    InvalidTimes,
+    /// The set of Kraus operators is empty.
+    Empty,
+    /// Kraus operators do not satisfy `sum_k K^dagger K = I`.
+    NotTracePreserving,
+}
+
+impl fmt::Display for ChannelError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::InvalidProbability => {
+                write!(f, "probability outside of range [0, 1]")
+            }
+            Self::InvalidTimes => write!(f, "invalid relaxation times"),
+            Self::Empty => write!(f, "empty set of Kraus operators"),
+            Self::NotTracePreserving => {
+                write!(f, "Kraus operators are not trace preserving")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChannelError {}
+
+/// Kraus operators acting on one or two qubits.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum KrausOps<T> {
+    One(Vec<Matrix2<T>>),
+    Two(Vec<Matrix4<T>>),
+}
+
+/// Quantum channel given by a set of Kraus operators `K_k`:
+///
+/// `rho -> sum_k K_k rho K_k^dagger`.
+///
+/// A channel is not bound to any particular qubits.  The target qubits are
+/// specified when the channel is applied to a system.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Channel, DensityMatrixSystem, Gate};
+/// let num_qubits = NonZeroU16::new(1).unwrap();
+/// let mut stm: DensityMatrixSystem<f64> =
+///     DensityMatrixSystem::new(num_qubits, 123);
+/// stm.apply(&Gate::X(0));
+///
+/// let channel = Channel::amplitude_damping(1.).unwrap();
+/// stm.apply_channel(&channel, &[0]);
+///
+/// assert!(stm.probability(0).unwrap().abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Channel<T> {
+    ops: KrausOps<T>,
+}
+
+/// Check if `p` is a probability.
+fn check_probability<T>(p: T) -> Result<T, ChannelError>
+where
+    T: Float,
+{
+    if (T::zero()..=T::one()).contains(&p) {
+        Ok(p)
+    } else {
+        Err(ChannelError::InvalidProbability)
+    }
+}
+
+/// Matrix of a single-qubit gate.
+fn matrix<T>(gate: &Gate<T>) -> Matrix2<T>
+where
+    T: Float,
+{
+    match gate.kernel() {
+        Kernel::One(_, m) => m,
+        Kernel::Two(..) => unreachable!(),
+    }
+}
+
+/// Multiply a matrix by a scalar.
+fn scale2<T>(
+    m: &Matrix2<T>,
+    x: T,
+) -> Matrix2<T>
+where
+    T: Float,
+{
+    m.map(|row| row.map(|z| z * x))
+}
+
+/// Product of two single-qubit matrices.
+fn mul2<T>(
+    a: &Matrix2<T>,
+    b: &Matrix2<T>,
+) -> Matrix2<T>
+where
+    T: Float,
+{
+    let mut c = [[Complex::zero(); 2]; 2];
+    for (i, row) in c.iter_mut().enumerate() {
+        for (j, z) in row.iter_mut().enumerate() {
+            *z = a[i][0] * b[0][j] + a[i][1] * b[1][j];
+        }
+    }
+    c
+}
+
+/// Kronecker product `b (x) a` of single-qubit matrices, with `a` acting on
+/// the first and `b` on the second qubit.
+fn kron2<T>(
+    a: &Matrix2<T>,
+    b: &Matrix2<T>,
+) -> Matrix4<T>
+where
+    T: Float,
+{
+    let mut c = [[Complex::zero(); 4]; 4];
+    for (r, row) in c.iter_mut().enumerate() {
+        for (s, z) in row.iter_mut().enumerate() {
+            *z = a[r & 1][s & 1] * b[r >> 1][s >> 1];
+        }
+    }
+    c
+}
+
+/// Check if Kraus operators satisfy `sum_k K^dagger K = I`.
+fn is_complete<T, const N: usize>(ops: &[[[Complex<T>; N]; N]]) -> bool
+where
+    T: Float,
+{
+    let tolerance = T::epsilon().sqrt();
+    (0..N).all(|i| {
+        (0..N).all(|j| {
+            let sum: Complex<T> = ops
+                .iter()
+                .flat_map(|k| (0..N).map(move |l| k[l][i].conj() * k[l][j]))
+                .sum();
+            let id = if i == j {
+                Complex::one()
+            } else {
+                Complex::zero()
+            };
+            (sum - id).norm() <= tolerance
+        })
+    })
+}
+
+impl<T> Channel<T>
+where
+    T: Float,
+{
+    /// Single-qubit channel with user-defined Kraus operators.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if `ops` is empty or if the operators do not
+    /// satisfy the completeness relation `sum_k K^dagger K = I`.
+    pub fn from_kraus(ops: Vec<Matrix2<T>>) -> Result<Self, ChannelError> {
+        if ops.is_empty() {
+            return Err(ChannelError::Empty);
+        }
+        if !is_complete(&ops) {
+            return Err(ChannelError::NotTracePreserving);
+        }
+        Ok(Self {
+            ops: KrausOps::One(ops),
+        })
+    }
+
+    /// Two-qubit channel with user-defined Kraus operators.
+    ///
+    /// See [`Matrix4`] for the ordering of basis states.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if `ops` is empty or if the operators do not
+    /// satisfy the completeness relation `sum_k K^dagger K = I`.
+    pub fn from_kraus2(ops: Vec<Matrix4<T>>) -> Result<Self, ChannelError> {
+        if ops.is_empty() {
+            return Err(ChannelError::Empty);
+        }
+        if !is_complete(&ops) {
+            return Err(ChannelError::NotTracePreserving);
+        }
+        Ok(Self {
+            ops: KrausOps::Two(ops),
+        })
+    }
+
+    /// Pauli channel: apply X, Y, Z with probabilities `px`, `py`, `pz`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if any of the probabilities, or their sum, is
+    /// outside of the interval `[0, 1]`.
+    pub fn pauli(
+        px: T,
+        py: T,
+        pz: T,
+    ) -> Result<Self, ChannelError> {
+        let p = [px, py, pz].map(check_probability);
+        let [px, py, pz] = [p[0]?, p[1]?, p[2]?];
+        let pi = T::one() - px - py - pz;
+        if pi < -T::epsilon() {
+            return Err(ChannelError::InvalidProbability);
+        }
+        let pi = pi.max(T::zero());
+
+        let (zero, one) = (Complex::zero(), Complex::one());
+        let id = [[one, zero], [zero, one]];
+        let ops = [
+            (pi, id),
+            (px, matrix(&Gate::X(0))),
+            (py, matrix(&Gate::Y(0))),
+            (pz, matrix(&Gate::Z(0))),
+        ]
+        .into_iter()
+        .filter(|(p, _)| *p > T::zero())
+        .map(|(p, m)| scale2(&m, p.sqrt()))
+        .collect();
+        Self::from_kraus(ops)
+    }
+
+    /// Depolarizing channel: `rho -> (1 - p) rho + p/3 (X rho X + Y rho Y +
+    /// Z rho Z)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if `p` is outside of the interval `[0, 1]`.
+    pub fn depolarizing(p: T) -> Result<Self, ChannelError> {
+        let p = check_probability(p)? / T::from(3).unwrap();
+        Self::pauli(p, p, p)
+    }
+
+    /// Two-qubit depolarizing channel: with probability `p` apply one of the
+    /// 15 non-identity two-qubit Pauli operators, chosen uniformly.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if `p` is outside of the interval `[0, 1]`.
+    pub fn depolarizing2(p: T) -> Result<Self, ChannelError> {
+        let p = check_probability(p)?;
+        let id = [
+            [Complex::one(), Complex::zero()],
+            [Complex::zero(), Complex::one()],
+        ];
+        let paulis = [
+            id,
+            matrix(&Gate::X(0)),
+            matrix(&Gate::Y(0)),
+            matrix(&Gate::Z(0)),
+        ];
+        let w_id = (T::one() - p).sqrt();
+        let w = (p / T::from(15).unwrap()).sqrt();
+
+        let mut ops = Vec::with_capacity(16);
+        for (i, a) in paulis.iter().enumerate() {
+            for (j, b) in paulis.iter().enumerate() {
+                let w = if i == 0 && j == 0 { w_id } else { w };
+                if w > T::zero() {
+                    ops.push(kron2(a, b).map(|row| row.map(|z| z * w)));
+                }
+            }
+        }
+        Self::from_kraus2(ops)
+    }
+
+    /// Bit-flip channel: apply X with probability `p`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if `p` is outside of the interval `[0, 1]`.
+    pub fn bit_flip(p: T) -> Result<Self, ChannelError> {
+        Self::pauli(p, T::zero(), T::zero())
+    }
+
+    /// Phase-flip channel: apply Z with probability `p`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if `p` is outside of the interval `[0, 1]`.
+    pub fn phase_flip(p: T) -> Result<Self, ChannelError> {
+        Self::pauli(T::zero(), T::zero(), p)
+    }
+
+    /// Amplitude-damping channel: decay `|1> -> |0>` with probability
+    /// `gamma`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if `gamma` is outside of the interval `[0, 1]`.
+    pub fn amplitude_damping(gamma: T) -> Result<Self, ChannelError> {
+        let gamma = check_probability(gamma)?;
+        let (zero, one) = (Complex::zero(), Complex::one());
+        Self::from_kraus(vec![
+            [
+                [one, zero],
+                [zero, Complex::from((T::one() - gamma).sqrt())],
+            ],
+            [[zero, Complex::from(gamma.sqrt())], [zero, zero]],
+        ])
+    }
+
+    /// Phase-damping channel: off-diagonal entries of the density matrix are
+    /// multiplied by `sqrt(1 - lambda)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if `lambda` is outside of the interval `[0, 1]`.
+    pub fn phase_damping(lambda: T) -> Result<Self, ChannelError> {
+        let lambda = check_probability(lambda)?;
+        let (zero, one) = (Complex::zero(), Complex::one());
+        Self::from_kraus(vec![
+            [
+                [one, zero],
+                [zero, Complex::from((T::one() - lambda).sqrt())],
+            ],
+            [[zero, zero], [zero, Complex::from(lambda.sqrt())]],
+        ])
+    }
+
+    /// Thermal relaxation of a qubit during `time`.
+    ///
+    /// The qubit relaxes towards the thermal state with population
+    /// `excited_population` of the state `|1>`, with energy relaxation time
+    /// `t1` and dephasing time `t2`.  Populations relax as `e^{-time/t1}` and
+    /// coherences decay as `e^{-time/t2}`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error
+    /// - if `t1` or `t2` is not positive, `time` is negative, or `t2 > 2 * t1`
+    /// - if `excited_population` is outside of the interval `[0, 1]`
+    pub fn thermal_relaxation(
+        t1: T,
+        t2: T,
+        time: T,
+        excited_population: T,
+    ) -> Result<Self, ChannelError> {
+        if t1 <= T::zero()
+            || t2 <= T::zero()
+            || time < T::zero()
+            || t2 > t1 + t1
+        {
+            return Err(ChannelError::InvalidTimes);
+        }
+        let pe = check_probability(excited_population)?;
+        let gamma = T::one() - (-time / t1).exp();
+        // Generalized amplitude damping reduces coherences by sqrt(1 -
+        // gamma) = e^{-time/(2 t1)}.  Pure dephasing accounts for the rest.
+        let lambda =
+            (T::one() - (time / t1 - (time + time) / t2).exp()).max(T::zero());
+
+        let (zero, one) = (Complex::zero(), Complex::one());
+        let decay = Complex::from((T::one() - gamma).sqrt());
+        let jump = Complex::from(gamma.sqrt());
+        let damping = [
+            scale2(&[[one, zero], [zero, decay]], (T::one() - pe).sqrt()),
+            scale2(&[[zero, jump], [zero, zero]], (T::one() - pe).sqrt()),
+            scale2(&[[decay, zero], [zero, one]], pe.sqrt()),
+            scale2(&[[zero, zero], [jump, zero]], pe.sqrt()),
+        ];
+        let dephasing = [
+            [
+                [one, zero],
+                [zero, Complex::from((T::one() - lambda).sqrt())],
+            ],
+            [[zero, zero], [zero, Complex::from(lambda.sqrt())]],
+        ];
+
+        let ops = dephasing
+            .iter()
+            .flat_map(|p| damping.iter().map(move |a| mul2(p, a)))
+            .filter(|k| k.iter().flatten().any(|z| !z.is_zero()))
+            .collect();
+        Self::from_kraus(ops)
+    }
+
+    /// Number of qubits the channel acts on: 1 or 2.
+    #[must_use]
+    pub fn num_qubits(&self) -> u16 {
+        match self.ops {
+            KrausOps::One(_) => 1,
+            KrausOps::Two(_) => 2,
+        }
+    }
+
+    /// Kraus operators as kernels acting on `qubits`.
+    pub(crate) fn kernels(
+        &self,
+        qubits: &[u16],
+    ) -> Vec<Kernel<T>> {
+        match &self.ops {
+            KrausOps::One(ops) => {
+                ops.iter().map(|m| Kernel::One(qubits[0], *m)).collect()
+            }
+            KrausOps::Two(ops) => ops
+                .iter()
+                .map(|m| Kernel::Two(qubits[0], qubits[1], *m))
+                .collect(),
+        }
+    }
+
+    /// Check if the channel can be applied to `qubits` of a system of
+    /// `num_qubits` qubits.
+    ///
+    /// Returns `false`, if the number of `qubits` differs from
+    /// `self.num_qubits()`, if any qubit index is larger or equal than
+    /// `num_qubits`, or if indices are not distinct.
+    #[must_use]
+    pub fn is_valid(
+        &self,
+        qubits: &[u16],
+        num_qubits: u16,
+    ) -> bool {
+        qubits.len() == usize::from(self.num_qubits())
+            && qubits.iter().all(|&i| i < num_qubits)
+            && (qubits.len() < 2 || qubits[0] != qubits[1])
+    }
+}
@@ -0,0 +1,320 @@
+use std::num::NonZeroU16;
+
+use crate::{
+    fusion,
+    Backend,
+    Bit,
+    Channel,
+    DensityMatrixSystem,
+    Float,
+    Gate,
+    Layout,
+    NoiseModel,
+    System,
+};
+
+/// Single operation recorded in a circuit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation<T> {
+    /// Apply a gate.
+    Gate(Gate<T>),
+    /// Measure a qubit.
+    Measure(u16),
+}
+
+/// Sequence of gates and measurements that can be executed on a system.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Circuit, Gate, System};
+/// let num_qubits = NonZeroU16::new(2).unwrap();
+/// let mut circuit = Circuit::new(num_qubits);
+/// circuit.apply(Gate::H(0));
+/// circuit.apply(Gate::CNOT(0, 1));
+/// circuit.measure(0);
+/// circuit.measure(1);
+///
+/// let mut stm: System<f64> = System::new(num_qubits, 123);
+/// let outcomes = stm.run(&circuit);
+///
+/// assert_eq!(outcomes[0], outcomes[1]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Circuit<T> {
+    num_qubits: NonZeroU16,
+    ops:        Vec<Operation<T>>,
+}
+
+impl<T> Circuit<T>
+where
+    T: Float,
+{
+    /// Create an empty circuit on `num_qubits` qubits.
+    #[must_use]
+    pub fn new(num_qubits: NonZeroU16) -> Self {
+        Self {
+            num_qubits,
+            ops: Vec::new(),
+        }
+    }
+
+    /// Get the number of qubits.
+    #[must_use]
+    pub fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits
+    }
+
+    /// Get recorded operations.
+    #[must_use]
+    pub fn operations(&self) -> &[Operation<T>] {
+        &self.ops
+    }
+
+    /// Record a gate.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the gate is not valid for this circuit, see
+    /// [`Gate::is_valid()`].
+    pub fn apply(
+        &mut self,
+        gate: Gate<T>,
+    ) {
+        assert!(
+            gate.is_valid(self.num_qubits.get()),
+            "invalid gate: {gate:?}"
+        );
+        self.ops.push(Operation::Gate(gate));
+    }
+
+    /// Record measurement of qubit `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if index is larger or equal than `self.num_qubits()`
+    pub fn measure(
+        &mut self,
+        index: u16,
+    ) {
+        assert!(
+            index < self.num_qubits.get(),
+            "invalid qubit index: {index}"
+        );
+        self.ops.push(Operation::Measure(index));
+    }
+
+    /// Optimize the circuit for execution on a state vector.
+    ///
+    /// Consecutive gates are fused into dense unitaries, [`Gate::U`] and
+    /// [`Gate::U2`], acting on at most `max_qubits` qubits, so that each
+    /// fused block is applied with a single sweep over the state.  Gates
+    /// acting on the same qubits are always fused.  A gate is moved past
+    /// gates acting on other qubits, and past other diagonal gates when it
+    /// is diagonal itself, to find a block it can be fused with.  Blocks
+    /// that multiply to the identity, like pairs of inverse gates, are
+    /// removed.  Measurements are kept in place.
+    ///
+    /// Blocks of a single gate keep the original gate.  Fused blocks are not
+    /// Clifford gates, even if all fused gates were.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `max_qubits` is not 1 or 2.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::num::NonZeroU16;
+    /// # use qn::{Circuit, Gate};
+    /// let mut circuit = Circuit::<f64>::new(NonZeroU16::new(2).unwrap());
+    /// circuit.apply(Gate::H(0));
+    /// circuit.apply(Gate::Rz(0, 0.3));
+    /// circuit.apply(Gate::CNOT(0, 1));
+    /// circuit.apply(Gate::CNOT(0, 1));
+    /// circuit.apply(Gate::Rz(0, -0.3));
+    /// circuit.apply(Gate::H(0));
+    ///
+    /// assert!(circuit.optimize(1).operations().is_empty());
+    /// ```
+    #[must_use]
+    pub fn optimize(
+        &self,
+        max_qubits: u16,
+    ) -> Self {
+        assert!(
+            (1..=2).contains(&max_qubits),
+            "invalid number of qubits: {max_qubits}"
+        );
+        fusion::optimize(self, max_qubits)
+    }
+
+    /// Execute the circuit on a backend.
+    ///
+    /// Returns reported outcomes of measurements in the order they appear in
+    /// the circuit.
+    ///
+    /// # Panics
+    ///
+    /// Panics
+    /// - if the circuit has more qubits than the backend
+    /// - if the backend does not support any of the gates, see
+    ///   [`Backend::apply()`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::num::NonZeroU16;
+    /// # use qn::{Circuit, Gate, StabilizerSystem};
+    /// let mut circuit = Circuit::new(NonZeroU16::new(3).unwrap());
+    /// circuit.apply(Gate::X(0));
+    /// circuit.apply(Gate::SWAP(0, 2));
+    /// circuit.measure(2);
+    ///
+    /// let num_qubits = NonZeroU16::new(1000).unwrap();
+    /// let mut stm: StabilizerSystem<f64> = StabilizerSystem::new(num_qubits, 123);
+    /// let outcomes = circuit.run(&mut stm);
+    ///
+    /// assert!(bool::from(outcomes[0]));
+    /// ```
+    pub fn run<B>(
+        &self,
+        stm: &mut B,
+    ) -> Vec<Bit>
+    where
+        B: Backend<T>,
+    {
+        execute_with(self, stm, |_, _| {}, |_, _, outcome| outcome)
+    }
+}
+
+/// System on which a circuit can be executed subject to noise.
+pub(crate) trait Execute<T>: Backend<T>
+where
+    T: Float,
+{
+    fn apply_noise(
+        &mut self,
+        channel: &Channel<T>,
+        qubits: &[u16],
+    );
+
+    /// Apply readout errors of a noise model to a reported outcome.
+    fn read_out(
+        &mut self,
+        noise: &NoiseModel<T>,
+        index: u16,
+        outcome: bool,
+    ) -> bool;
+}
+
+/// Execute circuit on a system subject to noise.
+///
+/// Returns reported outcomes of measurements in the order they appear in the
+/// circuit.
+pub(crate) fn execute_noisy<T, S>(
+    circuit: &Circuit<T>,
+    stm: &mut S,
+    noise: &NoiseModel<T>,
+) -> Vec<Bit>
+where
+    T: Float,
+    S: Execute<T>,
+{
+    let num_qubits = circuit.num_qubits().get();
+    execute_with(
+        circuit,
+        stm,
+        |stm, gate| {
+            for (channel, qubits) in noise.channels_after(gate, num_qubits) {
+                stm.apply_noise(channel, &qubits);
+            }
+        },
+        |stm, index, outcome| stm.read_out(noise, index, outcome.into()).into(),
+    )
+}
+
+/// Execute circuit, calling `after_gate` after each gate and passing each
+/// measurement outcome through `read_out`.
+fn execute_with<T, S>(
+    circuit: &Circuit<T>,
+    stm: &mut S,
+    mut after_gate: impl FnMut(&mut S, &Gate<T>),
+    mut read_out: impl FnMut(&mut S, u16, Bit) -> Bit,
+) -> Vec<Bit>
+where
+    T: Float,
+    S: Backend<T>,
+{
+    assert!(
+        circuit.num_qubits() <= stm.num_qubits(),
+        "circuit does not fit in the system"
+    );
+
+    let mut outcomes = Vec::new();
+    for op in circuit.operations() {
+        match op {
+            Operation::Gate(gate) => {
+                stm.apply(gate);
+                after_gate(stm, gate);
+            }
+            Operation::Measure(index) => {
+                let outcome = stm.measure(*index).expect("valid qubit index");
+                outcomes.push(read_out(stm, *index, outcome));
+            }
+        }
+    }
+    outcomes
+}
+
+impl<T, L> Execute<T> for System<T, L>
+where
+    T: Float,
+    L: Layout,
+{
+    fn apply_noise(
+        &mut self,
+        channel: &Channel<T>,
+        qubits: &[u16],
+    ) {
+        self.apply_channel(channel, qubits);
+    }
+
+    fn read_out(
+        &mut self,
+        noise: &NoiseModel<T>,
+        index: u16,
+        outcome: bool,
+    ) -> bool {
+        match noise.readout() {
+            Some(model) => self.read_out_bit_with(model, index, outcome),
+            None => outcome,
+        }
+    }
+}
+
+impl<T> Execute<T> for DensityMatrixSystem<T>
+where
+    T: Float,
+{
+    fn apply_noise(
+        &mut self,
+        channel: &Channel<T>,
+        qubits: &[u16],
+    ) {
+        self.apply_channel(channel, qubits);
+    }
+
+    fn read_out(
+        &mut self,
+        noise: &NoiseModel<T>,
+        index: u16,
+        outcome: bool,
+    ) -> bool {
+        match noise.readout() {
+            Some(model) => model.read_bit(index, outcome, self.rng_mut()),
+            None => outcome,
+        }
+    }
+}
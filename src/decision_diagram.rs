@@ -0,0 +1,658 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroU16,
+};
+
+use num::{
+    Complex,
+    One,
+    Zero,
+};
+use rand::{
+    distributions::{
+        Bernoulli,
+        Distribution,
+    },
+    SeedableRng,
+};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    gate::Kernel,
+    Bit,
+    Float,
+    Gate,
+    Matrix2,
+    System,
+};
+
+/// Index of the terminal node in the node table.
+const TERMINAL: usize = 0;
+
+/// Minimal number of nodes in the table before garbage collection.
+const GC_MIN_NODES: usize = 1 << 12;
+
+/// Weighted edge pointing to a node.  Zero edges point to the terminal node.
+#[derive(Debug, Clone, Copy)]
+struct Edge<T> {
+    w: Complex<T>,
+    n: usize,
+}
+
+/// Node at level `level` (qubit index), with edges for the qubit in the state
+/// `ZERO` and `ONE`.  Edges point to nodes at level `level - 1`, or to the
+/// terminal node, if `level` is zero.
+#[derive(Debug, Clone, Copy)]
+struct Node<T> {
+    level: u16,
+    e:     [Edge<T>; 2],
+}
+
+/// Weight rounded to a multiple of the tolerance, used as a hash key.
+type WeightKey = (i64, i64);
+
+type UniqueKey = (u16, [(WeightKey, usize); 2]);
+
+/// Memoized results of adding two edges: `(node a, node b, ratio of weights)`.
+type AddTable<T> = HashMap<(usize, usize, WeightKey), Edge<T>>;
+
+/// Quantum system of qubits in a pure state, represented as an edge-weighted
+/// decision diagram (QMDD).
+///
+/// The state vector is a binary tree: the root node branches on the last
+/// qubit, its children on the second to last, and so on, down to the first
+/// qubit and a single terminal node.  Each edge carries a complex weight and
+/// the amplitude of a basis state is the product of weights along its path.
+/// Identical subtrees are stored once: nodes are normalized and kept in a
+/// unique table, so states with a lot of structure, like GHZ or graph states,
+/// need memory linear in the number of qubits.
+///
+/// Weights are compared with the tolerance of `1024 * T::epsilon()`.  Edges
+/// whose weights are smaller, relative to the other edge of the same node,
+/// are dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Bit, DecisionDiagramSystem, Gate};
+/// let num_qubits = NonZeroU16::new(100).unwrap();
+/// let mut stm: DecisionDiagramSystem<f64> =
+///     DecisionDiagramSystem::new(num_qubits, 123);
+///
+/// stm.apply(&Gate::H(0));
+/// for k in 1..100 {
+///     stm.apply(&Gate::CNOT(k - 1, k));
+/// }
+/// assert_eq!(stm.num_nodes(), 199);
+///
+/// let amp = stm.amplitude(&[Bit::ONE; 100]).unwrap();
+/// assert!((amp.re - 0.5f64.sqrt()).abs() < 1e-12);
+/// ```
+pub struct DecisionDiagramSystem<T>
+where
+    T: Float,
+{
+    rng:        ChaCha8Rng,
+    num_qubits: NonZeroU16,
+    nodes:      Vec<Node<T>>,
+    unique:     HashMap<UniqueKey, usize>,
+    root:       Edge<T>,
+    gc_size:    usize,
+    tol:        T,
+}
+
+impl<T> DecisionDiagramSystem<T>
+where
+    T: Float,
+{
+    /// Initialize a new quantum system of `n` qubits in the zero state.
+    ///
+    /// Seed internal RNG with `seed`.
+    #[must_use]
+    pub fn new(
+        num_qubits: NonZeroU16,
+        seed: u64,
+    ) -> Self {
+        let mut stm = Self::empty(num_qubits, ChaCha8Rng::seed_from_u64(seed));
+        stm.reset();
+        stm
+    }
+
+    fn empty(
+        num_qubits: NonZeroU16,
+        rng: ChaCha8Rng,
+    ) -> Self {
+        let zero = Edge {
+            w: Complex::zero(),
+            n: TERMINAL,
+        };
+        let terminal = Node {
+            level: 0,
+            e:     [zero; 2],
+        };
+        Self {
+            rng,
+            num_qubits,
+            nodes: vec![terminal],
+            unique: HashMap::new(),
+            root: zero,
+            gc_size: GC_MIN_NODES,
+            tol: T::epsilon() * T::from(1024).unwrap(),
+        }
+    }
+
+    /// Initialize a system with the same state as `stm`.
+    ///
+    /// The RNG state is copied from `stm`.
+    #[must_use]
+    pub fn from_system(stm: &System<T>) -> Self {
+        let mut dd = Self::empty(stm.num_qubits(), stm.rng().clone());
+        dd.root = dd.build(stm.as_slice(), stm.num_qubits().get());
+        dd
+    }
+
+    /// Convert the state to a dense [`System`].
+    ///
+    /// The RNG state is carried over.
+    #[must_use]
+    pub fn to_system(&self) -> System<T> {
+        let mut stm = System::new(self.num_qubits, 0);
+        *stm.rng_mut() = self.rng.clone();
+        let amp = stm.as_mut_slice();
+        amp[0] = Complex::zero();
+        self.fill(self.root, Complex::one(), 0, amp);
+        stm
+    }
+
+    /// Get the number of qubits.
+    #[must_use]
+    pub fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits
+    }
+
+    /// Number of distinct nodes in the diagram, excluding the terminal node.
+    #[must_use]
+    pub fn num_nodes(&self) -> usize {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut stack = vec![self.root.n];
+        let mut count = 0;
+        while let Some(n) = stack.pop() {
+            if n == TERMINAL || seen[n] {
+                continue;
+            }
+            seen[n] = true;
+            count += 1;
+            stack.extend(self.nodes[n].e.iter().map(|e| e.n));
+        }
+        count
+    }
+
+    /// Reset all qubits to the zero state.
+    ///
+    /// The RNG is not affected.
+    pub fn reset(&mut self) {
+        self.nodes.truncate(1);
+        self.unique.clear();
+        self.gc_size = GC_MIN_NODES;
+        let mut e = Edge {
+            w: Complex::one(),
+            n: TERMINAL,
+        };
+        for level in 0..self.num_qubits.get() {
+            e = self.make_node(level, e, self.zero());
+        }
+        self.root = e;
+    }
+
+    /// Apply a gate to the system.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the gate is not valid for this system, see
+    /// [`Gate::is_valid()`].
+    pub fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        assert!(
+            gate.is_valid(self.num_qubits.get()),
+            "invalid gate: {gate:?}"
+        );
+        let mut add = HashMap::new();
+        self.root = match gate.kernel() {
+            Kernel::One(t, m) => {
+                self.apply_one(self.root, t, &m, &mut HashMap::new(), &mut add)
+            }
+            Kernel::Two(q0, q1, m) => {
+                // Split the matrix into 2x2 blocks acting on the lower
+                // qubit: blocks[r][c] maps the higher qubit from c to r.
+                let (hi, lo) = (q0.max(q1), q0.min(q1));
+                let index = |hi_bit: usize, lo_bit: usize| {
+                    if q1 > q0 {
+                        lo_bit + 2 * hi_bit
+                    } else {
+                        hi_bit + 2 * lo_bit
+                    }
+                };
+                let blocks = [0, 1].map(|r| {
+                    [0, 1].map(|c| {
+                        [0, 1].map(|i| {
+                            [0, 1].map(|j| m[index(r, i)][index(c, j)])
+                        })
+                    })
+                });
+                let mut memos = Default::default();
+                self.apply_two(
+                    self.root,
+                    (hi, lo),
+                    &blocks,
+                    &mut HashMap::new(),
+                    &mut memos,
+                    &mut add,
+                )
+            }
+        };
+        self.collect_garbage();
+    }
+
+    /// Probability of measuring qubit `index` in the state `ONE`.
+    ///
+    /// Returns `None`, if index is larger or equal than `self.num_qubits()`
+    #[must_use]
+    pub fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T> {
+        if index >= self.num_qubits.get() {
+            return None;
+        }
+        let mut norms = HashMap::new();
+        let total = self.norm_sqr(self.root.n, &mut norms);
+        let one =
+            self.mass_one(self.root.n, index, &mut HashMap::new(), &mut norms);
+        Some(one / total)
+    }
+
+    /// Amplitude of the computational basis state given by `bits`, where
+    /// `bits[k]` is the value of qubit `k`.
+    ///
+    /// Returns `None`, if the length of `bits` is not `self.num_qubits()`
+    #[must_use]
+    pub fn amplitude(
+        &self,
+        bits: &[Bit],
+    ) -> Option<Complex<T>> {
+        if bits.len() != usize::from(self.num_qubits.get()) {
+            return None;
+        }
+        let mut e = self.root;
+        let mut amp = Complex::<T>::one();
+        while e.n != TERMINAL {
+            amp *= e.w;
+            let node = &self.nodes[e.n];
+            e = node.e[usize::from(bool::from(bits[usize::from(node.level)]))];
+        }
+        Some(amp * e.w)
+    }
+
+    /// Measure qubit `index` and project the state onto the outcome.
+    pub(crate) fn measure(
+        &mut self,
+        index: u16,
+    ) -> bool {
+        let amp_sq_1 = self.probability(index).unwrap();
+        let p = T::to_f64(&amp_sq_1).unwrap().clamp(0., 1.);
+        let outcome = Bernoulli::new(p).unwrap().sample(&mut self.rng);
+
+        let (zero, one) = (Complex::zero(), Complex::one());
+        let (projector, norm_factor) = if outcome {
+            ([[zero, zero], [zero, one]], amp_sq_1.sqrt())
+        } else {
+            ([[one, zero], [zero, zero]], (T::one() - amp_sq_1).sqrt())
+        };
+        let root = self.apply_one(
+            self.root,
+            index,
+            &projector,
+            &mut HashMap::new(),
+            &mut HashMap::new(),
+        );
+        self.root = self.scale(root, Complex::from(norm_factor.recip()));
+        self.collect_garbage();
+        outcome
+    }
+
+    fn zero(&self) -> Edge<T> {
+        Edge {
+            w: Complex::zero(),
+            n: TERMINAL,
+        }
+    }
+
+    /// Edge with weight `w`, or the zero edge, if the weight is negligible.
+    fn edge(
+        &self,
+        w: Complex<T>,
+        n: usize,
+    ) -> Edge<T> {
+        if w.norm() < self.tol {
+            self.zero()
+        } else {
+            Edge {
+                w,
+                n,
+            }
+        }
+    }
+
+    fn scale(
+        &self,
+        e: Edge<T>,
+        s: Complex<T>,
+    ) -> Edge<T> {
+        self.edge(e.w * s, e.n)
+    }
+
+    fn key(
+        &self,
+        w: Complex<T>,
+    ) -> WeightKey {
+        let round = |x: T| (x / self.tol).round().to_i64().unwrap_or(i64::MAX);
+        (round(w.re), round(w.im))
+    }
+
+    /// Find or insert a node with edges `e0` and `e1`, and return a weighted
+    /// edge pointing to it.
+    ///
+    /// Edges of the node are normalized by the weight of larger modulus,
+    /// which becomes exactly one.
+    fn make_node(
+        &mut self,
+        level: u16,
+        e0: Edge<T>,
+        e1: Edge<T>,
+    ) -> Edge<T> {
+        if e0.w.is_zero() && e1.w.is_zero() {
+            return self.zero();
+        }
+        let (norm, pivot) = if e0.w.norm() >= e1.w.norm() {
+            (e0.w, 0)
+        } else {
+            (e1.w, 1)
+        };
+        let mut e = [e0, e1].map(|e| self.scale(e, norm.inv()));
+        e[pivot].w = Complex::one();
+        let key = (level, e.map(|e| (self.key(e.w), e.n)));
+        let n = match self.unique.get(&key) {
+            Some(&n) => n,
+            None => {
+                self.nodes.push(Node {
+                    level,
+                    e,
+                });
+                self.unique.insert(key, self.nodes.len() - 1);
+                self.nodes.len() - 1
+            }
+        };
+        Edge {
+            w: norm,
+            n,
+        }
+    }
+
+    /// Add two edges pointing to nodes at the same level.
+    fn add(
+        &mut self,
+        a: Edge<T>,
+        b: Edge<T>,
+        memo: &mut AddTable<T>,
+    ) -> Edge<T> {
+        if a.w.is_zero() {
+            return b;
+        }
+        if b.w.is_zero() {
+            return a;
+        }
+        if a.n == b.n {
+            return self.edge(a.w + b.w, a.n);
+        }
+
+        // a + b = a.w (A + ratio B)
+        let ratio = b.w / a.w;
+        let key = (a.n, b.n, self.key(ratio));
+        if let Some(&r) = memo.get(&key) {
+            return self.scale(r, a.w);
+        }
+        let (na, nb) = (self.nodes[a.n], self.nodes[b.n]);
+        let [e0, e1] = [0, 1].map(|i| (na.e[i], self.scale(nb.e[i], ratio)));
+        let e0 = self.add(e0.0, e0.1, memo);
+        let e1 = self.add(e1.0, e1.1, memo);
+        let r = self.make_node(na.level, e0, e1);
+        memo.insert(key, r);
+        self.scale(r, a.w)
+    }
+
+    /// Apply a 2x2 matrix, not necessarily unitary, to qubit `t`.
+    ///
+    /// The edge must point to a node at level `t` or higher.
+    fn apply_one(
+        &mut self,
+        e: Edge<T>,
+        t: u16,
+        m: &Matrix2<T>,
+        memo: &mut HashMap<usize, Edge<T>>,
+        add: &mut AddTable<T>,
+    ) -> Edge<T> {
+        if e.w.is_zero() {
+            return e;
+        }
+        if let Some(&r) = memo.get(&e.n) {
+            return self.scale(r, e.w);
+        }
+        let node = self.nodes[e.n];
+        let r = if node.level > t {
+            let e0 = self.apply_one(node.e[0], t, m, memo, add);
+            let e1 = self.apply_one(node.e[1], t, m, memo, add);
+            self.make_node(node.level, e0, e1)
+        } else {
+            let [a, b] = node.e;
+            let mut out = [self.zero(); 2];
+            for (out, row) in out.iter_mut().zip(m) {
+                let x = self.scale(a, row[0]);
+                let y = self.scale(b, row[1]);
+                *out = self.add(x, y, add);
+            }
+            self.make_node(t, out[0], out[1])
+        };
+        memo.insert(e.n, r);
+        self.scale(r, e.w)
+    }
+
+    /// Apply a two-qubit operator, given as 2x2 blocks acting on the lower
+    /// qubit, to qubits `(hi, lo)`.
+    fn apply_two(
+        &mut self,
+        e: Edge<T>,
+        (hi, lo): (u16, u16),
+        blocks: &[[Matrix2<T>; 2]; 2],
+        memo: &mut HashMap<usize, Edge<T>>,
+        block_memos: &mut [[HashMap<usize, Edge<T>>; 2]; 2],
+        add: &mut AddTable<T>,
+    ) -> Edge<T> {
+        if e.w.is_zero() {
+            return e;
+        }
+        if let Some(&r) = memo.get(&e.n) {
+            return self.scale(r, e.w);
+        }
+        let node = self.nodes[e.n];
+        let r = if node.level > hi {
+            let e0 = self.apply_two(
+                node.e[0],
+                (hi, lo),
+                blocks,
+                memo,
+                block_memos,
+                add,
+            );
+            let e1 = self.apply_two(
+                node.e[1],
+                (hi, lo),
+                blocks,
+                memo,
+                block_memos,
+                add,
+            );
+            self.make_node(node.level, e0, e1)
+        } else {
+            let mut out = [self.zero(); 2];
+            for (r, out) in out.iter_mut().enumerate() {
+                for c in 0..2 {
+                    let block = &blocks[r][c];
+                    if block.iter().flatten().all(Complex::is_zero) {
+                        continue;
+                    }
+                    let x = self.apply_one(
+                        node.e[c],
+                        lo,
+                        block,
+                        &mut block_memos[r][c],
+                        add,
+                    );
+                    *out = self.add(*out, x, add);
+                }
+            }
+            self.make_node(hi, out[0], out[1])
+        };
+        memo.insert(e.n, r);
+        self.scale(r, e.w)
+    }
+
+    /// Squared norm of the subtree of node `n`.
+    fn norm_sqr(
+        &self,
+        n: usize,
+        memo: &mut HashMap<usize, T>,
+    ) -> T {
+        if n == TERMINAL {
+            return T::one();
+        }
+        if let Some(&x) = memo.get(&n) {
+            return x;
+        }
+        let node = &self.nodes[n];
+        let x = node
+            .e
+            .iter()
+            .filter(|e| !e.w.is_zero())
+            .map(|e| e.w.norm_sqr() * self.norm_sqr(e.n, memo))
+            .sum();
+        memo.insert(n, x);
+        x
+    }
+
+    /// Squared norm of the part of the subtree of node `n`, where qubit `k`
+    /// is in the state `ONE`.  The node must be at level `k` or higher.
+    fn mass_one(
+        &self,
+        n: usize,
+        k: u16,
+        memo: &mut HashMap<usize, T>,
+        norms: &mut HashMap<usize, T>,
+    ) -> T {
+        if let Some(&x) = memo.get(&n) {
+            return x;
+        }
+        let node = &self.nodes[n];
+        let x = if node.level == k {
+            let e = node.e[1];
+            if e.w.is_zero() {
+                T::zero()
+            } else {
+                e.w.norm_sqr() * self.norm_sqr(e.n, norms)
+            }
+        } else {
+            node.e
+                .iter()
+                .filter(|e| !e.w.is_zero())
+                .map(|e| e.w.norm_sqr() * self.mass_one(e.n, k, memo, norms))
+                .sum()
+        };
+        memo.insert(n, x);
+        x
+    }
+
+    /// Build a diagram of the amplitudes of `level` qubits.
+    fn build(
+        &mut self,
+        amp: &[Complex<T>],
+        level: u16,
+    ) -> Edge<T> {
+        if level == 0 {
+            return self.edge(amp[0], TERMINAL);
+        }
+        let (lo, hi) = amp.split_at(amp.len() / 2);
+        let e0 = self.build(lo, level - 1);
+        let e1 = self.build(hi, level - 1);
+        self.make_node(level - 1, e0, e1)
+    }
+
+    /// Write amplitudes of the subtree of `e`, multiplied by `acc`, to `amp`
+    /// at offset `base`.
+    fn fill(
+        &self,
+        e: Edge<T>,
+        acc: Complex<T>,
+        base: usize,
+        amp: &mut [Complex<T>],
+    ) {
+        if e.w.is_zero() {
+            return;
+        }
+        let acc = acc * e.w;
+        if e.n == TERMINAL {
+            amp[base] = acc;
+            return;
+        }
+        let node = &self.nodes[e.n];
+        for (bit, e) in node.e.iter().enumerate() {
+            self.fill(*e, acc, base | (bit << node.level), amp);
+        }
+    }
+
+    /// Remove nodes not reachable from the root, if the node table has grown
+    /// large enough.
+    fn collect_garbage(&mut self) {
+        if self.nodes.len() <= 2 * self.gc_size {
+            return;
+        }
+        let nodes = std::mem::take(&mut self.nodes);
+        self.nodes.push(nodes[TERMINAL]);
+        self.unique.clear();
+        let mut remap = HashMap::from([(TERMINAL, TERMINAL)]);
+        self.root.n = self.copy_node(&nodes, self.root.n, &mut remap);
+        self.gc_size = self.nodes.len().max(GC_MIN_NODES);
+    }
+
+    fn copy_node(
+        &mut self,
+        nodes: &[Node<T>],
+        n: usize,
+        remap: &mut HashMap<usize, usize>,
+    ) -> usize {
+        if let Some(&m) = remap.get(&n) {
+            return m;
+        }
+        let mut node = nodes[n];
+        for e in &mut node.e {
+            e.n = self.copy_node(nodes, e.n, remap);
+        }
+        let key = (node.level, node.e.map(|e| (self.key(e.w), e.n)));
+        self.nodes.push(node);
+        let m = self.nodes.len() - 1;
+        self.unique.insert(key, m);
+        remap.insert(n, m);
+        m
+    }
+}
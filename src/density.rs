@@ -0,0 +1,404 @@
+use std::num::NonZeroU16;
+
+use num::{
+    Complex,
+    Zero,
+};
+use rand::{
+    distributions::{
+        Bernoulli,
+        BernoulliError,
+        Distribution,
+    },
+    SeedableRng,
+};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::{
+    IndexedParallelIterator,
+    IntoParallelRefMutIterator,
+    ParallelIterator,
+};
+
+use crate::{
+    circuit,
+    gate::{
+        self,
+        Kernel,
+    },
+    lindblad,
+    BackendQubit,
+    Bit,
+    Channel,
+    Circuit,
+    Float,
+    Gate,
+    LindbladError,
+    Lindbladian,
+    NoiseModel,
+    System,
+};
+
+/// Quantum system of qubits in a mixed state.
+///
+/// The state is represented by its density matrix `rho`, stored in row-major
+/// order: the entry `rho[r][c]` has index `r * 2^n + c`, where `n` is the
+/// number of qubits.
+pub struct DensityMatrixSystem<T>
+where
+    T: Float,
+{
+    rng:        ChaCha8Rng,
+    num_qubits: NonZeroU16,
+    rho:        Vec<Complex<T>>,
+}
+
+impl<T> DensityMatrixSystem<T>
+where
+    T: Float,
+{
+    /// Initialize a new quantum system of `n` qubits in the zero state.
+    ///
+    /// Seed internal RNG with `seed`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::num::NonZeroU16;
+    /// # use qn::{Bit, DensityMatrixSystem};
+    /// let num_qubits = NonZeroU16::new(4).unwrap();
+    /// let seed = 123;
+    /// let mut stm: DensityMatrixSystem<f64> =
+    ///     DensityMatrixSystem::new(num_qubits, seed);
+    ///
+    /// for mut qubit in stm.qubit_iter() {
+    ///     assert_eq!(qubit.measure(), Bit::ZERO);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn new(
+        num_qubits: NonZeroU16,
+        seed: u64,
+    ) -> Self {
+        let mut rho = vec![Complex::zero(); 1usize << (2 * num_qubits.get())];
+        rho[0] = Complex::from(T::one());
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            num_qubits,
+            rho,
+        }
+    }
+
+    /// Draw from Bernoulli distribution with probability of success `p`.
+    ///
+    /// Uses internal RNG.
+    pub(crate) fn bernoulli(
+        &mut self,
+        p: f64,
+    ) -> Result<bool, BernoulliError> {
+        Ok(Bernoulli::new(p)?.sample(&mut self.rng))
+    }
+
+    /// Get mutable access to the internal RNG.
+    pub(crate) fn rng_mut(&mut self) -> &mut ChaCha8Rng {
+        &mut self.rng
+    }
+
+    /// Get the number of qubits.
+    #[must_use]
+    pub fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits
+    }
+
+    /// Get entries of the density matrix in row-major order.
+    #[must_use]
+    pub fn as_slice(&self) -> &[Complex<T>] {
+        &self.rho
+    }
+
+    /// Get mutable access to entries of the density matrix.
+    pub fn as_mut_slice(&mut self) -> &mut [Complex<T>] {
+        &mut self.rho
+    }
+
+    /// Trace of the density matrix.
+    #[must_use]
+    pub fn trace(&self) -> T {
+        let dim = 1usize << self.num_qubits.get();
+        (0..dim).map(|i| self.rho[i * dim + i].re).sum()
+    }
+
+    /// Purity of the state: `Tr(rho^2)`.
+    ///
+    /// Equals one for pure states.
+    #[must_use]
+    pub fn purity(&self) -> T {
+        // For a Hermitian matrix: Tr(rho^2) = sum |rho_ij|^2
+        self.rho.iter().map(Complex::norm_sqr).sum()
+    }
+
+    /// Reset all qubits to the zero state.
+    ///
+    /// The RNG is not affected.
+    pub fn reset(&mut self) {
+        self.rho.par_iter_mut().for_each(|a| *a = Complex::zero());
+        self.rho[0] = Complex::from(T::one());
+    }
+
+    /// Apply a gate to the system: `rho -> U rho U^dagger`.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the gate is not valid for this system, see
+    /// [`Gate::is_valid()`].
+    pub fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        assert!(
+            gate.is_valid(self.num_qubits.get()),
+            "invalid gate: {gate:?}"
+        );
+        conjugate(&mut self.rho, self.num_qubits.get(), &gate.kernel());
+    }
+
+    /// Apply a quantum channel to `qubits`: `rho -> sum_k K_k rho
+    /// K_k^dagger`.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the channel cannot be applied to `qubits`, see
+    /// [`Channel::is_valid()`].
+    pub fn apply_channel(
+        &mut self,
+        channel: &Channel<T>,
+        qubits: &[u16],
+    ) {
+        let n = self.num_qubits.get();
+        assert!(
+            channel.is_valid(qubits, n),
+            "invalid channel qubits: {qubits:?}"
+        );
+
+        let kernels = channel.kernels(qubits);
+        let mut acc = vec![Complex::zero(); self.rho.len()];
+        for kernel in &kernels {
+            let mut rho = self.rho.clone();
+            conjugate(&mut rho, n, kernel);
+            acc.par_iter_mut().zip(&rho).for_each(|(a, x)| *a += x);
+        }
+        self.rho = acc;
+    }
+
+    /// Evolve the system for the time `time` according to the Lindblad
+    /// master equation.
+    ///
+    /// The equation is integrated with an adaptive Runge–Kutta method
+    /// (Dormand–Prince 5(4)).  The step size is chosen so that the local
+    /// error estimate of each entry of the density matrix stays below
+    /// `tolerance`.
+    ///
+    /// Returns the number of integration steps.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error
+    /// - if `time` is negative or `tolerance` is not positive
+    /// - if the integrator cannot reach the requested tolerance
+    ///
+    /// In the latter case, the system is left in the state reached so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the Lindbladian acts on qubits outside of the system.
+    pub fn evolve(
+        &mut self,
+        lindbladian: &Lindbladian<T>,
+        time: T,
+        tolerance: T,
+    ) -> Result<usize, LindbladError> {
+        let n = self.num_qubits.get();
+        assert!(
+            lindbladian.num_qubits() <= n,
+            "Lindbladian does not fit in the system"
+        );
+        lindblad::integrate(lindbladian, &mut self.rho, n, time, tolerance)
+    }
+
+    /// Probability of measuring qubit `index` in the state `ONE`.
+    ///
+    /// Returns `None`, if index is larger or equal than `self.num_qubits()`
+    #[must_use]
+    pub fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T> {
+        if index >= self.num_qubits.get() {
+            return None;
+        }
+        let dim = 1usize << self.num_qubits.get();
+        let mask = 1usize << index;
+        Some(
+            (0..dim)
+                .filter(|i| i & mask == mask)
+                .map(|i| self.rho[i * dim + i].re)
+                .sum(),
+        )
+    }
+
+    /// Measure qubit `index` and project the state onto the outcome.
+    pub(crate) fn measure(
+        &mut self,
+        index: u16,
+    ) -> Bit {
+        let n = self.num_qubits.get();
+        let amp_sq_1 = self.probability(index).unwrap();
+
+        let p = T::to_f64(&amp_sq_1).unwrap().clamp(0., 1.);
+        let outcome = self.bernoulli(p).unwrap();
+
+        // zero the entries outside of the outcome block, normalize the rest
+        let norm_factor = if outcome {
+            amp_sq_1
+        } else {
+            T::one() - amp_sq_1
+        };
+        let mask = (1usize << index) | (1usize << (index + n));
+        let outcome_shifted = if outcome { mask } else { 0 };
+        self.rho.par_iter_mut().enumerate().for_each(|(i, a)| {
+            if i & mask == outcome_shifted {
+                *a /= norm_factor;
+            } else {
+                *a = Complex::zero();
+            }
+        });
+        outcome.into()
+    }
+
+    /// Execute a circuit on the system.
+    ///
+    /// Returns reported outcomes of measurements in the order they appear in
+    /// the circuit.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the circuit has more qubits than the system.
+    pub fn run(
+        &mut self,
+        circuit: &Circuit<T>,
+    ) -> Vec<Bit> {
+        circuit.run(self)
+    }
+
+    /// Execute a circuit on the system, subject to noise.
+    ///
+    /// See [`NoiseModel`] for how noise is applied.
+    ///
+    /// Returns reported outcomes of measurements in the order they appear in
+    /// the circuit.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the circuit has more qubits than the system.
+    pub fn run_noisy(
+        &mut self,
+        circuit: &Circuit<T>,
+        noise: &NoiseModel<T>,
+    ) -> Vec<Bit> {
+        circuit::execute_noisy(circuit, self, noise)
+    }
+
+    /// Get a qubit.
+    ///
+    /// Returns `None`, if index is larger or equal than `self.num_qubits()`
+    pub fn qubit(
+        &mut self,
+        index: u16,
+    ) -> Option<DensityMatrixQubit<'_, T>> {
+        BackendQubit::new(self, index)
+    }
+
+    /// Get a pair of qubits.
+    ///
+    /// # Result
+    ///
+    /// Returns `None`
+    /// - if any of indices is larger or equal than `self.num_qubits()`
+    /// - if indices are equal
+    pub fn qubit_pair(
+        &mut self,
+        index1: u16,
+        index2: u16,
+    ) -> Option<(DensityMatrixQubit<'_, T>, DensityMatrixQubit<'_, T>)> {
+        BackendQubit::new_pair(self, index1, index2)
+    }
+
+    /// Create an iterator over all qubits in system.
+    pub fn qubit_iter(
+        &mut self
+    ) -> impl Iterator<Item = DensityMatrixQubit<'_, T>> {
+        BackendQubit::new_iter(self)
+    }
+}
+
+/// Apply a single- or two-qubit operator `K` to the density matrix `rho` of
+/// `n` qubits as `rho -> K rho K^dagger`.
+pub(crate) fn conjugate<T>(
+    rho: &mut [Complex<T>],
+    n: u16,
+    kernel: &Kernel<T>,
+) where
+    T: Float,
+{
+    // Rows correspond to bits n..2n, columns to bits 0..n.
+    match kernel {
+        Kernel::One(k, m) => {
+            gate::apply_one(rho, k + n, m);
+            gate::apply_one(rho, *k, &gate::conj2(m));
+        }
+        Kernel::Two(i, j, m) => {
+            gate::apply_two(rho, i + n, j + n, m);
+            gate::apply_two(rho, *i, *j, &gate::conj4(m));
+        }
+    }
+}
+
+impl<T> From<&System<T>> for DensityMatrixSystem<T>
+where
+    T: Float,
+{
+    /// Density matrix `|psi><psi|` of a pure state.
+    ///
+    /// The internal RNG is cloned from the pure system.
+    fn from(stm: &System<T>) -> Self {
+        let amp = stm.as_slice();
+        let rho = amp
+            .iter()
+            .flat_map(|a| amp.iter().map(move |b| a * b.conj()))
+            .collect();
+        Self {
+            rng: stm.rng().clone(),
+            num_qubits: stm.num_qubits(),
+            rho,
+        }
+    }
+}
+
+/// A representation of a qubit in a quantum system in a mixed state.
+///
+/// See [`BackendQubit`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Bit, DensityMatrixSystem, Gate};
+/// let num_qubits = NonZeroU16::new(2).unwrap();
+/// let mut stm: DensityMatrixSystem<f64> =
+///     DensityMatrixSystem::new(num_qubits, 123);
+/// stm.apply(&Gate::X(1));
+/// let mut qubit = stm.qubit(1).unwrap();
+///
+/// assert_eq!(qubit.measure(), Bit::ONE);
+/// ```
+pub type DensityMatrixQubit<'a, T> =
+    BackendQubit<'a, T, DensityMatrixSystem<T>>;
@@ -0,0 +1,330 @@
+//! Entanglement measures of bipartite quantum systems.
+//!
+//! A bipartition of a system is specified by a list of qubit indices forming
+//! subsystem A.  The remaining qubits form subsystem B.  Within a subsystem,
+//! the k-th listed qubit corresponds to the k-th bit of the subsystem's
+//! computational basis index.
+//!
+//! All entropies are computed in bits, i.e. with logarithm to base 2.
+
+use num::{
+    Complex,
+    Zero,
+};
+
+use crate::{
+    linalg,
+    Float,
+    System,
+};
+
+/// Schmidt decomposition of a pure state with respect to a bipartition.
+///
+/// The state is `sum_k coefficients[k] * left[k] (x) right[k]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchmidtDecomposition<T> {
+    /// Schmidt coefficients in non-increasing order.
+    pub coefficients: Vec<T>,
+    /// Orthonormal vectors of subsystem A.
+    pub left:         Vec<Vec<Complex<T>>>,
+    /// Orthonormal vectors of subsystem B (remaining qubits in increasing
+    /// order of their indices).
+    pub right:        Vec<Vec<Complex<T>>>,
+}
+
+/// Split computational basis index `i` into indices of subsystems `part` and
+/// `rest`.
+fn split_index(
+    i: usize,
+    part: &[u16],
+    rest: &[u16],
+) -> (usize, usize) {
+    let gather = |qubits: &[u16]| {
+        qubits
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (k, &q)| acc | ((i >> q) & 1) << k)
+    };
+    (gather(part), gather(rest))
+}
+
+/// Check that qubit indices are distinct and within system, and return
+/// indices of the complement of `part`.
+fn complement(
+    num_qubits: u16,
+    part: &[u16],
+) -> Option<Vec<u16>> {
+    for (k, &q) in part.iter().enumerate() {
+        if q >= num_qubits || part[..k].contains(&q) {
+            return None;
+        }
+    }
+    Some((0..num_qubits).filter(|q| !part.contains(q)).collect())
+}
+
+/// Reshape amplitudes of `stm` into a `2^|part| x 2^|rest|` matrix.
+fn reshape<T>(
+    stm: &System<T>,
+    part: &[u16],
+    rest: &[u16],
+) -> Vec<Complex<T>>
+where
+    T: Float,
+{
+    let cols = 1usize << rest.len();
+    let mut mat = vec![Complex::zero(); stm.as_slice().len()];
+    for (i, &a) in stm.as_slice().iter().enumerate() {
+        let (r, c) = split_index(i, part, rest);
+        mat[r * cols + c] = a;
+    }
+    mat
+}
+
+/// Reduced density matrix of the subsystem `qubits`.
+///
+/// Returns a `2^k x 2^k` matrix in row-major order, where `k =
+/// qubits.len()`.
+///
+/// Returns `None`, if any index is larger or equal than `stm.num_qubits()`,
+/// or if indices are not distinct.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use num::Complex;
+/// # use qn::{entanglement::reduced_density_matrix, System};
+/// let num_qubits = NonZeroU16::new(2).unwrap();
+/// let stm: System<f64> = System::new(num_qubits, 123);
+/// let rho = reduced_density_matrix(&stm, &[1]).unwrap();
+///
+/// assert_eq!(rho.len(), 4);
+/// assert_eq!(rho[0], Complex::from(1.));
+/// assert_eq!(rho[3], Complex::from(0.));
+/// ```
+#[must_use]
+pub fn reduced_density_matrix<T>(
+    stm: &System<T>,
+    qubits: &[u16],
+) -> Option<Vec<Complex<T>>>
+where
+    T: Float,
+{
+    let rest = complement(stm.num_qubits().get(), qubits)?;
+    let (rows, cols) = (1usize << qubits.len(), 1usize << rest.len());
+    let mat = reshape(stm, qubits, &rest);
+    let adj = linalg::adjoint(&mat, rows, cols);
+    Some(linalg::matmul(&mat, &adj, rows, cols, rows))
+}
+
+/// Schmidt decomposition of the state of `stm` with respect to the
+/// bipartition (`part`, rest of the system).
+///
+/// Computed via the singular value decomposition of the amplitudes reshaped
+/// into a matrix.
+///
+/// Returns `None`, if any index is larger or equal than `stm.num_qubits()`,
+/// or if indices are not distinct.
+#[must_use]
+pub fn schmidt_decomposition<T>(
+    stm: &System<T>,
+    part: &[u16],
+) -> Option<SchmidtDecomposition<T>>
+where
+    T: Float,
+{
+    let rest = complement(stm.num_qubits().get(), part)?;
+    let (rows, cols) = (1usize << part.len(), 1usize << rest.len());
+    let svd = linalg::svd(&reshape(stm, part, &rest), rows, cols);
+    let k = svd.s.len();
+    let column = |mat: &[Complex<T>], len: usize, j: usize| {
+        (0..len).map(|i| mat[i * k + j]).collect()
+    };
+    Some(SchmidtDecomposition {
+        left:         (0..k).map(|j| column(&svd.u, rows, j)).collect(),
+        right:        (0..k)
+            .map(|j| {
+                column(&svd.v, cols, j)
+                    .into_iter()
+                    .map(|z: Complex<T>| z.conj())
+                    .collect()
+            })
+            .collect(),
+        coefficients: svd.s,
+    })
+}
+
+/// Schmidt coefficients of the state of `stm` with respect to the
+/// bipartition (`part`, rest of the system), in non-increasing order.
+///
+/// Returns `None`, if any index is larger or equal than `stm.num_qubits()`,
+/// or if indices are not distinct.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{entanglement::schmidt_coefficients, System};
+/// let num_qubits = NonZeroU16::new(3).unwrap();
+/// let stm: System<f64> = System::new(num_qubits, 123);
+/// let coeffs = schmidt_coefficients(&stm, &[0, 2]).unwrap();
+///
+/// assert_eq!(coeffs.len(), 2);
+/// assert!((coeffs[0] - 1.).abs() < 1e-12);
+/// assert!(coeffs[1].abs() < 1e-12);
+/// ```
+#[must_use]
+pub fn schmidt_coefficients<T>(
+    stm: &System<T>,
+    part: &[u16],
+) -> Option<Vec<T>>
+where
+    T: Float,
+{
+    let rest = complement(stm.num_qubits().get(), part)?;
+    let (rows, cols) = (1usize << part.len(), 1usize << rest.len());
+    Some(linalg::svd(&reshape(stm, part, &rest), rows, cols).s)
+}
+
+/// Von Neumann entanglement entropy of the bipartition (`part`, rest of the
+/// system).
+///
+/// Returns `None`, if any index is larger or equal than `stm.num_qubits()`,
+/// or if indices are not distinct.
+#[must_use]
+pub fn entropy_von_neumann<T>(
+    stm: &System<T>,
+    part: &[u16],
+) -> Option<T>
+where
+    T: Float,
+{
+    Some(
+        schmidt_coefficients(stm, part)?
+            .into_iter()
+            .map(|s| s * s)
+            .filter(|&p| p > T::zero())
+            .map(|p| -p * p.log2())
+            .sum(),
+    )
+}
+
+/// Rényi entanglement entropy of order `alpha` of the bipartition (`part`,
+/// rest of the system).
+///
+/// For `alpha == 1` this is the von Neumann entropy.
+///
+/// Returns `None`, if any index is larger or equal than `stm.num_qubits()`,
+/// if indices are not distinct, or if `alpha` is negative.
+#[must_use]
+pub fn entropy_renyi<T>(
+    stm: &System<T>,
+    part: &[u16],
+    alpha: T,
+) -> Option<T>
+where
+    T: Float,
+{
+    if alpha < T::zero() {
+        return None;
+    }
+    if alpha == T::one() {
+        return entropy_von_neumann(stm, part);
+    }
+    let sum = schmidt_coefficients(stm, part)?
+        .into_iter()
+        .map(|s| s * s)
+        .filter(|&p| p > T::zero())
+        .map(|p| p.powf(alpha))
+        .sum::<T>();
+    Some(sum.log2() / (T::one() - alpha))
+}
+
+/// Concurrence of the reduced state of a pair of qubits.
+///
+/// Uses Wootters' formula `C = max(0, l1 - l2 - l3 - l4)`, where `l1`,
+/// ..., `l4` are the square roots of the eigenvalues of `rho * rho_tilde` in
+/// non-increasing order.
+///
+/// Returns `None`
+/// - if any of indices is larger or equal than `stm.num_qubits()`
+/// - if indices are equal
+#[must_use]
+pub fn concurrence<T>(
+    stm: &System<T>,
+    index1: u16,
+    index2: u16,
+) -> Option<T>
+where
+    T: Float,
+{
+    let rho = reduced_density_matrix(stm, &[index1, index2])?;
+
+    // rho_tilde = (Y (x) Y) rho^* (Y (x) Y)
+    let sign = |i: usize| {
+        if i == 0 || i == 3 {
+            -T::one()
+        } else {
+            T::one()
+        }
+    };
+    let mut rho_tilde = vec![Complex::zero(); 16];
+    for i in 0..4 {
+        for j in 0..4 {
+            rho_tilde[i * 4 + j] =
+                rho[(3 - i) * 4 + (3 - j)].conj() * sign(i) * sign(j);
+        }
+    }
+
+    let sqrt_rho = linalg::hermitian_map(&rho, 4, |x| x.max(T::zero()).sqrt());
+    let prod = linalg::matmul(
+        &linalg::matmul(&sqrt_rho, &rho_tilde, 4, 4, 4),
+        &sqrt_rho,
+        4,
+        4,
+        4,
+    );
+    let (values, _) = linalg::eigh(&prod, 4);
+    let l: Vec<T> = values
+        .into_iter()
+        .rev()
+        .map(|x| x.max(T::zero()).sqrt())
+        .collect();
+    Some((l[0] - l[1] - l[2] - l[3]).max(T::zero()))
+}
+
+/// Logarithmic negativity between subsystems `part_a` and `part_b`.
+///
+/// Computed from the reduced state of `part_a` and `part_b` as `log2 ||
+/// rho^{T_B} ||_1`, where `T_B` is the partial transpose with respect to
+/// `part_b`.
+///
+/// Returns `None`, if any index is larger or equal than `stm.num_qubits()`,
+/// or if indices are not distinct.
+#[must_use]
+pub fn log_negativity<T>(
+    stm: &System<T>,
+    part_a: &[u16],
+    part_b: &[u16],
+) -> Option<T>
+where
+    T: Float,
+{
+    let qubits: Vec<_> = part_a.iter().chain(part_b).copied().collect();
+    let rho = reduced_density_matrix(stm, &qubits)?;
+
+    let dim_a = 1usize << part_a.len();
+    let dim = 1usize << qubits.len();
+    let mut rho_pt = vec![Complex::zero(); dim * dim];
+    for i in 0..dim {
+        for j in 0..dim {
+            let (ia, ib) = (i % dim_a, i / dim_a);
+            let (ja, jb) = (j % dim_a, j / dim_a);
+            rho_pt[i * dim + j] =
+                rho[(ia + jb * dim_a) * dim + ja + ib * dim_a];
+        }
+    }
+
+    let (values, _) = linalg::eigh(&rho_pt, dim);
+    Some(values.into_iter().map(T::abs).sum::<T>().log2())
+}
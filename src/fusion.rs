@@ -0,0 +1,234 @@
+use num::{
+    Complex,
+    One,
+    Zero,
+};
+
+use crate::{
+    gate::Kernel,
+    Circuit,
+    Float,
+    Gate,
+    Operation,
+};
+
+/// Gates fused into a dense unitary, or a measurement.
+enum Block<T> {
+    Gates {
+        /// Qubits the block acts on.  Bit `j` of the matrix index is the
+        /// value of `qubits[j]`.
+        qubits: Vec<u16>,
+        matrix: Vec<Complex<T>>,
+        gates:  Vec<Gate<T>>,
+    },
+    Measure(u16),
+}
+
+/// Fuse gates of a circuit into blocks acting on at most `max_qubits`
+/// qubits, see [`Circuit::optimize()`].
+pub(crate) fn optimize<T>(
+    circuit: &Circuit<T>,
+    max_qubits: u16,
+) -> Circuit<T>
+where
+    T: Float,
+{
+    let tol = T::epsilon() * T::from(64).unwrap();
+    let mut blocks: Vec<Block<T>> = Vec::new();
+
+    for op in circuit.operations() {
+        let gate = match op {
+            Operation::Gate(gate) => gate,
+            Operation::Measure(index) => {
+                blocks.push(Block::Measure(*index));
+                continue;
+            }
+        };
+        let (qubits, matrix): (_, Vec<_>) = match gate.kernel() {
+            Kernel::One(q, m) => {
+                (vec![q], m.iter().flatten().copied().collect())
+            }
+            Kernel::Two(q0, q1, m) => {
+                (vec![q0, q1], m.iter().flatten().copied().collect())
+            }
+        };
+        let diagonal = is_diagonal(&matrix, tol);
+
+        // Find a block to merge with, moving the gate past blocks that act
+        // on other qubits, or that commute with it because both are
+        // diagonal.
+        let mut target = None;
+        for (i, block) in blocks.iter().enumerate().rev() {
+            match block {
+                Block::Measure(q) if qubits.contains(q) => break,
+                Block::Measure(_) => continue,
+                Block::Gates {
+                    qubits: bq,
+                    matrix: bm,
+                    ..
+                } => {
+                    if !bq.iter().any(|q| qubits.contains(q)) {
+                        continue;
+                    }
+                    let same = bq.len() == qubits.len()
+                        && bq.iter().all(|q| qubits.contains(q));
+                    let union = bq.len()
+                        + qubits.iter().filter(|q| !bq.contains(q)).count();
+                    if same || union <= usize::from(max_qubits) {
+                        target = Some(i);
+                        break;
+                    }
+                    if diagonal && is_diagonal(bm, tol) {
+                        continue;
+                    }
+                    break;
+                }
+            }
+        }
+
+        let Some(i) = target else {
+            blocks.push(Block::Gates {
+                qubits,
+                matrix,
+                gates: vec![*gate],
+            });
+            continue;
+        };
+        let Block::Gates {
+            qubits: bq,
+            matrix: bm,
+            gates,
+        } = &mut blocks[i]
+        else {
+            unreachable!("target is a block of gates");
+        };
+        let mut union = bq.clone();
+        union.extend(qubits.iter().filter(|q| !bq.contains(q)));
+        let product =
+            matmul(&embed(&matrix, &qubits, &union), &embed(bm, bq, &union));
+        if is_identity(&product, tol) {
+            blocks.remove(i);
+        } else {
+            *bq = union;
+            *bm = product;
+            gates.push(*gate);
+        }
+    }
+
+    let mut optimized = Circuit::new(circuit.num_qubits());
+    for block in blocks {
+        match block {
+            Block::Measure(index) => optimized.measure(index),
+            Block::Gates {
+                gates, ..
+            } if gates.len() == 1 => optimized.apply(gates[0]),
+            Block::Gates {
+                qubits,
+                matrix,
+                ..
+            } => optimized.apply(match qubits[..] {
+                [q] => {
+                    Gate::U(q, [[matrix[0], matrix[1]], [matrix[2], matrix[3]]])
+                }
+                [q0, q1] => Gate::U2(
+                    q0,
+                    q1,
+                    [0, 1, 2, 3]
+                        .map(|r| [0, 1, 2, 3].map(|c| matrix[r * 4 + c])),
+                ),
+                _ => unreachable!("blocks act on at most two qubits"),
+            }),
+        }
+    }
+    optimized
+}
+
+/// Extend matrix `m` acting on qubits `from` to qubits `to`, a superset of
+/// `from`, with identity.
+fn embed<T>(
+    m: &[Complex<T>],
+    from: &[u16],
+    to: &[u16],
+) -> Vec<Complex<T>>
+where
+    T: Float,
+{
+    let dim = 1usize << to.len();
+    let pos: Vec<usize> = from
+        .iter()
+        .map(|q| to.iter().position(|p| p == q).expect("qubit in target"))
+        .collect();
+    let rest = (0..to.len())
+        .filter(|j| !pos.contains(j))
+        .fold(0, |acc, j| acc | (1 << j));
+    let sub = |i: usize| {
+        pos.iter()
+            .enumerate()
+            .fold(0, |acc, (j, &p)| acc | (((i >> p) & 1) << j))
+    };
+    let from_dim = 1usize << from.len();
+
+    let mut out = vec![Complex::zero(); dim * dim];
+    for r in 0..dim {
+        for c in 0..dim {
+            if r & rest == c & rest {
+                out[r * dim + c] = m[sub(r) * from_dim + sub(c)];
+            }
+        }
+    }
+    out
+}
+
+fn matmul<T>(
+    a: &[Complex<T>],
+    b: &[Complex<T>],
+) -> Vec<Complex<T>>
+where
+    T: Float,
+{
+    let dim = (1..).find(|d| d * d == a.len()).expect("square matrix");
+    let mut out = vec![Complex::zero(); dim * dim];
+    for r in 0..dim {
+        for k in 0..dim {
+            let x = a[r * dim + k];
+            if x.is_zero() {
+                continue;
+            }
+            for c in 0..dim {
+                out[r * dim + c] += x * b[k * dim + c];
+            }
+        }
+    }
+    out
+}
+
+fn is_diagonal<T>(
+    m: &[Complex<T>],
+    tol: T,
+) -> bool
+where
+    T: Float,
+{
+    let dim = (1..).find(|d| d * d == m.len()).expect("square matrix");
+    m.iter()
+        .enumerate()
+        .all(|(i, x)| i / dim == i % dim || x.norm() <= tol)
+}
+
+fn is_identity<T>(
+    m: &[Complex<T>],
+    tol: T,
+) -> bool
+where
+    T: Float,
+{
+    let dim = (1..).find(|d| d * d == m.len()).expect("square matrix");
+    m.iter().enumerate().all(|(i, x)| {
+        let expected = if i / dim == i % dim {
+            Complex::one()
+        } else {
+            Complex::zero()
+        };
+        (x - expected).norm() <= tol
+    })
+}
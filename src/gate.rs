@@ -0,0 +1,451 @@
+use std::ops::Range;
+
+use num::{
+    Complex,
+    One,
+    Zero,
+};
+use rayon::{
+    prelude::ParallelIterator,
+    slice::ParallelSliceMut,
+};
+
+use crate::Float;
+
+/// Matrix of a single-qubit operator, in row-major order.
+pub type Matrix2<T> = [[Complex<T>; 2]; 2];
+
+/// Matrix of a two-qubit operator, in row-major order.
+///
+/// For an operator acting on qubits `(q0, q1)`, the row and column index is
+/// `b0 + 2 * b1`, where `b0`, `b1` are values of the bits `q0`, `q1`.
+pub type Matrix4<T> = [[Complex<T>; 4]; 4];
+
+/// Quantum gate acting on one or two qubits of a system.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Bit, Gate, System};
+/// let num_qubits = NonZeroU16::new(2).unwrap();
+/// let mut stm: System<f64> = System::new(num_qubits, 123);
+/// stm.apply(&Gate::X(0));
+/// stm.apply(&Gate::CNOT(0, 1));
+///
+/// let (mut qb0, mut qb1) = stm.qubit_pair(0, 1).unwrap();
+/// assert_eq!(qb0.measure(), Bit::ONE);
+/// assert_eq!(qb1.measure(), Bit::ONE);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gate<T> {
+    /// Hadamard gate.
+    H(u16),
+    /// Pauli X gate.
+    X(u16),
+    /// Pauli Y gate.
+    Y(u16),
+    /// Pauli Z gate.
+    Z(u16),
+    /// Phase gate: `diag(1, i)`.
+    S(u16),
+    /// Phase shift by an angle: `diag(1, e^{i phi})`.
+    Phase(u16, T),
+    /// Rotation about X axis: `e^{-i theta X / 2}`.
+    Rx(u16, T),
+    /// Rotation about Y axis: `e^{-i theta Y / 2}`.
+    Ry(u16, T),
+    /// Rotation about Z axis: `e^{-i theta Z / 2}`.
+    Rz(u16, T),
+    /// Arbitrary single-qubit unitary.
+    U(u16, Matrix2<T>),
+    /// Controlled NOT gate: `CNOT(control, target)`.
+    CNOT(u16, u16),
+    /// Controlled Z gate.
+    CZ(u16, u16),
+    /// Swap two qubits.
+    SWAP(u16, u16),
+    /// Arbitrary two-qubit unitary.
+    U2(u16, u16, Matrix4<T>),
+}
+
+/// Kind of a gate, regardless of qubits it acts on and its parameters.
+///
+/// See [`Gate`] for the description of each kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GateKind {
+    H,
+    X,
+    Y,
+    Z,
+    S,
+    Phase,
+    Rx,
+    Ry,
+    Rz,
+    U,
+    CNOT,
+    CZ,
+    SWAP,
+    U2,
+}
+
+impl GateKind {
+    /// Number of qubits a gate of this kind acts on.
+    #[must_use]
+    pub fn num_qubits(&self) -> u16 {
+        match self {
+            Self::CNOT | Self::CZ | Self::SWAP | Self::U2 => 2,
+            _ => 1,
+        }
+    }
+
+    /// Check if gates of this kind are Clifford gates for any value of
+    /// their parameters.
+    #[must_use]
+    pub fn is_clifford(&self) -> bool {
+        matches!(
+            self,
+            Self::H
+                | Self::X
+                | Self::Y
+                | Self::Z
+                | Self::S
+                | Self::CNOT
+                | Self::CZ
+                | Self::SWAP
+        )
+    }
+}
+
+/// Matrix representation of a gate together with qubits it acts on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kernel<T> {
+    One(u16, Matrix2<T>),
+    Two(u16, u16, Matrix4<T>),
+}
+
+impl<T> Gate<T>
+where
+    T: Float,
+{
+    /// Indices of qubits the gate acts on.
+    #[must_use]
+    pub fn qubits(&self) -> Vec<u16> {
+        match *self {
+            Self::H(i)
+            | Self::X(i)
+            | Self::Y(i)
+            | Self::Z(i)
+            | Self::S(i)
+            | Self::Phase(i, _)
+            | Self::Rx(i, _)
+            | Self::Ry(i, _)
+            | Self::Rz(i, _)
+            | Self::U(i, _) => vec![i],
+            Self::CNOT(i, j)
+            | Self::CZ(i, j)
+            | Self::SWAP(i, j)
+            | Self::U2(i, j, _) => vec![i, j],
+        }
+    }
+
+    /// Kind of the gate.
+    #[must_use]
+    pub fn kind(&self) -> GateKind {
+        match self {
+            Self::H(_) => GateKind::H,
+            Self::X(_) => GateKind::X,
+            Self::Y(_) => GateKind::Y,
+            Self::Z(_) => GateKind::Z,
+            Self::S(_) => GateKind::S,
+            Self::Phase(..) => GateKind::Phase,
+            Self::Rx(..) => GateKind::Rx,
+            Self::Ry(..) => GateKind::Ry,
+            Self::Rz(..) => GateKind::Rz,
+            Self::U(..) => GateKind::U,
+            Self::CNOT(..) => GateKind::CNOT,
+            Self::CZ(..) => GateKind::CZ,
+            Self::SWAP(..) => GateKind::SWAP,
+            Self::U2(..) => GateKind::U2,
+        }
+    }
+
+    /// Check if the gate can be applied to a system of `num_qubits` qubits.
+    ///
+    /// Returns `false`, if any qubit index is larger or equal than
+    /// `num_qubits`, or if indices of a two-qubit gate are equal.
+    #[must_use]
+    pub fn is_valid(
+        &self,
+        num_qubits: u16,
+    ) -> bool {
+        let qubits = self.qubits();
+        qubits.iter().all(|&i| i < num_qubits)
+            && (qubits.len() < 2 || qubits[0] != qubits[1])
+    }
+
+    /// Matrix of the gate.
+    pub(crate) fn kernel(&self) -> Kernel<T> {
+        let zero = Complex::zero();
+        let one = Complex::one();
+        let i = Complex::i();
+        let half = |x: T| x / (T::one() + T::one());
+
+        match *self {
+            Self::H(k) => {
+                let h = Complex::from((T::one() + T::one()).sqrt().recip());
+                Kernel::One(k, [[h, h], [h, -h]])
+            }
+            Self::X(k) => Kernel::One(k, [[zero, one], [one, zero]]),
+            Self::Y(k) => Kernel::One(k, [[zero, -i], [i, zero]]),
+            Self::Z(k) => Kernel::One(k, [[one, zero], [zero, -one]]),
+            Self::S(k) => Kernel::One(k, [[one, zero], [zero, i]]),
+            Self::Phase(k, phi) => Kernel::One(
+                k,
+                [[one, zero], [zero, Complex::from_polar(T::one(), phi)]],
+            ),
+            Self::Rx(k, theta) => {
+                let (s, c) = half(theta).sin_cos();
+                let c = Complex::from(c);
+                let s = Complex::new(T::zero(), -s);
+                Kernel::One(k, [[c, s], [s, c]])
+            }
+            Self::Ry(k, theta) => {
+                let (s, c) = half(theta).sin_cos();
+                let c = Complex::from(c);
+                let s = Complex::from(s);
+                Kernel::One(k, [[c, -s], [s, c]])
+            }
+            Self::Rz(k, theta) => {
+                let e = Complex::from_polar(T::one(), half(theta));
+                Kernel::One(k, [[e.conj(), zero], [zero, e]])
+            }
+            Self::U(k, m) => Kernel::One(k, m),
+            Self::CNOT(c, t) => Kernel::Two(
+                c,
+                t,
+                [
+                    [one, zero, zero, zero],
+                    [zero, zero, zero, one],
+                    [zero, zero, one, zero],
+                    [zero, one, zero, zero],
+                ],
+            ),
+            Self::CZ(c, t) => Kernel::Two(
+                c,
+                t,
+                [
+                    [one, zero, zero, zero],
+                    [zero, one, zero, zero],
+                    [zero, zero, one, zero],
+                    [zero, zero, zero, -one],
+                ],
+            ),
+            Self::SWAP(i, j) => Kernel::Two(
+                i,
+                j,
+                [
+                    [one, zero, zero, zero],
+                    [zero, zero, one, zero],
+                    [zero, one, zero, zero],
+                    [zero, zero, zero, one],
+                ],
+            ),
+            Self::U2(i, j, m) => Kernel::Two(i, j, m),
+        }
+    }
+}
+
+/// Complex conjugate of a matrix (not transposed).
+pub(crate) fn conj2<T>(m: &Matrix2<T>) -> Matrix2<T>
+where
+    T: Float,
+{
+    m.map(|row| row.map(|z| z.conj()))
+}
+
+/// Complex conjugate of a matrix (not transposed).
+pub(crate) fn conj4<T>(m: &Matrix4<T>) -> Matrix4<T>
+where
+    T: Float,
+{
+    m.map(|row| row.map(|z| z.conj()))
+}
+
+/// Apply single-qubit operator `m` to qubit `target` of the state vector
+/// `amp`.
+pub(crate) fn apply_one<T>(
+    amp: &mut [Complex<T>],
+    target: u16,
+    m: &Matrix2<T>,
+) where
+    T: Float,
+{
+    let half = 1usize << target;
+    amp.par_chunks_mut(half << 1)
+        .for_each(|chunk| apply_one_chunk(chunk, half, m));
+}
+
+/// Apply two-qubit operator `m` to qubits `(q0, q1)` of the state vector
+/// `amp`.
+///
+/// Qubit indices must be distinct.
+pub(crate) fn apply_two<T>(
+    amp: &mut [Complex<T>],
+    q0: u16,
+    q1: u16,
+    m: &Matrix4<T>,
+) where
+    T: Float,
+{
+    let (half_lo, half_hi, m) = reorder(q0, q1, m);
+    amp.par_chunks_mut(half_hi << 1)
+        .for_each(|chunk| apply_two_chunk(chunk, half_lo, half_hi, &m));
+}
+
+/// Apply a gate kernel to the state vector `amp`.
+pub(crate) fn apply_kernel<T>(
+    amp: &mut [Complex<T>],
+    kernel: &Kernel<T>,
+) where
+    T: Float,
+{
+    match kernel {
+        Kernel::One(k, m) => apply_one(amp, *k, m),
+        Kernel::Two(i, j, m) => apply_two(amp, *i, *j, m),
+    }
+}
+
+/// Sum of squared moduli of amplitudes `range` of a state vector after
+/// applying a gate kernel, computed without changing the state.  The
+/// amplitude of the basis state `i` is `amp(i)`.
+///
+/// The kernel need not be unitary: for a Kraus operator `K`, the sum over
+/// the whole state vector is the probability `<psi|K^dagger K|psi>`.
+pub(crate) fn kernel_norm_sqr<T>(
+    amp: impl Fn(usize) -> Complex<T>,
+    kernel: &Kernel<T>,
+    range: Range<usize>,
+) -> T
+where
+    T: Float,
+{
+    match kernel {
+        Kernel::One(k, m) => {
+            let mask = 1usize << k;
+            range
+                .map(|i| {
+                    let base = i & !mask;
+                    let row = &m[usize::from(i & mask != 0)];
+                    (row[0] * amp(base) + row[1] * amp(base | mask)).norm_sqr()
+                })
+                .fold(T::zero(), |acc, x| acc + x)
+        }
+        Kernel::Two(q0, q1, m) => {
+            let (mask0, mask1) = (1usize << q0, 1usize << q1);
+            // Offsets of the four amplitudes of each group, in the order of
+            // the matrix index `b0 + 2 * b1`.
+            let offsets = [0, mask0, mask1, mask0 | mask1];
+            range
+                .map(|i| {
+                    let base = i & !(mask0 | mask1);
+                    let row = &m[usize::from(i & mask0 != 0)
+                        + 2 * usize::from(i & mask1 != 0)];
+                    row.iter()
+                        .zip(offsets)
+                        .map(|(c, o)| c * amp(base | o))
+                        .sum::<Complex<T>>()
+                        .norm_sqr()
+                })
+                .fold(T::zero(), |acc, x| acc + x)
+        }
+    }
+}
+
+/// Apply a gate kernel to the state vector `amp` on the current thread.
+///
+/// Used to apply kernels to chunks of a larger state processed in parallel.
+pub(crate) fn apply_kernel_seq<T>(
+    amp: &mut [Complex<T>],
+    kernel: &Kernel<T>,
+) where
+    T: Float,
+{
+    match kernel {
+        Kernel::One(k, m) => {
+            let half = 1usize << k;
+            amp.chunks_mut(half << 1)
+                .for_each(|chunk| apply_one_chunk(chunk, half, m));
+        }
+        Kernel::Two(i, j, m) => {
+            let (half_lo, half_hi, m) = reorder(*i, *j, m);
+            amp.chunks_mut(half_hi << 1)
+                .for_each(|chunk| apply_two_chunk(chunk, half_lo, half_hi, &m));
+        }
+    }
+}
+
+/// Reorder the matrix of a two-qubit operator so that the index is `b_lo + 2
+/// * b_hi`.  Returns `(1 << lo, 1 << hi, m)`.
+pub(crate) fn reorder<T>(
+    q0: u16,
+    q1: u16,
+    m: &Matrix4<T>,
+) -> (usize, usize, Matrix4<T>)
+where
+    T: Float,
+{
+    debug_assert_ne!(q0, q1);
+    let (lo, hi, m) = if q0 < q1 {
+        (q0, q1, *m)
+    } else {
+        const PERM: [usize; 4] = [0, 2, 1, 3];
+        (q1, q0, PERM.map(|r| PERM.map(|c| m[r][c])))
+    };
+    (1usize << lo, 1usize << hi, m)
+}
+
+fn apply_one_chunk<T>(
+    chunk: &mut [Complex<T>],
+    half: usize,
+    m: &Matrix2<T>,
+) where
+    T: Float,
+{
+    let (lo, hi) = chunk.split_at_mut(half);
+    if m[0][1].is_zero() && m[1][0].is_zero() {
+        // Diagonal phases.
+        if !m[0][0].is_one() {
+            T::scale(lo, m[0][0]);
+        }
+        if !m[1][1].is_one() {
+            T::scale(hi, m[1][1]);
+        }
+    } else {
+        T::rotate(lo, hi, m);
+    }
+}
+
+fn apply_two_chunk<T>(
+    chunk: &mut [Complex<T>],
+    half_lo: usize,
+    half_hi: usize,
+    m: &Matrix4<T>,
+) where
+    T: Float,
+{
+    let (h0, h1) = chunk.split_at_mut(half_hi);
+    for (c0, c1) in h0.chunks_mut(half_lo << 1).zip(h1.chunks_mut(half_lo << 1))
+    {
+        let (a0, a1) = c0.split_at_mut(half_lo);
+        let (a2, a3) = c1.split_at_mut(half_lo);
+        for (((x0, x1), x2), x3) in a0.iter_mut().zip(a1).zip(a2).zip(a3) {
+            let x = [*x0, *x1, *x2, *x3];
+            let y = m.map(|row| {
+                row.iter().zip(&x).map(|(r, v)| r * v).sum::<Complex<T>>()
+            });
+            (*x0, *x1, *x2, *x3) = (y[0], y[1], y[2], y[3]);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! Schrödinger–Feynman hybrid simulation of selected amplitudes.
+//!
+//! The register is cut into two halves: qubits `0..cut` and `cut..n`.  Each
+//! half is simulated with its own [`System`], so memory grows as
+//! `2^cut + 2^(n - cut)` instead of `2^n`.  A two-qubit gate acting across the
+//! cut is split with the operator-Schmidt decomposition `M = sum_k A_k (x)
+//! B_k` into at most four terms, e.g. two for CNOT and CZ, and four for SWAP.
+//! Each choice of terms for all cut gates is a path, and the amplitude
+//! `<x|C|0>` is the sum over paths of products of amplitudes of both halves.
+//! The number of paths grows exponentially with the number of cut gates, see
+//! [`num_paths()`].
+//!
+//! Paths are explored depth-first, so the state of both halves is copied
+//! only at cut gates.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use std::num::NonZeroU16;
+//! # use qn::{hybrid, Bit, Circuit, Gate};
+//! // GHZ state on 24 qubits, with one gate across the cut.
+//! let mut circuit = Circuit::new(NonZeroU16::new(24).unwrap());
+//! circuit.apply(Gate::H(0));
+//! for k in 1..24 {
+//!     circuit.apply(Gate::CNOT(k - 1, k));
+//! }
+//! assert_eq!(hybrid::num_paths(&circuit, 12), Ok(2));
+//!
+//! let amp = hybrid::amplitudes::<f64>(
+//!     &circuit,
+//!     12,
+//!     &[vec![Bit::ONE; 24], vec![Bit::ZERO; 24]],
+//! )
+//! .unwrap();
+//! assert!((amp[0].re - 0.5f64.sqrt()).abs() < 1e-12);
+//! assert!((amp[1].re - 0.5f64.sqrt()).abs() < 1e-12);
+//! ```
+//!
+//! [`System`]: crate::System
+
+use std::{
+    fmt,
+    num::NonZeroU16,
+};
+
+use num::{
+    Complex,
+    Zero,
+};
+
+use crate::{
+    gate::{
+        self,
+        Kernel,
+    },
+    linalg,
+    Bit,
+    Circuit,
+    Float,
+    Matrix2,
+    Operation,
+    System,
+};
+
+/// Error type returned by the hybrid simulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HybridError {
+    /// Cut does not split the register into two non-empty halves, or one of
+    /// the halves is too large to simulate.
+    InvalidCut,
+    /// Circuit contains measurements.
+    Measurement,
+    /// Length of a bitstring is not the number of qubits of the circuit.
+    InvalidBitstring,
+}
+
+impl fmt::Display for HybridError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::InvalidCut => write!(f, "invalid cut"),
+            Self::Measurement => write!(f, "circuit contains measurements"),
+            Self::InvalidBitstring => write!(f, "invalid bitstring"),
+        }
+    }
+}
+
+impl std::error::Error for HybridError {}
+
+/// Single step of the circuit, with qubit indices local to each half.
+enum Step<T> {
+    Lower(Kernel<T>),
+    Upper(Kernel<T>),
+    /// Terms `(A_k, B_k)` of a gate across the cut, acting on qubits
+    /// `(lower, upper)`.
+    Cut {
+        lower: u16,
+        upper: u16,
+        terms: Vec<(Matrix2<T>, Matrix2<T>)>,
+    },
+}
+
+/// Number of paths summed over to compute an amplitude: the product of
+/// Schmidt ranks of gates acting across the cut.
+///
+/// Saturates at `usize::MAX`.
+///
+/// # Errors
+///
+/// Returns an error, if the cut or the circuit is invalid, see
+/// [`amplitudes()`].
+pub fn num_paths<T>(
+    circuit: &Circuit<T>,
+    cut: u16,
+) -> Result<usize, HybridError>
+where
+    T: Float,
+{
+    Ok(split(circuit, cut)?
+        .iter()
+        .map(|step| match step {
+            Step::Cut {
+                terms, ..
+            } => terms.len(),
+            _ => 1,
+        })
+        .fold(1, usize::saturating_mul))
+}
+
+/// Compute amplitudes `<x|C|0>` of the computational basis states given by
+/// `bitstrings`, where `x[k]` is the value of qubit `k`.
+///
+/// The register is cut between qubits `cut - 1` and `cut`.
+///
+/// # Errors
+///
+/// Returns an error
+/// - if `cut` is zero or not smaller than the number of qubits, or if any of
+///   the halves has at least `usize::BITS` qubits
+/// - if the circuit contains measurements
+/// - if the length of any of the bitstrings is not the number of qubits of the
+///   circuit
+pub fn amplitudes<T>(
+    circuit: &Circuit<T>,
+    cut: u16,
+    bitstrings: &[Vec<Bit>],
+) -> Result<Vec<Complex<T>>, HybridError>
+where
+    T: Float,
+{
+    let steps = split(circuit, cut)?;
+    let n = usize::from(circuit.num_qubits().get());
+    if bitstrings.iter().any(|x| x.len() != n) {
+        return Err(HybridError::InvalidBitstring);
+    }
+    let index = |bits: &[Bit]| {
+        bits.iter()
+            .enumerate()
+            .filter(|(_, &b)| bool::from(b))
+            .fold(0usize, |acc, (k, _)| acc | (1 << k))
+    };
+    let targets: Vec<_> = bitstrings
+        .iter()
+        .map(|x| {
+            let (lo, hi) = x.split_at(usize::from(cut));
+            (index(lo), index(hi))
+        })
+        .collect();
+
+    let lower = System::new(NonZeroU16::new(cut).expect("valid cut"), 0);
+    let upper = System::new(
+        NonZeroU16::new(circuit.num_qubits().get() - cut).expect("valid cut"),
+        0,
+    );
+    let mut acc = vec![Complex::zero(); targets.len()];
+    explore(&steps, lower, upper, &targets, &mut acc);
+    Ok(acc)
+}
+
+/// Split the circuit into steps acting on each half.
+fn split<T>(
+    circuit: &Circuit<T>,
+    cut: u16,
+) -> Result<Vec<Step<T>>, HybridError>
+where
+    T: Float,
+{
+    let n = circuit.num_qubits().get();
+    if cut == 0
+        || cut >= n
+        || u32::from(cut) >= usize::BITS
+        || u32::from(n - cut) >= usize::BITS
+    {
+        return Err(HybridError::InvalidCut);
+    }
+
+    circuit
+        .operations()
+        .iter()
+        .map(|op| {
+            let Operation::Gate(gate) = op else {
+                return Err(HybridError::Measurement);
+            };
+            Ok(match gate.kernel() {
+                Kernel::One(q, m) if q < cut => Step::Lower(Kernel::One(q, m)),
+                Kernel::One(q, m) => Step::Upper(Kernel::One(q - cut, m)),
+                Kernel::Two(q0, q1, m) if q0 < cut && q1 < cut => {
+                    Step::Lower(Kernel::Two(q0, q1, m))
+                }
+                Kernel::Two(q0, q1, m) if q0 >= cut && q1 >= cut => {
+                    Step::Upper(Kernel::Two(q0 - cut, q1 - cut, m))
+                }
+                Kernel::Two(q0, q1, m) => {
+                    // Reshape M[(a, b), (a', b')] into R[(a, a'), (b, b')],
+                    // where a is the qubit in the lower half.
+                    let lower_first = q0 < q1;
+                    let index = |a: usize, b: usize| {
+                        if lower_first {
+                            a + 2 * b
+                        } else {
+                            b + 2 * a
+                        }
+                    };
+                    let mut r = vec![Complex::zero(); 16];
+                    for a in 0..4 {
+                        for b in 0..4 {
+                            let (i, j) = (a >> 1, a & 1);
+                            let (k, l) = (b >> 1, b & 1);
+                            r[a * 4 + b] = m[index(i, k)][index(j, l)];
+                        }
+                    }
+                    let svd = linalg::svd(&r, 4, 4);
+                    let tol = T::epsilon() * T::from(16).unwrap() * svd.s[0];
+                    let terms = (0..4)
+                        .filter(|&t| svd.s[t] > tol)
+                        .map(|t| {
+                            let a = [0, 1].map(|i| {
+                                [0, 1].map(|j| {
+                                    svd.u[(i * 2 + j) * 4 + t] * svd.s[t]
+                                })
+                            });
+                            let b = [0, 1].map(|i| {
+                                [0, 1]
+                                    .map(|j| svd.v[(i * 2 + j) * 4 + t].conj())
+                            });
+                            (a, b)
+                        })
+                        .collect();
+                    let (lower, upper) = (q0.min(q1), q0.max(q1) - cut);
+                    Step::Cut {
+                        lower,
+                        upper,
+                        terms,
+                    }
+                }
+            })
+        })
+        .collect()
+}
+
+/// Apply steps to both halves, branching at cut gates, and add the resulting
+/// contributions to the amplitudes of `targets`.
+fn explore<T>(
+    steps: &[Step<T>],
+    mut lower: System<T>,
+    mut upper: System<T>,
+    targets: &[(usize, usize)],
+    acc: &mut [Complex<T>],
+) where
+    T: Float,
+{
+    for (i, step) in steps.iter().enumerate() {
+        match step {
+            Step::Lower(kernel) => {
+                gate::apply_kernel(lower.as_mut_slice(), kernel);
+            }
+            Step::Upper(kernel) => {
+                gate::apply_kernel(upper.as_mut_slice(), kernel);
+            }
+            Step::Cut {
+                lower: a,
+                upper: b,
+                terms,
+            } => {
+                let rest = &steps[i + 1..];
+                let (last, first) = terms.split_last().expect("non-zero gate");
+                for (ma, mb) in first {
+                    let (mut lo, mut hi) = (copy(&lower), copy(&upper));
+                    if branch(&mut lo, *a, ma) && branch(&mut hi, *b, mb) {
+                        explore(rest, lo, hi, targets, acc);
+                    }
+                }
+                let (ma, mb) = last;
+                if branch(&mut lower, *a, ma) && branch(&mut upper, *b, mb) {
+                    explore(rest, lower, upper, targets, acc);
+                }
+                return;
+            }
+        }
+    }
+
+    let (lo, hi) = (lower.as_slice(), upper.as_slice());
+    for (a, &(x, y)) in acc.iter_mut().zip(targets) {
+        *a += lo[x] * hi[y];
+    }
+}
+
+/// Apply a term of a cut gate to one half.  Returns `false`, if the state
+/// vanishes.
+fn branch<T>(
+    stm: &mut System<T>,
+    target: u16,
+    m: &Matrix2<T>,
+) -> bool
+where
+    T: Float,
+{
+    gate::apply_one(stm.as_mut_slice(), target, m);
+    stm.as_slice().iter().any(|a| !a.is_zero())
+}
+
+fn copy<T>(stm: &System<T>) -> System<T>
+where
+    T: Float,
+{
+    let mut dst = System::new(stm.num_qubits(), 0);
+    dst.as_mut_slice().copy_from_slice(stm.as_slice());
+    dst
+}
@@ -0,0 +1,231 @@
+use std::thread::{
+    self,
+    ThreadId,
+};
+
+use crate::{
+    Bit,
+    Float,
+    Gate,
+};
+
+/// Operation performed on a [`System`], recorded in its journal and passed to
+/// its observer.
+///
+/// Events are recorded in the order in which operations were performed, with
+/// the thread that performed them, so that interleavings of threads using
+/// qubits of the same system can be audited and replayed.  See
+/// [`System::set_journal()`] and [`System::set_observer()`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Bit, EventKind, Gate, System};
+/// let num_qubits = NonZeroU16::new(2).unwrap();
+/// let mut stm: System<f64> = System::new(num_qubits, 123);
+/// stm.set_journal(true);
+///
+/// stm.apply(&Gate::X(1));
+/// assert_eq!(stm.qubit(1).unwrap().measure(), Bit::ONE);
+///
+/// let journal = stm.journal().unwrap();
+/// assert_eq!(journal.len(), 2);
+/// assert_eq!(journal[0].kind, EventKind::Gate(Gate::X(1)));
+/// assert!(matches!(
+///     journal[1].kind,
+///     EventKind::Measure {
+///         index: 1,
+///         outcome: Bit::ONE,
+///         ..
+///     }
+/// ));
+/// assert_eq!(journal[1].seq, 1);
+/// assert_eq!(journal[1].thread, std::thread::current().id());
+/// ```
+///
+/// [`System`]: crate::System
+/// [`System::set_journal()`]: crate::System::set_journal
+/// [`System::set_observer()`]: crate::System::set_observer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event<T> {
+    /// Number of the event among events recorded by the system, starting
+    /// from zero.
+    pub seq:    u64,
+    /// Thread that requested the operation.
+    pub thread: ThreadId,
+    pub kind:   EventKind<T>,
+}
+
+/// Kind of operation recorded in an [`Event`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventKind<T> {
+    /// Gate applied to the system.
+    ///
+    /// Gates queued by [`Qubit::apply()`] are recorded one by one, in the
+    /// order they were queued and with the thread that queued each of them,
+    /// when the queue is applied.
+    ///
+    /// [`Qubit::apply()`]: crate::Qubit::apply
+    Gate(Gate<T>),
+    /// Kraus operator of a channel applied to `qubits`, sampled with the
+    /// uniform `draw` from the interval `[0, 1)`.
+    Channel {
+        qubits: Vec<u16>,
+        kraus:  usize,
+        draw:   f64,
+    },
+    /// Measurement of qubit `index`, with the actual outcome (not subject to
+    /// readout errors) and the probability of `ONE`.  `rng_word_pos` is the
+    /// position of the internal RNG before the draw, see
+    /// `ChaCha8Rng::get_word_pos()`.
+    Measure {
+        index:        u16,
+        outcome:      Bit,
+        probability:  T,
+        rng_word_pos: u128,
+    },
+    /// Outcome of measuring qubit `index` reported by a readout model: that
+    /// of the system, or that of the noise model passed to
+    /// [`System::run_noisy()`].  Recorded only if the model is set.
+    ///
+    /// [`System::run_noisy()`]: crate::System::run_noisy
+    Readout {
+        index:    u16,
+        outcome:  Bit,
+        reported: Bit,
+    },
+    /// Sampling of outcomes of measuring all qubits, without changing the
+    /// state.
+    Sample {
+        shots:        usize,
+        rng_word_pos: u128,
+    },
+    /// Reset of all qubits to the zero state.
+    Reset,
+}
+
+/// Callback invoked for each operation performed on a [`System`], see
+/// [`System::set_observer()`].
+///
+/// The observer is called while the system is locked, so it sees operations
+/// in the order they were performed.  It is implemented for closures.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::{num::NonZeroU16, sync::mpsc};
+/// # use qn::{Event, Gate, System};
+/// let num_qubits = NonZeroU16::new(2).unwrap();
+/// let mut stm: System<f64> = System::new(num_qubits, 123);
+///
+/// let (tx, rx) = mpsc::channel();
+/// stm.set_observer(Some(Box::new(move |event: &Event<f64>| {
+///     tx.send(event.seq).unwrap();
+/// })));
+/// stm.apply(&Gate::H(0));
+/// stm.apply(&Gate::CNOT(0, 1));
+///
+/// assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![0, 1]);
+/// ```
+///
+/// [`System`]: crate::System
+/// [`System::set_observer()`]: crate::System::set_observer
+pub trait Observer<T>: Send + Sync {
+    /// Handle an event.
+    fn observe(
+        &mut self,
+        event: &Event<T>,
+    );
+}
+
+impl<T, F> Observer<T> for F
+where
+    F: FnMut(&Event<T>) + Send + Sync,
+{
+    fn observe(
+        &mut self,
+        event: &Event<T>,
+    ) {
+        self(event);
+    }
+}
+
+/// Journal and observer of a system.
+pub(crate) struct Recorder<T> {
+    journal:  Option<Vec<Event<T>>>,
+    observer: Option<Box<dyn Observer<T>>>,
+    seq:      u64,
+}
+
+impl<T> Default for Recorder<T> {
+    fn default() -> Self {
+        Self {
+            journal:  None,
+            observer: None,
+            seq:      0,
+        }
+    }
+}
+
+impl<T> Recorder<T>
+where
+    T: Float,
+{
+    pub(crate) fn journal(&self) -> Option<&[Event<T>]> {
+        self.journal.as_deref()
+    }
+
+    /// Start a new, empty journal, or stop recording.
+    pub(crate) fn set_journal(
+        &mut self,
+        enabled: bool,
+    ) {
+        self.journal = enabled.then(Vec::new);
+    }
+
+    pub(crate) fn take_journal(&mut self) -> Vec<Event<T>> {
+        self.journal
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn set_observer(
+        &mut self,
+        observer: Option<Box<dyn Observer<T>>>,
+    ) {
+        self.observer = observer;
+    }
+
+    /// Check if events are recorded or observed.
+    pub(crate) fn is_active(&self) -> bool {
+        self.journal.is_some() || self.observer.is_some()
+    }
+
+    /// Record an event of kind `kind()` performed by `thread`, or by the
+    /// current thread, if `thread` is `None`.
+    ///
+    /// `kind` is not called, unless events are recorded or observed.
+    pub(crate) fn record(
+        &mut self,
+        thread: Option<ThreadId>,
+        kind: impl FnOnce() -> EventKind<T>,
+    ) {
+        if !self.is_active() {
+            return;
+        }
+        let event = Event {
+            seq:    self.seq,
+            thread: thread.unwrap_or_else(|| thread::current().id()),
+            kind:   kind(),
+        };
+        self.seq += 1;
+        if let Some(observer) = &mut self.observer {
+            observer.observe(&event);
+        }
+        if let Some(journal) = &mut self.journal {
+            journal.push(event);
+        }
+    }
+}
@@ -0,0 +1,609 @@
+//! Krylov subspace methods for Hermitian operators.
+//!
+//! Operators are accessed only through matrix-vector products, see
+//! [`LinearOperator`].  The Krylov subspace spanned by `psi, H psi, H^2 psi,
+//! ...` is built with the Lanczos iteration, with full reorthogonalisation.
+//! Functions of `H` acting on `psi` are then approximated by the same
+//! functions of a small tridiagonal matrix.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use std::num::NonZeroU16;
+//! # use qn::{krylov, Gate, Pauli, PauliString, PauliSum, System};
+//! let num_qubits = NonZeroU16::new(1).unwrap();
+//! let mut ham = PauliSum::new();
+//! ham.add_term(0.5, PauliString::new(&[(0, Pauli::X)]).unwrap());
+//!
+//! let mut stm: System<f64> = System::new(num_qubits, 123);
+//! krylov::expm_multiply(&mut stm, &ham, 1.2, 1e-12).unwrap();
+//!
+//! let mut expected: System<f64> = System::new(num_qubits, 123);
+//! expected.apply(&Gate::Rx(0, 1.2));
+//! for (a, b) in stm.as_slice().iter().zip(expected.as_slice()) {
+//!     assert!((a - b).norm() < 1e-10);
+//! }
+//! ```
+//!
+//! [`LinearOperator`]: crate::LinearOperator
+
+use std::fmt;
+
+use num::{
+    Complex,
+    Zero,
+};
+use rayon::prelude::{
+    IndexedParallelIterator,
+    IntoParallelRefIterator,
+    IntoParallelRefMutIterator,
+    ParallelIterator,
+};
+
+use crate::{
+    linalg,
+    Float,
+    LinearOperator,
+    System,
+};
+
+/// Maximal dimension of the Krylov subspace built for a single time step.
+const MAX_DIM: usize = 30;
+
+/// Maximal number of matrix-vector products of the eigensolver.
+const MAX_MATVEC: usize = 10_000;
+
+/// Error type returned by Krylov subspace methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KrylovError {
+    /// Time is not finite.
+    InvalidTime,
+    /// Tolerance is not positive or not finite.
+    InvalidTolerance,
+    /// Number of requested eigenvalues is zero or exceeds the dimension.
+    InvalidCount,
+    /// Method could not reach the requested tolerance.
+    NotConverged,
+}
+
+impl fmt::Display for KrylovError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::InvalidTime => write!(f, "invalid time"),
+            Self::InvalidTolerance => write!(f, "invalid tolerance"),
+            Self::InvalidCount => {
+                write!(f, "invalid number of eigenvalues")
+            }
+            Self::NotConverged => write!(f, "not converged"),
+        }
+    }
+}
+
+impl std::error::Error for KrylovError {}
+
+/// Evolve the system under the Hermitian operator `op`: `psi -> exp(-i op
+/// time) psi`.
+///
+/// The time interval is split into steps.  For each step, a Krylov subspace
+/// of dimension at most 30 is built from the current state, and the step
+/// size is chosen so that the a posteriori estimate of the error of the step
+/// is below `tolerance` times the fraction of the time interval it covers.
+/// The Euclidean norm of the error of the final state is therefore of the
+/// order of `tolerance`.
+///
+/// Returns the number of steps.
+///
+/// # Errors
+///
+/// Returns an error
+/// - if `time` is not finite or `tolerance` is not positive
+/// - if the step size needed to reach the tolerance becomes negligible
+///
+/// In the latter case, the system is left in the state reached so far.
+///
+/// # Panics
+///
+/// Panics, if the operator cannot act on the state vector of the system, see
+/// [`LinearOperator::is_valid_dim()`].
+///
+/// [`LinearOperator::is_valid_dim()`]: crate::LinearOperator::is_valid_dim
+pub fn expm_multiply<T, A>(
+    stm: &mut System<T>,
+    op: &A,
+    time: T,
+    tolerance: T,
+) -> Result<usize, KrylovError>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+{
+    assert!(
+        op.is_valid_dim(stm.as_slice().len()),
+        "operator does not fit in the system"
+    );
+    if !time.is_finite() {
+        return Err(KrylovError::InvalidTime);
+    }
+    if !tolerance.is_finite() || tolerance <= T::zero() {
+        return Err(KrylovError::InvalidTolerance);
+    }
+    expm_multiply_amp(op, stm.as_mut_slice(), time, tolerance)
+}
+
+fn expm_multiply_amp<T, A>(
+    op: &A,
+    amp: &mut [Complex<T>],
+    time: T,
+    tolerance: T,
+) -> Result<usize, KrylovError>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+{
+    let total = time.abs();
+    let min_step = total * T::epsilon();
+    let max_dim = MAX_DIM.min(amp.len());
+    let (half, two) = (T::from(0.5).unwrap(), T::from(2.).unwrap());
+
+    let mut done = T::zero();
+    let mut tau = total;
+    let mut steps = 0;
+    while done < total {
+        let norm = norm(amp);
+        if norm == T::zero() {
+            break;
+        }
+        let mut lanczos = Lanczos::new(amp, norm);
+        while lanczos.dim() < max_dim && lanczos.extend(op) {}
+        let (values, vectors) =
+            linalg::eigh(&lanczos.tridiagonal(), lanczos.dim());
+
+        // Largest step, for which the error estimate is below tolerance.
+        tau = tau.min(total - done);
+        let coeffs = loop {
+            if tau <= min_step {
+                return Err(KrylovError::NotConverged);
+            }
+            let dt = if time < T::zero() { -tau } else { tau };
+            let coeffs = exp_first_column(&values, &vectors, dt);
+            let error = lanczos.residual()
+                * coeffs.last().expect("non-empty subspace").norm()
+                * norm;
+            if error <= tolerance * tau / total {
+                break coeffs;
+            }
+            tau *= half;
+        };
+
+        lanczos.combine(&coeffs, norm, amp);
+        done += tau;
+        steps += 1;
+        // Try a larger step next time.
+        tau *= two;
+    }
+    Ok(steps)
+}
+
+/// Compute the `k` lowest eigenvalues of the Hermitian operator `op`.
+///
+/// Uses the thick-restart Lanczos method with full reorthogonalisation,
+/// started from a random vector drawn with the system's RNG; the state of the
+/// system is not modified.  The basis holds at most `max(2k + 10, 30)`
+/// vectors, each of the size of the state vector of the system.  When the
+/// basis is full, the iteration is restarted, keeping the lowest Ritz
+/// vectors.
+///
+/// The iteration stops, when the residual norm `|H y - theta y|` of each of
+/// the `k` lowest Ritz pairs `(theta, y)` is below `tolerance`, which is
+/// then also the bound on the error of the eigenvalues.
+///
+/// Returns eigenvalues in non-decreasing order.  Since the Krylov subspace is
+/// generated by a single vector, eigenvalues of higher multiplicity are
+/// reported only once.
+///
+/// # Errors
+///
+/// Returns an error
+/// - if `k` is zero or larger than the dimension of the state vector
+/// - if `tolerance` is not positive
+/// - if the method does not converge within 10000 matrix-vector products, or if
+///   the Krylov subspace becomes invariant with fewer than `k` eigenvalues
+///
+/// # Panics
+///
+/// Panics, if the operator cannot act on the state vector of the system, see
+/// [`LinearOperator::is_valid_dim()`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{krylov, Pauli, PauliString, PauliSum, System};
+/// let num_qubits = NonZeroU16::new(2).unwrap();
+/// let mut ham = PauliSum::new();
+/// ham.add_term(1., PauliString::new(&[(0, Pauli::Z)]).unwrap());
+/// ham.add_term(0.5, PauliString::new(&[(1, Pauli::Z)]).unwrap());
+///
+/// let mut stm: System<f64> = System::new(num_qubits, 123);
+/// let values = krylov::lowest_eigenvalues(&mut stm, &ham, 2, 1e-10).unwrap();
+///
+/// assert!((values[0] + 1.5).abs() < 1e-10);
+/// assert!((values[1] + 0.5).abs() < 1e-10);
+/// ```
+///
+/// [`LinearOperator::is_valid_dim()`]: crate::LinearOperator::is_valid_dim
+pub fn lowest_eigenvalues<T, A>(
+    stm: &mut System<T>,
+    op: &A,
+    k: usize,
+    tolerance: T,
+) -> Result<Vec<T>, KrylovError>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+{
+    let start = random_vector(stm, op);
+    eigensolve(op, start, k, tolerance).map(|(values, _)| values)
+}
+
+/// Load the ground state of the Hermitian operator `op` into the system.
+///
+/// See [`lowest_eigenvalues()`] for the description of the method.  The
+/// ground state is determined up to a global phase.
+///
+/// Returns the ground-state energy.
+///
+/// # Errors
+///
+/// Returns an error, if the method fails to converge, see
+/// [`lowest_eigenvalues()`].  In this case, the state of the system is not
+/// modified.
+///
+/// # Panics
+///
+/// Panics, if the operator cannot act on the state vector of the system, see
+/// [`LinearOperator::is_valid_dim()`].
+///
+/// [`LinearOperator::is_valid_dim()`]: crate::LinearOperator::is_valid_dim
+pub fn ground_state<T, A>(
+    stm: &mut System<T>,
+    op: &A,
+    tolerance: T,
+) -> Result<T, KrylovError>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+{
+    let start = random_vector(stm, op);
+    let (values, vector) = eigensolve(op, start, 1, tolerance)?;
+    let norm = norm(&vector);
+    stm.as_mut_slice()
+        .par_iter_mut()
+        .zip(&vector)
+        .for_each(|(a, v)| *a = v / norm);
+    Ok(values[0])
+}
+
+/// Random starting vector for the eigensolver, drawn with the system's RNG.
+fn random_vector<T, A>(
+    stm: &mut System<T>,
+    op: &A,
+) -> Vec<Complex<T>>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+{
+    let dim = stm.as_slice().len();
+    assert!(op.is_valid_dim(dim), "operator does not fit in the system");
+    let half = 0.5;
+    (0..dim)
+        .map(|_| {
+            let re = T::from(stm.uniform() - half).unwrap();
+            let im = T::from(stm.uniform() - half).unwrap();
+            Complex::new(re, im)
+        })
+        .collect()
+}
+
+/// Thick-restart Lanczos iteration.
+///
+/// Returns the `k` lowest eigenvalues and the eigenvector of the lowest one.
+fn eigensolve<T, A>(
+    op: &A,
+    start: Vec<Complex<T>>,
+    k: usize,
+    tolerance: T,
+) -> Result<(Vec<T>, Vec<Complex<T>>), KrylovError>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+{
+    let dim = start.len();
+    if k == 0 || k > dim {
+        return Err(KrylovError::InvalidCount);
+    }
+    if !tolerance.is_finite() || tolerance <= T::zero() {
+        return Err(KrylovError::InvalidTolerance);
+    }
+    let max_dim = (2 * k + 10).max(30).min(dim);
+    let keep = (k + (max_dim - k) / 2).min(max_dim - 1);
+
+    // Orthonormal basis and the projection of the operator onto it, stored
+    // as a dense matrix with row stride `max_dim`.
+    let mut basis: Vec<Vec<Complex<T>>> = Vec::with_capacity(max_dim);
+    let mut h: Vec<Complex<T>> = vec![Complex::zero(); max_dim * max_dim];
+    let norm0 = norm(&start);
+    let mut next = start;
+    next.par_iter_mut().for_each(|z| *z /= norm0);
+
+    let mut w = vec![Complex::zero(); dim];
+    for _ in 0..MAX_MATVEC {
+        let j = basis.len();
+        op.mul_vec(&next, &mut w);
+        basis.push(next);
+
+        // Project out the basis, twice to counter cancellation.  The
+        // coefficients form a column of the projection of the operator.
+        for _ in 0..2 {
+            for (i, v) in basis.iter().enumerate() {
+                let c = dot(v, &w);
+                axpy(-c, v, &mut w);
+                h[i * max_dim + j] += c;
+            }
+        }
+        h[j * max_dim + j] = Complex::from(h[j * max_dim + j].re);
+        for i in 0..j {
+            h[j * max_dim + i] = h[i * max_dim + j].conj();
+        }
+        let beta = norm(&w);
+
+        let m = j + 1;
+        let proj: Vec<_> = (0..m)
+            .flat_map(|r| h[r * max_dim..r * max_dim + m].iter().copied())
+            .collect();
+        let (values, vectors) = linalg::eigh(&proj, m);
+        let scale = values.iter().fold(T::one(), |a, b| a.max(b.abs()));
+        let invariant = beta <= T::epsilon() * scale;
+        let converged = m >= k
+            && (invariant
+                || (0..k).all(|i| {
+                    beta * vectors[(m - 1) * m + i].norm() <= tolerance
+                }));
+
+        if converged {
+            let mut ground = vec![Complex::zero(); dim];
+            ritz_vector(&basis, &vectors, m, 0, &mut ground);
+            return Ok((values[..k].to_vec(), ground));
+        }
+        if invariant {
+            return Err(KrylovError::NotConverged);
+        }
+
+        // Next basis vector, orthogonal to the current basis.
+        let mut residual = vec![Complex::zero(); dim];
+        std::mem::swap(&mut residual, &mut w);
+        residual.par_iter_mut().for_each(|z| *z /= beta);
+        next = residual;
+
+        if m == max_dim {
+            // Thick restart: keep the lowest Ritz vectors.  The projection
+            // onto them is diagonal.
+            let ritz: Vec<_> = (0..keep)
+                .map(|col| {
+                    let mut y = vec![Complex::zero(); dim];
+                    ritz_vector(&basis, &vectors, m, col, &mut y);
+                    y
+                })
+                .collect();
+            basis = ritz;
+            h.iter_mut().for_each(|z| *z = Complex::zero());
+            for (i, &theta) in values.iter().take(keep).enumerate() {
+                h[i * max_dim + i] = Complex::from(theta);
+            }
+        }
+    }
+    Err(KrylovError::NotConverged)
+}
+
+/// Compute the Ritz vector `y = V s`, where `s` is the column `col` of the
+/// `m x m` matrix `vectors`.
+fn ritz_vector<T>(
+    basis: &[Vec<Complex<T>>],
+    vectors: &[Complex<T>],
+    m: usize,
+    col: usize,
+    out: &mut [Complex<T>],
+) where
+    T: Float,
+{
+    out.par_iter_mut().enumerate().for_each(|(j, z)| {
+        *z = basis
+            .iter()
+            .enumerate()
+            .map(|(i, v)| v[j] * vectors[i * m + col])
+            .sum();
+    });
+}
+
+/// Compute `exp(-i dt T) e_0`, where `T = V diag(values) V^dagger`.
+fn exp_first_column<T>(
+    values: &[T],
+    vectors: &[Complex<T>],
+    dt: T,
+) -> Vec<Complex<T>>
+where
+    T: Float,
+{
+    let m = values.len();
+    (0..m)
+        .map(|k| {
+            values
+                .iter()
+                .enumerate()
+                .map(|(col, &lambda)| {
+                    vectors[k * m + col]
+                        * vectors[col].conj()
+                        * Complex::from_polar(T::one(), -lambda * dt)
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// Lanczos iteration with full reorthogonalisation.
+///
+/// Builds an orthonormal basis `v_0, ..., v_{m-1}` of the Krylov subspace,
+/// in which the operator is tridiagonal, with diagonal `alpha` and
+/// off-diagonal `beta`.  The last entry of `beta` is the norm of the
+/// residual: the component of `H v_{m-1}` outside the subspace.
+pub(crate) struct Lanczos<T> {
+    basis:    Vec<Vec<Complex<T>>>,
+    alpha:    Vec<T>,
+    beta:     Vec<T>,
+    residual: Option<Vec<Complex<T>>>,
+}
+
+impl<T> Lanczos<T>
+where
+    T: Float,
+{
+    /// Start the iteration from `v / norm`, where `norm` is the norm of `v`.
+    pub(crate) fn new(
+        v: &[Complex<T>],
+        norm: T,
+    ) -> Self {
+        let v0 = v.par_iter().map(|z| z / norm).collect();
+        Self {
+            basis:    Vec::new(),
+            alpha:    Vec::new(),
+            beta:     Vec::new(),
+            residual: Some(v0),
+        }
+    }
+
+    /// Dimension of the subspace built so far.
+    pub(crate) fn dim(&self) -> usize {
+        self.basis.len()
+    }
+
+    /// Norm of the residual, or zero if the subspace is invariant.
+    pub(crate) fn residual(&self) -> T {
+        self.beta.last().copied().unwrap_or_else(T::zero)
+    }
+
+    /// Add one vector to the basis.
+    ///
+    /// Returns `false`, if the subspace has become invariant under the
+    /// operator and cannot be extended any further.
+    pub(crate) fn extend<A>(
+        &mut self,
+        op: &A,
+    ) -> bool
+    where
+        A: LinearOperator<T> + ?Sized,
+    {
+        let Some(v) = self.residual.take() else {
+            return false;
+        };
+        let mut w = vec![Complex::zero(); v.len()];
+        op.mul_vec(&v, &mut w);
+
+        let alpha = dot(&v, &w).re;
+        axpy(-Complex::from(alpha), &v, &mut w);
+        if let (Some(prev), Some(&beta)) = (self.basis.last(), self.beta.last())
+        {
+            axpy(-Complex::from(beta), prev, &mut w);
+        }
+        self.basis.push(v);
+        // Full reorthogonalisation, repeated to counter cancellation.
+        for _ in 0..2 {
+            for u in &self.basis {
+                let c = dot(u, &w);
+                axpy(-c, u, &mut w);
+            }
+        }
+
+        let beta = norm(&w);
+        self.alpha.push(alpha);
+        self.beta.push(beta);
+        let scale = self
+            .alpha
+            .iter()
+            .chain(&self.beta)
+            .fold(T::one(), |a, b| a.max(b.abs()));
+        if beta > T::epsilon() * scale {
+            w.par_iter_mut().for_each(|z| *z /= beta);
+            self.residual = Some(w);
+        } else {
+            // Invariant subspace: the tridiagonal matrix is exact.
+            *self.beta.last_mut().expect("non-empty") = T::zero();
+        }
+        true
+    }
+
+    /// Tridiagonal matrix of the operator in the basis, as a dense complex
+    /// `m x m` matrix in row-major order.
+    pub(crate) fn tridiagonal(&self) -> Vec<Complex<T>> {
+        let m = self.dim();
+        let mut t = vec![Complex::zero(); m * m];
+        for (k, &a) in self.alpha.iter().enumerate() {
+            t[k * m + k] = Complex::from(a);
+        }
+        for (k, &b) in self.beta.iter().take(m.saturating_sub(1)).enumerate() {
+            t[k * m + k + 1] = Complex::from(b);
+            t[(k + 1) * m + k] = Complex::from(b);
+        }
+        t
+    }
+
+    /// Compute `out = scale * sum_k coeffs[k] v_k`.
+    pub(crate) fn combine(
+        &self,
+        coeffs: &[Complex<T>],
+        scale: T,
+        out: &mut [Complex<T>],
+    ) {
+        out.par_iter_mut().enumerate().for_each(|(j, z)| {
+            *z = self
+                .basis
+                .iter()
+                .zip(coeffs)
+                .map(|(v, c)| v[j] * c)
+                .sum::<Complex<T>>()
+                * scale;
+        });
+    }
+}
+
+/// Inner product `<a|b>`.
+fn dot<T>(
+    a: &[Complex<T>],
+    b: &[Complex<T>],
+) -> Complex<T>
+where
+    T: Float,
+{
+    a.par_iter().zip(b).map(|(x, y)| x.conj() * y).sum()
+}
+
+/// Compute `y += c x`.
+fn axpy<T>(
+    c: Complex<T>,
+    x: &[Complex<T>],
+    y: &mut [Complex<T>],
+) where
+    T: Float,
+{
+    y.par_iter_mut().zip(x).for_each(|(y, x)| *y += c * x);
+}
+
+/// Euclidean norm.
+pub(crate) fn norm<T>(v: &[Complex<T>]) -> T
+where
+    T: Float,
+{
+    v.par_iter().map(Complex::norm_sqr).sum::<T>().sqrt()
+}
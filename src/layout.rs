@@ -0,0 +1,347 @@
+use std::{
+    num::NonZeroU16,
+    ops::Range,
+};
+
+use num::{
+    Complex,
+    Zero,
+};
+use rayon::prelude::{
+    IndexedParallelIterator,
+    IntoParallelRefMutIterator,
+    ParallelIterator,
+};
+
+use crate::{
+    gate,
+    gate::Kernel,
+    soa,
+    Float,
+    Schedule,
+};
+
+/// Storage of amplitudes of a [`System`].
+///
+/// All operations of [`System`] are available for both layouts: they differ
+/// only in how kernels access memory.  Use [`Interleaved`] (the default) for
+/// access to amplitudes as a slice of complex numbers, see
+/// [`System::as_slice()`], and [`Split`] for kernels that vectorise over
+/// contiguous runs of real numbers, see [`SoaSystem`].
+///
+/// This trait is sealed: it is implemented only for the two layouts above.
+///
+/// [`System`]: crate::System
+/// [`System::as_slice()`]: crate::System::as_slice
+/// [`SoaSystem`]: crate::SoaSystem
+pub trait Layout: Sealed {}
+
+/// Amplitudes stored as complex numbers, with interleaved real and imaginary
+/// parts (array of structures).  The default layout of [`System`].
+///
+/// [`System`]: crate::System
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Interleaved;
+
+/// Real and imaginary parts of amplitudes stored in separate vectors
+/// (structure of arrays).  See [`SoaSystem`].
+///
+/// [`SoaSystem`]: crate::SoaSystem
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Split;
+
+impl Layout for Interleaved {}
+impl Layout for Split {}
+
+/// Operations of [`System`] on the state vector, specialised for each
+/// layout.  Not exported, so that [`Layout`] cannot be implemented outside of
+/// this crate.
+///
+/// Methods taking `parallel` use rayon's parallel iterators, if it is
+/// `true`, and must not use rayon otherwise, see `Executor::run()`.
+///
+/// [`System`]: crate::System
+pub trait Sealed {
+    /// State vector.
+    type Amp<T: Float>: Send + Sync;
+
+    /// State vector of `num_qubits` qubits in the zero state.
+    fn zero_state<T: Float>(num_qubits: NonZeroU16) -> Self::Amp<T>;
+
+    /// Number of amplitudes.
+    fn len<T: Float>(amp: &Self::Amp<T>) -> usize;
+
+    /// Amplitude of the computational basis state `index`.
+    ///
+    /// Panics, if index is out of bounds.
+    fn get<T: Float>(
+        amp: &Self::Amp<T>,
+        index: usize,
+    ) -> Complex<T>;
+
+    /// Set the state to the zero state.
+    fn reset<T: Float>(
+        amp: &mut Self::Amp<T>,
+        parallel: bool,
+    );
+
+    /// Apply a gate kernel.
+    fn apply_kernel<T: Float>(
+        amp: &mut Self::Amp<T>,
+        kernel: &Kernel<T>,
+        parallel: bool,
+    );
+
+    /// Apply scheduled gates.
+    fn execute<T: Float>(
+        amp: &mut Self::Amp<T>,
+        schedule: &Schedule<T>,
+        parallel: bool,
+    );
+
+    /// Sum of squared moduli of amplitudes `range`.
+    fn norm_sqr_sum<T: Float>(
+        amp: &Self::Amp<T>,
+        range: Range<usize>,
+    ) -> T;
+
+    /// Sum of squared moduli of amplitudes `range` after applying a gate
+    /// kernel, see `gate::kernel_norm_sqr()`.
+    fn kernel_norm_sqr<T: Float>(
+        amp: &Self::Amp<T>,
+        kernel: &Kernel<T>,
+        range: Range<usize>,
+    ) -> T;
+
+    /// Zero amplitudes of basis states `i` with `i & mask !=
+    /// outcome_shifted`, and divide the rest by `norm_factor`.
+    fn project<T: Float>(
+        amp: &mut Self::Amp<T>,
+        mask: usize,
+        outcome_shifted: usize,
+        norm_factor: T,
+        parallel: bool,
+    );
+
+    /// Divide all amplitudes by `norm_factor`.
+    fn unscale<T: Float>(
+        amp: &mut Self::Amp<T>,
+        norm_factor: T,
+        parallel: bool,
+    );
+}
+
+impl Sealed for Interleaved {
+    type Amp<T: Float> = Vec<Complex<T>>;
+
+    fn zero_state<T: Float>(num_qubits: NonZeroU16) -> Self::Amp<T> {
+        let mut amp = vec![Complex::zero(); 1usize << num_qubits.get()];
+        amp[0] = Complex::from(T::one());
+        amp
+    }
+
+    fn len<T: Float>(amp: &Self::Amp<T>) -> usize {
+        amp.len()
+    }
+
+    fn get<T: Float>(
+        amp: &Self::Amp<T>,
+        index: usize,
+    ) -> Complex<T> {
+        amp[index]
+    }
+
+    fn reset<T: Float>(
+        amp: &mut Self::Amp<T>,
+        parallel: bool,
+    ) {
+        if parallel {
+            amp.par_iter_mut().for_each(|a| *a = Complex::zero());
+        } else {
+            amp.fill(Complex::zero());
+        }
+        amp[0] = Complex::from(T::one());
+    }
+
+    fn apply_kernel<T: Float>(
+        amp: &mut Self::Amp<T>,
+        kernel: &Kernel<T>,
+        parallel: bool,
+    ) {
+        if parallel {
+            gate::apply_kernel(amp, kernel);
+        } else {
+            gate::apply_kernel_seq(amp, kernel);
+        }
+    }
+
+    fn execute<T: Float>(
+        amp: &mut Self::Amp<T>,
+        schedule: &Schedule<T>,
+        parallel: bool,
+    ) {
+        schedule.execute(amp, parallel);
+    }
+
+    fn norm_sqr_sum<T: Float>(
+        amp: &Self::Amp<T>,
+        range: Range<usize>,
+    ) -> T {
+        T::norm_sqr_sum(&amp[range])
+    }
+
+    fn kernel_norm_sqr<T: Float>(
+        amp: &Self::Amp<T>,
+        kernel: &Kernel<T>,
+        range: Range<usize>,
+    ) -> T {
+        gate::kernel_norm_sqr(|i| amp[i], kernel, range)
+    }
+
+    fn project<T: Float>(
+        amp: &mut Self::Amp<T>,
+        mask: usize,
+        outcome_shifted: usize,
+        norm_factor: T,
+        parallel: bool,
+    ) {
+        let project = |(i, a): (usize, &mut Complex<T>)| {
+            if i & mask == outcome_shifted {
+                *a /= norm_factor;
+            } else {
+                *a = Complex::zero();
+            }
+        };
+        if parallel {
+            amp.par_iter_mut().enumerate().for_each(project);
+        } else {
+            amp.iter_mut().enumerate().for_each(project);
+        }
+    }
+
+    fn unscale<T: Float>(
+        amp: &mut Self::Amp<T>,
+        norm_factor: T,
+        parallel: bool,
+    ) {
+        if parallel {
+            amp.par_iter_mut().for_each(|a| *a /= norm_factor);
+        } else {
+            amp.iter_mut().for_each(|a| *a /= norm_factor);
+        }
+    }
+}
+
+impl Sealed for Split {
+    type Amp<T: Float> = soa::Parts<T>;
+
+    fn zero_state<T: Float>(num_qubits: NonZeroU16) -> Self::Amp<T> {
+        let mut re = vec![T::zero(); 1usize << num_qubits.get()];
+        re[0] = T::one();
+        soa::Parts {
+            im: vec![T::zero(); re.len()],
+            re,
+        }
+    }
+
+    fn len<T: Float>(amp: &Self::Amp<T>) -> usize {
+        amp.re.len()
+    }
+
+    fn get<T: Float>(
+        amp: &Self::Amp<T>,
+        index: usize,
+    ) -> Complex<T> {
+        Complex::new(amp.re[index], amp.im[index])
+    }
+
+    fn reset<T: Float>(
+        amp: &mut Self::Amp<T>,
+        parallel: bool,
+    ) {
+        for v in [&mut amp.re, &mut amp.im] {
+            if parallel {
+                v.par_iter_mut().for_each(|x| *x = T::zero());
+            } else {
+                v.fill(T::zero());
+            }
+        }
+        amp.re[0] = T::one();
+    }
+
+    fn apply_kernel<T: Float>(
+        amp: &mut Self::Amp<T>,
+        kernel: &Kernel<T>,
+        parallel: bool,
+    ) {
+        if parallel {
+            soa::apply_kernel(&mut amp.re, &mut amp.im, kernel);
+        } else {
+            soa::apply_kernel_seq(&mut amp.re, &mut amp.im, kernel);
+        }
+    }
+
+    fn execute<T: Float>(
+        amp: &mut Self::Amp<T>,
+        schedule: &Schedule<T>,
+        parallel: bool,
+    ) {
+        schedule.execute_split(&mut amp.re, &mut amp.im, parallel);
+    }
+
+    fn norm_sqr_sum<T: Float>(
+        amp: &Self::Amp<T>,
+        range: Range<usize>,
+    ) -> T {
+        T::norm_sqr_sum_split(&amp.re[range.clone()], &amp.im[range])
+    }
+
+    fn kernel_norm_sqr<T: Float>(
+        amp: &Self::Amp<T>,
+        kernel: &Kernel<T>,
+        range: Range<usize>,
+    ) -> T {
+        gate::kernel_norm_sqr(
+            |i| Complex::new(amp.re[i], amp.im[i]),
+            kernel,
+            range,
+        )
+    }
+
+    fn project<T: Float>(
+        amp: &mut Self::Amp<T>,
+        mask: usize,
+        outcome_shifted: usize,
+        norm_factor: T,
+        parallel: bool,
+    ) {
+        let project = |(i, x): (usize, &mut T)| {
+            if i & mask == outcome_shifted {
+                *x /= norm_factor;
+            } else {
+                *x = T::zero();
+            }
+        };
+        for v in [&mut amp.re, &mut amp.im] {
+            if parallel {
+                v.par_iter_mut().enumerate().for_each(project);
+            } else {
+                v.iter_mut().enumerate().for_each(project);
+            }
+        }
+    }
+
+    fn unscale<T: Float>(
+        amp: &mut Self::Amp<T>,
+        norm_factor: T,
+        parallel: bool,
+    ) {
+        for v in [&mut amp.re, &mut amp.im] {
+            if parallel {
+                v.par_iter_mut().for_each(|x| *x /= norm_factor);
+            } else {
+                v.iter_mut().for_each(|x| *x /= norm_factor);
+            }
+        }
+    }
+}
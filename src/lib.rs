@@ -1,4 +1,7 @@
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
 use std::{
+    fmt::Debug,
     iter,
     ops::{
         AddAssign,
@@ -10,8 +13,12 @@ use std::{
 };
 
 /// Floating point number abstraction
+///
+/// Implemented for `f32` and `f64`.  To use another floating point type,
+/// implement [`Kernels`] for it as well: its default methods are enough.
 pub trait Float:
     num::Float
+    + Debug
     + Sync
     + Send
     + AddAssign<Self>
@@ -20,17 +27,146 @@ pub trait Float:
     + DivAssign<Self>
     + RemAssign<Self>
     + iter::Sum
+    + Kernels
 {
 }
 
 impl Float for f32 {}
 impl Float for f64 {}
 
+mod backend;
+pub use backend::{
+    Backend,
+    BackendQubit,
+};
+
+mod channel;
+pub use channel::{
+    Channel,
+    ChannelError,
+};
+
+mod circuit;
+pub use circuit::{
+    Circuit,
+    Operation,
+};
+
+mod decision_diagram;
+pub use decision_diagram::DecisionDiagramSystem;
+
+mod density;
+pub use density::{
+    DensityMatrixQubit,
+    DensityMatrixSystem,
+};
+
+pub mod entanglement;
+
+mod fusion;
+
+mod gate;
+pub use gate::{
+    Gate,
+    GateKind,
+    Matrix2,
+    Matrix4,
+};
+
+pub mod hybrid;
+
+mod journal;
+pub use journal::{
+    Event,
+    EventKind,
+    Observer,
+};
+
+pub mod krylov;
+
+mod layout;
+pub use layout::{
+    Interleaved,
+    Layout,
+    Split,
+};
+
+mod lindblad;
+pub use lindblad::{
+    LindbladError,
+    Lindbladian,
+};
+
+mod linalg;
+
+pub mod metrics;
+
+mod mps;
+pub use mps::{
+    MpsError,
+    MpsQubit,
+    MpsSystem,
+};
+
+mod noise;
+pub use noise::{
+    NoiseModel,
+    NoiseModelError,
+};
+
+mod operator;
+pub use operator::{
+    LinearOperator,
+    SparseMatrix,
+    SparseMatrixError,
+};
+
+mod parallel;
+pub use parallel::Parallelism;
+
+mod pauli;
+pub use pauli::{
+    Pauli,
+    PauliString,
+    PauliSum,
+};
+
 mod qubit;
 pub use qubit::{
     Bit,
     Qubit,
 };
 
+mod readout;
+pub use readout::{
+    ReadoutError,
+    ReadoutModel,
+    ReadoutModelError,
+};
+
+mod reduce;
+
+mod schedule;
+pub use schedule::Schedule;
+
+mod simd;
+pub use simd::Kernels;
+
+mod soa;
+pub use soa::SoaSystem;
+
+mod sparse;
+pub use sparse::SparseSystem;
+
+mod stabilizer;
+pub use stabilizer::{
+    StabilizerQubit,
+    StabilizerSystem,
+};
+
 mod system;
 pub use system::System;
+
+pub mod trajectory;
+
+pub mod trotter;
@@ -0,0 +1,348 @@
+//! Dense linear algebra on small complex matrices.
+//!
+//! All matrices are stored in row-major order as flat slices of complex
+//! numbers.  The routines here are meant for reduced states and other small
+//! matrices derived from a quantum system, not for the full state vector.
+
+use num::{
+    Complex,
+    One,
+    Zero,
+};
+
+use crate::Float;
+
+/// Maximal number of Jacobi sweeps before giving up on convergence.
+const MAX_SWEEPS: usize = 100;
+
+/// Singular value decomposition: `A = U * diag(s) * V^dagger`.
+pub(crate) struct Svd<T> {
+    /// Left singular vectors, `m x k` matrix, where `k = min(m, n)`.
+    pub u: Vec<Complex<T>>,
+    /// Singular values in non-increasing order.
+    pub s: Vec<T>,
+    /// Right singular vectors, `n x k` matrix.
+    pub v: Vec<Complex<T>>,
+}
+
+/// Compute the Jacobi rotation that annihilates the off-diagonal element of
+/// the Hermitian matrix `[[app, apq], [apq*, aqq]]`.
+///
+/// Returns the entries `(j_pp, j_pq, j_qp, j_qq)` of the unitary rotation `J`,
+/// such that `J^dagger A J` is diagonal in the `(p, q)` block.
+fn jacobi_rotation<T>(
+    app: T,
+    aqq: T,
+    apq: Complex<T>,
+) -> (Complex<T>, Complex<T>, Complex<T>, Complex<T>)
+where
+    T: Float,
+{
+    let abs = apq.norm();
+    let phase = apq / abs;
+    let theta = (aqq - app) / (abs + abs);
+    let t = theta.signum() / (theta.abs() + (theta * theta + T::one()).sqrt());
+    let c = (t * t + T::one()).sqrt().recip();
+    let s = t * c;
+
+    (
+        Complex::from(c),
+        Complex::from(s),
+        -phase.conj() * s,
+        phase.conj() * c,
+    )
+}
+
+/// Eigendecomposition of a Hermitian `n x n` matrix.
+///
+/// Uses cyclic complex Jacobi rotations.  Returns eigenvalues in
+/// non-decreasing order together with the matrix whose columns are the
+/// corresponding orthonormal eigenvectors.
+pub(crate) fn eigh<T>(
+    mat: &[Complex<T>],
+    n: usize,
+) -> (Vec<T>, Vec<Complex<T>>)
+where
+    T: Float,
+{
+    debug_assert_eq!(mat.len(), n * n);
+    let mut a = mat.to_vec();
+    let mut v = identity(n);
+    let eps = T::epsilon();
+
+    for _ in 0..MAX_SWEEPS {
+        let off = (0..n)
+            .flat_map(|p| (0..n).filter(move |&q| q != p).map(move |q| (p, q)))
+            .map(|(p, q)| a[p * n + q].norm_sqr())
+            .sum::<T>();
+        let diag = (0..n).map(|p| a[p * n + p].norm_sqr()).sum::<T>();
+        if off <= eps * eps * diag || off < T::min_positive_value() {
+            break;
+        }
+
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = a[p * n + q];
+                if apq.norm() <= T::min_positive_value() {
+                    continue;
+                }
+                let (jpp, jpq, jqp, jqq) =
+                    jacobi_rotation(a[p * n + p].re, a[q * n + q].re, apq);
+                // A <- A J
+                for k in 0..n {
+                    let (akp, akq) = (a[k * n + p], a[k * n + q]);
+                    a[k * n + p] = akp * jpp + akq * jqp;
+                    a[k * n + q] = akp * jpq + akq * jqq;
+                }
+                // A <- J^dagger A
+                for k in 0..n {
+                    let (apk, aqk) = (a[p * n + k], a[q * n + k]);
+                    a[p * n + k] = jpp.conj() * apk + jqp.conj() * aqk;
+                    a[q * n + k] = jpq.conj() * apk + jqq.conj() * aqk;
+                }
+                // V <- V J
+                for k in 0..n {
+                    let (vkp, vkq) = (v[k * n + p], v[k * n + q]);
+                    v[k * n + p] = vkp * jpp + vkq * jqp;
+                    v[k * n + q] = vkp * jpq + vkq * jqq;
+                }
+            }
+        }
+    }
+
+    let mut order: Vec<_> = (0..n).collect();
+    order.sort_by(|&i, &j| {
+        a[i * n + i].re.partial_cmp(&a[j * n + j].re).unwrap()
+    });
+    let values = order.iter().map(|&i| a[i * n + i].re).collect();
+    let mut vectors = vec![Complex::zero(); n * n];
+    for (col, &i) in order.iter().enumerate() {
+        for k in 0..n {
+            vectors[k * n + col] = v[k * n + i];
+        }
+    }
+    (values, vectors)
+}
+
+/// Singular value decomposition of an `m x n` matrix.
+///
+/// Uses one-sided (Hestenes) Jacobi rotations.
+pub(crate) fn svd<T>(
+    mat: &[Complex<T>],
+    m: usize,
+    n: usize,
+) -> Svd<T>
+where
+    T: Float,
+{
+    debug_assert_eq!(mat.len(), m * n);
+    if m < n {
+        let Svd {
+            u,
+            s,
+            v,
+        } = svd(&adjoint(mat, m, n), n, m);
+        return Svd {
+            u: v,
+            s,
+            v: u,
+        };
+    }
+
+    let mut a = mat.to_vec();
+    let mut v = identity(n);
+    let eps = T::epsilon();
+
+    for _ in 0..MAX_SWEEPS {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let (mut alpha, mut beta) = (T::zero(), T::zero());
+                let mut gamma = Complex::zero();
+                for k in 0..m {
+                    let (akp, akq) = (a[k * n + p], a[k * n + q]);
+                    alpha += akp.norm_sqr();
+                    beta += akq.norm_sqr();
+                    gamma += akp.conj() * akq;
+                }
+                if gamma.norm() <= eps * (alpha * beta).sqrt()
+                    || gamma.norm() <= T::min_positive_value()
+                {
+                    continue;
+                }
+                rotated = true;
+                let (jpp, jpq, jqp, jqq) = jacobi_rotation(alpha, beta, gamma);
+                for k in 0..m {
+                    let (akp, akq) = (a[k * n + p], a[k * n + q]);
+                    a[k * n + p] = akp * jpp + akq * jqp;
+                    a[k * n + q] = akp * jpq + akq * jqq;
+                }
+                for k in 0..n {
+                    let (vkp, vkq) = (v[k * n + p], v[k * n + q]);
+                    v[k * n + p] = vkp * jpp + vkq * jqp;
+                    v[k * n + q] = vkp * jpq + vkq * jqq;
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    let norms: Vec<T> = (0..n)
+        .map(|j| (0..m).map(|k| a[k * n + j].norm_sqr()).sum::<T>().sqrt())
+        .collect();
+    let mut order: Vec<_> = (0..n).collect();
+    order.sort_by(|&i, &j| norms[j].partial_cmp(&norms[i]).unwrap());
+
+    let mut u = vec![Complex::zero(); m * n];
+    let mut vs = vec![Complex::zero(); n * n];
+    for (col, &j) in order.iter().enumerate() {
+        if norms[j] > T::min_positive_value() {
+            for k in 0..m {
+                u[k * n + col] = a[k * n + j] / norms[j];
+            }
+        }
+        for k in 0..n {
+            vs[k * n + col] = v[k * n + j];
+        }
+    }
+    Svd {
+        u,
+        s: order.iter().map(|&j| norms[j]).collect(),
+        v: vs,
+    }
+}
+
+/// Identity `n x n` matrix.
+pub(crate) fn identity<T>(n: usize) -> Vec<Complex<T>>
+where
+    T: Float,
+{
+    let mut id = vec![Complex::zero(); n * n];
+    for i in 0..n {
+        id[i * n + i] = Complex::one();
+    }
+    id
+}
+
+/// Conjugate transpose of an `m x n` matrix.
+pub(crate) fn adjoint<T>(
+    mat: &[Complex<T>],
+    m: usize,
+    n: usize,
+) -> Vec<Complex<T>>
+where
+    T: Float,
+{
+    let mut adj = vec![Complex::zero(); m * n];
+    for i in 0..m {
+        for j in 0..n {
+            adj[j * m + i] = mat[i * n + j].conj();
+        }
+    }
+    adj
+}
+
+/// Product of an `m x k` and a `k x n` matrix.
+pub(crate) fn matmul<T>(
+    a: &[Complex<T>],
+    b: &[Complex<T>],
+    m: usize,
+    k: usize,
+    n: usize,
+) -> Vec<Complex<T>>
+where
+    T: Float,
+{
+    let mut c = vec![Complex::zero(); m * n];
+    for i in 0..m {
+        for l in 0..k {
+            let ail = a[i * k + l];
+            if ail.is_zero() {
+                continue;
+            }
+            for j in 0..n {
+                c[i * n + j] += ail * b[l * n + j];
+            }
+        }
+    }
+    c
+}
+
+/// Apply a real function to the eigenvalues of a Hermitian matrix.
+pub(crate) fn hermitian_map<T, F>(
+    mat: &[Complex<T>],
+    n: usize,
+    f: F,
+) -> Vec<Complex<T>>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let (values, vectors) = eigh(mat, n);
+    let mut res = vec![Complex::zero(); n * n];
+    for (col, &lambda) in values.iter().enumerate() {
+        let fl = f(lambda);
+        for i in 0..n {
+            let vi = vectors[i * n + col] * fl;
+            for j in 0..n {
+                res[i * n + j] += vi * vectors[j * n + col].conj();
+            }
+        }
+    }
+    res
+}
+
+/// Inverse of an `n x n` matrix.
+///
+/// Uses Gauss-Jordan elimination with partial pivoting.  Returns `None`, if
+/// the matrix is singular.
+pub(crate) fn inverse<T>(
+    mat: &[Complex<T>],
+    n: usize,
+) -> Option<Vec<Complex<T>>>
+where
+    T: Float,
+{
+    debug_assert_eq!(mat.len(), n * n);
+    let mut a = mat.to_vec();
+    let mut inv = identity(n);
+    let scale = a.iter().map(|z| z.norm()).fold(T::zero(), T::max);
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| {
+                a[i * n + col]
+                    .norm()
+                    .partial_cmp(&a[j * n + col].norm())
+                    .unwrap()
+            })
+            .unwrap();
+        if a[pivot * n + col].norm() <= scale * T::epsilon() {
+            return None;
+        }
+        for k in 0..n {
+            a.swap(col * n + k, pivot * n + k);
+            inv.swap(col * n + k, pivot * n + k);
+        }
+
+        let p = a[col * n + col];
+        for k in 0..n {
+            a[col * n + k] /= p;
+            inv[col * n + k] /= p;
+        }
+        for row in (0..n).filter(|&r| r != col) {
+            let f = a[row * n + col];
+            if f.is_zero() {
+                continue;
+            }
+            for k in 0..n {
+                let (x, y) = (a[col * n + k], inv[col * n + k]);
+                a[row * n + k] -= f * x;
+                inv[row * n + k] -= f * y;
+            }
+        }
+    }
+    Some(inv)
+}
@@ -0,0 +1,380 @@
+use std::fmt;
+
+use num::{
+    Complex,
+    One,
+    Zero,
+};
+use rayon::prelude::{
+    IndexedParallelIterator,
+    IntoParallelRefMutIterator,
+    ParallelIterator,
+};
+
+use crate::{
+    density,
+    gate::{
+        self,
+        Kernel,
+    },
+    Float,
+    Matrix2,
+    PauliSum,
+};
+
+/// Error type returned when building a Lindbladian or integrating the master
+/// equation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LindbladError {
+    /// Rate is negative or not finite.
+    InvalidRate,
+    /// Evolution time is negative or not finite.
+    InvalidTime,
+    /// Tolerance is not positive or not finite.
+    InvalidTolerance,
+    /// Integrator could not reach the requested tolerance.
+    StepSizeUnderflow,
+}
+
+impl fmt::Display for LindbladError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::InvalidRate => write!(f, "invalid rate"),
+            Self::InvalidTime => write!(f, "invalid evolution time"),
+            Self::InvalidTolerance => write!(f, "invalid tolerance"),
+            Self::StepSizeUnderflow => write!(f, "step size underflow"),
+        }
+    }
+}
+
+impl std::error::Error for LindbladError {}
+
+/// Generator of Markovian open-system dynamics.
+///
+/// The density matrix evolves according to the Lindblad master equation:
+///
+/// ```text
+/// d rho / dt = -i [H, rho] + sum_k (L_k rho L_k^dagger
+///                 - 1/2 {L_k^dagger L_k, rho})
+/// ```
+///
+/// where `H` is a Hamiltonian given as a [`PauliSum`] and `L_k` are
+/// single-qubit jump operators.  Rates are absorbed into jump operators.
+///
+/// See [`DensityMatrixSystem::evolve()`] for integrating the equation.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{DensityMatrixSystem, Gate, Lindbladian, PauliSum};
+/// let num_qubits = NonZeroU16::new(1).unwrap();
+/// let mut lindbladian = Lindbladian::new(PauliSum::new());
+/// lindbladian.add_decay(0, 0.5).unwrap();
+///
+/// let mut stm: DensityMatrixSystem<f64> =
+///     DensityMatrixSystem::new(num_qubits, 123);
+/// stm.apply(&Gate::X(0));
+/// stm.evolve(&lindbladian, 2., 1e-10).unwrap();
+///
+/// let p = stm.probability(0).unwrap();
+/// assert!((p - (-1.0f64).exp()).abs() < 1e-8);
+/// ```
+///
+/// [`DensityMatrixSystem::evolve()`]: crate::DensityMatrixSystem::evolve
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lindbladian<T> {
+    hamiltonian: PauliSum<T>,
+    jumps:       Vec<(u16, Matrix2<T>)>,
+}
+
+impl<T> Lindbladian<T>
+where
+    T: Float,
+{
+    /// Closed-system dynamics with the Hamiltonian `hamiltonian`.
+    #[must_use]
+    pub fn new(hamiltonian: PauliSum<T>) -> Self {
+        Self {
+            hamiltonian,
+            jumps: Vec::new(),
+        }
+    }
+
+    /// Get the Hamiltonian.
+    #[must_use]
+    pub fn hamiltonian(&self) -> &PauliSum<T> {
+        &self.hamiltonian
+    }
+
+    /// Get jump operators together with qubits they act on.
+    #[must_use]
+    pub fn jumps(&self) -> &[(u16, Matrix2<T>)] {
+        &self.jumps
+    }
+
+    /// Add jump operator `op` acting on qubit `index`.
+    pub fn add_jump(
+        &mut self,
+        index: u16,
+        op: Matrix2<T>,
+    ) {
+        self.jumps.push((index, op));
+    }
+
+    /// Add energy relaxation of qubit `index` with rate `gamma`, i.e. the
+    /// jump operator `sqrt(gamma) |0><1|`.
+    ///
+    /// The population of the excited state decays as `exp(-gamma t)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if `gamma` is negative or not finite.
+    pub fn add_decay(
+        &mut self,
+        index: u16,
+        gamma: T,
+    ) -> Result<(), LindbladError> {
+        let a = Self::amplitude(gamma)?;
+        let zero = Complex::zero();
+        self.add_jump(index, [[zero, a], [zero, zero]]);
+        Ok(())
+    }
+
+    /// Add pure dephasing of qubit `index` with rate `gamma`, i.e. the jump
+    /// operator `sqrt(gamma / 2) Z`.
+    ///
+    /// Off-diagonal elements of the qubit decay as `exp(-gamma t)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if `gamma` is negative or not finite.
+    pub fn add_dephasing(
+        &mut self,
+        index: u16,
+        gamma: T,
+    ) -> Result<(), LindbladError> {
+        let a = Self::amplitude(gamma / T::from(2.).unwrap())?;
+        let zero = Complex::zero();
+        self.add_jump(index, [[a, zero], [zero, -a]]);
+        Ok(())
+    }
+
+    fn amplitude(rate: T) -> Result<Complex<T>, LindbladError> {
+        if !rate.is_finite() || rate < T::zero() {
+            return Err(LindbladError::InvalidRate);
+        }
+        Ok(Complex::from(rate.sqrt()))
+    }
+
+    /// Smallest number of qubits of a system the generator can act on.
+    #[must_use]
+    pub fn num_qubits(&self) -> u16 {
+        self.jumps
+            .iter()
+            .map(|(k, _)| k + 1)
+            .max()
+            .unwrap_or(0)
+            .max(self.hamiltonian.num_qubits())
+    }
+
+    /// Compute `d rho / dt` for the density matrix `rho` of `n` qubits.
+    pub(crate) fn derivative(
+        &self,
+        rho: &[Complex<T>],
+        n: u16,
+        out: &mut [Complex<T>],
+    ) {
+        out.par_iter_mut().for_each(|x| *x = Complex::zero());
+
+        // -i [H, rho]
+        let i = Complex::i();
+        self.hamiltonian.mul_add(-i, rho, out, n);
+        self.hamiltonian.mul_add_right(i, rho, out);
+
+        let half = Complex::from(T::from(0.5).unwrap());
+        for &(k, op) in &self.jumps {
+            let mut tmp = rho.to_vec();
+            density::conjugate(&mut tmp, n, &Kernel::One(k, op));
+            add(out, &tmp, Complex::one());
+
+            // L^dagger L is Hermitian, hence multiplying from the right
+            // amounts to applying its complex conjugate to column bits.
+            let m = mul_adjoint(&op);
+            let mut tmp = rho.to_vec();
+            gate::apply_one(&mut tmp, k + n, &m);
+            add(out, &tmp, -half);
+            let mut tmp = rho.to_vec();
+            gate::apply_one(&mut tmp, k, &gate::conj2(&m));
+            add(out, &tmp, -half);
+        }
+    }
+}
+
+/// Compute `L^dagger L`.
+fn mul_adjoint<T>(l: &Matrix2<T>) -> Matrix2<T>
+where
+    T: Float,
+{
+    let mut m = [[Complex::zero(); 2]; 2];
+    for (r, row) in m.iter_mut().enumerate() {
+        for (c, x) in row.iter_mut().enumerate() {
+            *x = l[0][r].conj() * l[0][c] + l[1][r].conj() * l[1][c];
+        }
+    }
+    m
+}
+
+/// Compute `dst += scale * src`.
+fn add<T>(
+    dst: &mut [Complex<T>],
+    src: &[Complex<T>],
+    scale: Complex<T>,
+) where
+    T: Float,
+{
+    dst.par_iter_mut()
+        .zip(src)
+        .for_each(|(d, s)| *d += scale * s);
+}
+
+// Dormand–Prince 5(4) Butcher tableau.  The equation is autonomous, hence
+// the nodes are not needed.
+const A: [&[f64]; 7] = [
+    &[],
+    &[1. / 5.],
+    &[3. / 40., 9. / 40.],
+    &[44. / 45., -56. / 15., 32. / 9.],
+    &[
+        19372. / 6561.,
+        -25360. / 2187.,
+        64448. / 6561.,
+        -212. / 729.,
+    ],
+    &[
+        9017. / 3168.,
+        -355. / 33.,
+        46732. / 5247.,
+        49. / 176.,
+        -5103. / 18656.,
+    ],
+    &[
+        35. / 384.,
+        0.,
+        500. / 1113.,
+        125. / 192.,
+        -2187. / 6784.,
+        11. / 84.,
+    ],
+];
+// Weights of the 5th-order solution are the last row of A.  These are the
+// differences between 5th- and 4th-order weights.
+const E: [f64; 7] = [
+    71. / 57600.,
+    0.,
+    -71. / 16695.,
+    71. / 1920.,
+    -17253. / 339200.,
+    22. / 525.,
+    -1. / 40.,
+];
+
+/// Integrate the master equation for the time `time`, with the adaptive
+/// Dormand–Prince 5(4) method.
+///
+/// The step size is chosen so that the local error estimate, measured as the
+/// largest absolute deviation of a density matrix entry, stays below
+/// `tolerance`.  Returns the number of accepted steps.
+pub(crate) fn integrate<T>(
+    lindbladian: &Lindbladian<T>,
+    rho: &mut [Complex<T>],
+    n: u16,
+    time: T,
+    tolerance: T,
+) -> Result<usize, LindbladError>
+where
+    T: Float,
+{
+    if !time.is_finite() || time < T::zero() {
+        return Err(LindbladError::InvalidTime);
+    }
+    if !tolerance.is_finite() || tolerance <= T::zero() {
+        return Err(LindbladError::InvalidTolerance);
+    }
+    if time == T::zero() {
+        return Ok(0);
+    }
+
+    let f = |x: f64| T::from(x).unwrap();
+    let len = rho.len();
+    let mut k = vec![vec![Complex::zero(); len]; 7];
+    let mut stage = vec![Complex::zero(); len];
+
+    // Initial step from the rough rate of change of the state.
+    let rate = lindbladian.hamiltonian.norm_bound()
+        + lindbladian
+            .jumps
+            .iter()
+            .map(|(_, l)| l.iter().flatten().map(|z| z.norm_sqr()).sum::<T>())
+            .sum::<T>();
+    let mut h = if rate > T::zero() {
+        (f(0.1) / rate).min(time)
+    } else {
+        time
+    };
+    let min_step = time * T::epsilon();
+
+    let mut t = T::zero();
+    let mut steps = 0;
+    lindbladian.derivative(rho, n, &mut k[0]);
+    while t < time {
+        // The last step ends exactly at `time`: `t + (time - t)` can round
+        // down, leaving a step too small to take.
+        let last = h >= time - t;
+        if last {
+            h = time - t;
+        }
+        if h <= min_step {
+            return Err(LindbladError::StepSizeUnderflow);
+        }
+
+        for (s, row) in A.iter().enumerate().skip(1) {
+            stage.copy_from_slice(rho);
+            for (a, ki) in row.iter().zip(&k) {
+                add(&mut stage, ki, Complex::from(h * f(*a)));
+            }
+            let (_, rest) = k.split_at_mut(s);
+            lindbladian.derivative(&stage, n, &mut rest[0]);
+        }
+
+        // The last stage was evaluated at the 5th-order solution.
+        let err = (0..len)
+            .map(|j| {
+                k.iter()
+                    .zip(E)
+                    .map(|(ki, e)| ki[j] * f(e))
+                    .sum::<Complex<T>>()
+                    .norm()
+                    * h
+            })
+            .fold(T::zero(), T::max);
+
+        if err <= tolerance {
+            rho.copy_from_slice(&stage);
+            k.swap(0, 6);
+            t = if last { time } else { t + h };
+            steps += 1;
+        }
+
+        let factor = if err > T::zero() {
+            f(0.9) * (tolerance / err).powf(f(0.2))
+        } else {
+            f(5.)
+        };
+        h *= factor.max(f(0.2)).min(f(5.));
+    }
+    Ok(steps)
+}
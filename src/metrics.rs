@@ -0,0 +1,157 @@
+//! Overlap, fidelity and distance between pure states.
+//!
+//! States can be given either as a [`System`] or as a raw slice of complex
+//! amplitudes: anything that implements `AsRef<[Complex<T>]>`.  Functions
+//! comparing two states return `None`, if the states have different
+//! dimensions.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use std::num::NonZeroU16;
+//! # use num::Complex;
+//! # use qn::{metrics, System};
+//! let num_qubits = NonZeroU16::new(1).unwrap();
+//! let mut stm: System<f64> = System::new(num_qubits, 123);
+//! let other: System<f64> = System::new(num_qubits, 456);
+//!
+//! stm.as_mut_slice()[0] = Complex::new(0., 1.);
+//! assert!(metrics::approx_eq_up_to_phase(&stm, &other, 1e-12));
+//! assert!(metrics::approx_eq_up_to_phase(
+//!     &stm,
+//!     &[Complex::from(-1.), Complex::from(0.)],
+//!     1e-12
+//! ));
+//! ```
+//!
+//! [`System`]: crate::System
+
+use num::{
+    Complex,
+    Zero,
+};
+use rayon::prelude::{
+    IndexedParallelIterator,
+    IntoParallelRefIterator,
+    ParallelIterator,
+};
+
+use crate::Float;
+
+/// Inner product `<phi|psi>`.
+///
+/// Returns `None`, if the states have different dimensions.
+#[must_use]
+pub fn inner_product<T, A, B>(
+    phi: &A,
+    psi: &B,
+) -> Option<Complex<T>>
+where
+    T: Float,
+    A: AsRef<[Complex<T>]> + ?Sized,
+    B: AsRef<[Complex<T>]> + ?Sized,
+{
+    let (phi, psi) = (phi.as_ref(), psi.as_ref());
+    if phi.len() != psi.len() {
+        return None;
+    }
+    Some(
+        phi.par_iter()
+            .zip(psi)
+            .map(|(a, b)| a.conj() * b)
+            .fold(Complex::zero, |acc, x| acc + x)
+            .reduce(Complex::zero, |acc, x| acc + x),
+    )
+}
+
+/// Fidelity `|<phi|psi>|^2` between two pure states.
+///
+/// Returns `None`, if the states have different dimensions.
+#[must_use]
+pub fn fidelity<T, A, B>(
+    phi: &A,
+    psi: &B,
+) -> Option<T>
+where
+    T: Float,
+    A: AsRef<[Complex<T>]> + ?Sized,
+    B: AsRef<[Complex<T>]> + ?Sized,
+{
+    inner_product(phi, psi).map(|z| z.norm_sqr())
+}
+
+/// Trace distance between two normalized pure states: `sqrt(1 - F)`, where
+/// `F` is the fidelity.
+///
+/// Returns `None`, if the states have different dimensions.
+#[must_use]
+pub fn trace_distance<T, A, B>(
+    phi: &A,
+    psi: &B,
+) -> Option<T>
+where
+    T: Float,
+    A: AsRef<[Complex<T>]> + ?Sized,
+    B: AsRef<[Complex<T>]> + ?Sized,
+{
+    fidelity(phi, psi).map(|f| (T::one() - f).max(T::zero()).sqrt())
+}
+
+/// Maximal absolute difference between corresponding amplitudes.
+///
+/// This comparison is sensitive to the global phase.
+///
+/// Returns `None`, if the states have different dimensions.
+#[must_use]
+pub fn max_error<T, A, B>(
+    phi: &A,
+    psi: &B,
+) -> Option<T>
+where
+    T: Float,
+    A: AsRef<[Complex<T>]> + ?Sized,
+    B: AsRef<[Complex<T>]> + ?Sized,
+{
+    let (phi, psi) = (phi.as_ref(), psi.as_ref());
+    if phi.len() != psi.len() {
+        return None;
+    }
+    Some(
+        phi.par_iter()
+            .zip(psi)
+            .map(|(a, b)| (a - b).norm())
+            .reduce(T::zero, T::max),
+    )
+}
+
+/// Check if two states are equal up to a global phase.
+///
+/// States are considered equal, if there is a phase `e^{i theta}` such that
+/// all amplitudes of `psi` differ from the corresponding amplitudes of
+/// `e^{i theta} phi` by at most `tolerance` in absolute value.
+///
+/// Returns `false`, if the states have different dimensions.
+#[must_use]
+pub fn approx_eq_up_to_phase<T, A, B>(
+    phi: &A,
+    psi: &B,
+    tolerance: T,
+) -> bool
+where
+    T: Float,
+    A: AsRef<[Complex<T>]> + ?Sized,
+    B: AsRef<[Complex<T>]> + ?Sized,
+{
+    let (phi, psi) = (phi.as_ref(), psi.as_ref());
+    let Some(overlap) = inner_product(phi, psi) else {
+        return false;
+    };
+    let phase = if overlap.norm() > T::zero() {
+        overlap / overlap.norm()
+    } else {
+        Complex::from(T::one())
+    };
+    phi.par_iter()
+        .zip(psi)
+        .all(|(a, b)| (a * phase - b).norm() <= tolerance)
+}
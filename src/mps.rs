@@ -0,0 +1,604 @@
+use std::{
+    fmt,
+    num::{
+        NonZeroU16,
+        NonZeroUsize,
+    },
+};
+
+use num::{
+    Complex,
+    One,
+    Zero,
+};
+use rand::{
+    Rng,
+    SeedableRng,
+};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    gate::Kernel,
+    linalg,
+    BackendQubit,
+    Bit,
+    Float,
+    Gate,
+    Matrix2,
+    Matrix4,
+};
+
+/// Error type returned when configuring truncation of a matrix product
+/// state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MpsError {
+    /// Truncation threshold is not in the interval `[0, 1)`.
+    InvalidThreshold,
+}
+
+impl fmt::Display for MpsError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::InvalidThreshold => write!(f, "invalid truncation threshold"),
+        }
+    }
+}
+
+impl std::error::Error for MpsError {}
+
+/// Quantum system of qubits in a pure state, represented as a matrix product
+/// state (MPS).
+///
+/// Qubit `k` corresponds to the tensor `A_k` with indices `[l][s][r]`: left
+/// bond, physical index and right bond.  The amplitude of a basis state is
+/// the product of matrices `A_0^{s_0} A_1^{s_1} ...`.  Memory grows
+/// linearly with the number of qubits and quadratically with the bond
+/// dimension, which is bounded by the entanglement of the state.
+///
+/// Two-qubit gates on neighbouring qubits are applied by contracting both
+/// tensors and splitting them again with singular value decomposition,
+/// keeping the state in mixed canonical form.  Gates on distant qubits are
+/// routed with SWAP gates.  When splitting, the smallest singular values are
+/// discarded as long as their total weight does not exceed the truncation
+/// threshold, and at most `max_bond_dim` of them are kept.  The discarded
+/// weight accumulates in [`truncation_error()`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Bit, Gate, MpsSystem};
+/// let num_qubits = NonZeroU16::new(100).unwrap();
+/// let mut stm: MpsSystem<f64> = MpsSystem::new(num_qubits, 123);
+///
+/// // GHZ state: bond dimension 2.
+/// stm.apply(&Gate::H(0));
+/// for k in 1..100 {
+///     stm.apply(&Gate::CNOT(k - 1, k));
+/// }
+/// assert!(stm.bond_dims().iter().all(|&d| d == 2));
+///
+/// let outcome = stm.qubit(0).unwrap().measure();
+/// assert_eq!(stm.qubit(99).unwrap().measure(), outcome);
+/// assert_eq!(stm.truncation_error(), 0.);
+/// ```
+///
+/// [`truncation_error()`]: MpsSystem::truncation_error
+pub struct MpsSystem<T>
+where
+    T: Float,
+{
+    rng:              ChaCha8Rng,
+    num_qubits:       NonZeroU16,
+    tensors:          Vec<Vec<Complex<T>>>,
+    // Bond dimensions: bonds[k] is the left bond of tensor k.  The first
+    // and the last bond have dimension 1.
+    bonds:            Vec<usize>,
+    // Orthogonality center: tensors to the left are left-isometric, tensors
+    // to the right are right-isometric.
+    center:           usize,
+    max_bond_dim:     Option<NonZeroUsize>,
+    threshold:        T,
+    truncation_error: T,
+}
+
+impl<T> MpsSystem<T>
+where
+    T: Float,
+{
+    /// Initialize a new quantum system of `n` qubits in the zero state.
+    ///
+    /// Seed internal RNG with `seed`.  No truncation is performed, until
+    /// configured with [`set_max_bond_dim()`] or
+    /// [`set_truncation_threshold()`].
+    ///
+    /// [`set_max_bond_dim()`]: MpsSystem::set_max_bond_dim
+    /// [`set_truncation_threshold()`]: MpsSystem::set_truncation_threshold
+    #[must_use]
+    pub fn new(
+        num_qubits: NonZeroU16,
+        seed: u64,
+    ) -> Self {
+        let mut stm = Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            num_qubits,
+            tensors: Vec::new(),
+            bonds: Vec::new(),
+            center: 0,
+            max_bond_dim: None,
+            threshold: T::zero(),
+            truncation_error: T::zero(),
+        };
+        stm.reset();
+        stm
+    }
+
+    /// Reset all qubits to the zero state and the truncation error to zero.
+    ///
+    /// The RNG and the truncation settings are not affected.
+    pub fn reset(&mut self) {
+        let n = usize::from(self.num_qubits.get());
+        let zero_state = vec![Complex::one(), Complex::zero()];
+        self.tensors = vec![zero_state; n];
+        self.bonds = vec![1; n + 1];
+        self.center = 0;
+        self.truncation_error = T::zero();
+    }
+
+    /// Get the number of qubits.
+    #[must_use]
+    pub fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits
+    }
+
+    /// Set the maximal bond dimension.  Use `None` for no limit.
+    ///
+    /// Affects only gates applied afterwards.
+    pub fn set_max_bond_dim(
+        &mut self,
+        dim: Option<NonZeroUsize>,
+    ) {
+        self.max_bond_dim = dim;
+    }
+
+    /// Get the maximal bond dimension.
+    #[must_use]
+    pub fn max_bond_dim(&self) -> Option<NonZeroUsize> {
+        self.max_bond_dim
+    }
+
+    /// Set the truncation threshold: the largest total weight of singular
+    /// values, relative to the norm of the state, discarded by a single
+    /// two-qubit gate.
+    ///
+    /// Affects only gates applied afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if `threshold` is not in the interval `[0, 1)`.
+    pub fn set_truncation_threshold(
+        &mut self,
+        threshold: T,
+    ) -> Result<(), MpsError> {
+        if !(threshold >= T::zero() && threshold < T::one()) {
+            return Err(MpsError::InvalidThreshold);
+        }
+        self.threshold = threshold;
+        Ok(())
+    }
+
+    /// Get the truncation threshold.
+    #[must_use]
+    pub fn truncation_threshold(&self) -> T {
+        self.threshold
+    }
+
+    /// Total weight of singular values discarded so far.
+    ///
+    /// To first order, this is the infidelity `1 - |<psi|psi_exact>|^2`
+    /// between the state and the state that would be obtained without
+    /// truncation.
+    #[must_use]
+    pub fn truncation_error(&self) -> T {
+        self.truncation_error
+    }
+
+    /// Dimensions of the bonds between neighbouring qubits.
+    #[must_use]
+    pub fn bond_dims(&self) -> Vec<usize> {
+        self.bonds[1..self.bonds.len() - 1].to_vec()
+    }
+
+    /// Apply a gate to the system.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the gate is not valid for this system, see
+    /// [`Gate::is_valid()`].
+    pub fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        assert!(
+            gate.is_valid(self.num_qubits.get()),
+            "invalid gate: {gate:?}"
+        );
+        match gate.kernel() {
+            Kernel::One(k, m) => self.apply_one(k.into(), &m),
+            Kernel::Two(q0, q1, m) => {
+                let (q0, q1) = (usize::from(q0), usize::from(q1));
+                // Bring the qubits next to each other, keeping the order.
+                let (lo, hi) = (q0.min(q1), q0.max(q1));
+                for k in (lo + 1..hi).rev() {
+                    self.apply_swap(k);
+                }
+                let m = if q0 < q1 {
+                    m
+                } else {
+                    const PERM: [usize; 4] = [0, 2, 1, 3];
+                    PERM.map(|r| PERM.map(|c| m[r][c]))
+                };
+                self.apply_two(lo, &m);
+                for k in lo + 1..hi {
+                    self.apply_swap(k);
+                }
+            }
+        }
+    }
+
+    fn apply_one(
+        &mut self,
+        k: usize,
+        m: &Matrix2<T>,
+    ) {
+        let r_dim = self.bonds[k + 1];
+        let tensor = &mut self.tensors[k];
+        for l in 0..self.bonds[k] {
+            for r in 0..r_dim {
+                let (i0, i1) = (2 * l * r_dim + r, (2 * l + 1) * r_dim + r);
+                let (a0, a1) = (tensor[i0], tensor[i1]);
+                tensor[i0] = m[0][0] * a0 + m[0][1] * a1;
+                tensor[i1] = m[1][0] * a0 + m[1][1] * a1;
+            }
+        }
+    }
+
+    /// Swap qubits `k` and `k + 1`.
+    fn apply_swap(
+        &mut self,
+        k: usize,
+    ) {
+        let (zero, one) = (Complex::zero(), Complex::one());
+        let swap = [
+            [one, zero, zero, zero],
+            [zero, zero, one, zero],
+            [zero, one, zero, zero],
+            [zero, zero, zero, one],
+        ];
+        self.apply_two(k, &swap);
+    }
+
+    /// Apply two-qubit operator `m` to qubits `(k, k + 1)`.
+    fn apply_two(
+        &mut self,
+        k: usize,
+        m: &Matrix4<T>,
+    ) {
+        self.move_center(k);
+        let (l_dim, b_dim, r_dim) =
+            (self.bonds[k], self.bonds[k + 1], self.bonds[k + 2]);
+
+        // theta[l][s0][s1][r] = sum_b A_k[l][s0][b] A_{k+1}[b][s1][r],
+        // then the gate acts on the index s0 + 2 * s1.
+        let (a, b) = (&self.tensors[k], &self.tensors[k + 1]);
+        let mut theta = vec![Complex::zero(); l_dim * 4 * r_dim];
+        for l in 0..l_dim {
+            for r in 0..r_dim {
+                let mut x = [Complex::zero(); 4];
+                for (s, x) in x.iter_mut().enumerate() {
+                    let (s0, s1) = (s & 1, s >> 1);
+                    for c in 0..b_dim {
+                        *x += a[(l * 2 + s0) * b_dim + c]
+                            * b[(c * 2 + s1) * r_dim + r];
+                    }
+                }
+                for (s, row) in m.iter().enumerate() {
+                    let (s0, s1) = (s & 1, s >> 1);
+                    theta[((l * 2 + s0) * 2 + s1) * r_dim + r] =
+                        row.iter().zip(&x).map(|(g, x)| g * x).sum();
+                }
+            }
+        }
+
+        // Split as an (l, s0) x (s1, r) matrix.
+        let svd = linalg::svd(&theta, l_dim * 2, 2 * r_dim);
+        let keep = self.truncate(&svd.s);
+        let (rows, cols) = (l_dim * 2, 2 * r_dim);
+        let width = rows.min(cols);
+
+        let kept: T = svd.s[..keep].iter().map(|s| *s * *s).sum();
+        let scale = kept.sqrt().recip();
+        let mut left = vec![Complex::zero(); rows * keep];
+        for i in 0..rows {
+            for j in 0..keep {
+                left[i * keep + j] = svd.u[i * width + j];
+            }
+        }
+        let mut right = vec![Complex::zero(); keep * cols];
+        for j in 0..keep {
+            for c in 0..cols {
+                right[j * cols + c] =
+                    svd.v[c * width + j].conj() * (svd.s[j] * scale);
+            }
+        }
+        self.tensors[k] = left;
+        self.tensors[k + 1] = right;
+        self.bonds[k + 1] = keep;
+        self.center = k + 1;
+    }
+
+    /// Number of singular values to keep, accumulating the discarded weight
+    /// in the truncation error.
+    fn truncate(
+        &mut self,
+        s: &[T],
+    ) -> usize {
+        let total: T = s.iter().map(|s| *s * *s).sum();
+        let zero = T::epsilon() * s[0];
+        let mut keep = s.iter().take_while(|&&s| s > zero).count().max(1);
+
+        let mut discarded = T::zero();
+        while keep > 1 {
+            let w = s[keep - 1] * s[keep - 1] / total;
+            if discarded + w > self.threshold {
+                break;
+            }
+            discarded += w;
+            keep -= 1;
+        }
+        if let Some(max) = self.max_bond_dim {
+            while keep > max.get() {
+                discarded += s[keep - 1] * s[keep - 1] / total;
+                keep -= 1;
+            }
+        }
+        self.truncation_error += discarded;
+        keep
+    }
+
+    /// Move the orthogonality center to qubit `k`, without truncation.
+    fn move_center(
+        &mut self,
+        k: usize,
+    ) {
+        while self.center < k {
+            let c = self.center;
+            let (l_dim, r_dim) = (self.bonds[c], self.bonds[c + 1]);
+            let svd = linalg::svd(&self.tensors[c], l_dim * 2, r_dim);
+            let width = (l_dim * 2).min(r_dim);
+            let keep = nonzero(&svd.s);
+
+            // A_c = U, carry = diag(s) V^dagger into A_{c+1}.
+            let mut left = vec![Complex::zero(); l_dim * 2 * keep];
+            for i in 0..l_dim * 2 {
+                for j in 0..keep {
+                    left[i * keep + j] = svd.u[i * width + j];
+                }
+            }
+            let next_r = self.bonds[c + 2];
+            let next = &self.tensors[c + 1];
+            let mut carried = vec![Complex::zero(); keep * 2 * next_r];
+            for j in 0..keep {
+                for m in 0..r_dim {
+                    let w = svd.v[m * width + j].conj() * svd.s[j];
+                    for x in 0..2 * next_r {
+                        carried[j * 2 * next_r + x] +=
+                            w * next[m * 2 * next_r + x];
+                    }
+                }
+            }
+            self.tensors[c] = left;
+            self.tensors[c + 1] = carried;
+            self.bonds[c + 1] = keep;
+            self.center += 1;
+        }
+        while self.center > k {
+            let c = self.center;
+            let (l_dim, r_dim) = (self.bonds[c], self.bonds[c + 1]);
+            let svd = linalg::svd(&self.tensors[c], l_dim, 2 * r_dim);
+            let width = l_dim.min(2 * r_dim);
+            let keep = nonzero(&svd.s);
+
+            // A_c = V^dagger, carry = U diag(s) into A_{c-1}.
+            let mut right = vec![Complex::zero(); keep * 2 * r_dim];
+            for j in 0..keep {
+                for x in 0..2 * r_dim {
+                    right[j * 2 * r_dim + x] = svd.v[x * width + j].conj();
+                }
+            }
+            let prev = &self.tensors[c - 1];
+            let rows = self.bonds[c - 1] * 2;
+            let mut carried = vec![Complex::zero(); rows * keep];
+            for i in 0..rows {
+                for m in 0..l_dim {
+                    let p = prev[i * l_dim + m];
+                    for j in 0..keep {
+                        carried[i * keep + j] +=
+                            p * svd.u[m * width + j] * svd.s[j];
+                    }
+                }
+            }
+            self.tensors[c] = right;
+            self.tensors[c - 1] = carried;
+            self.bonds[c] = keep;
+            self.center -= 1;
+        }
+    }
+
+    /// Contract the state with its conjugate.  If `restrict` is
+    /// `Some((k, s))`, the physical index of qubit `k` is restricted to `s`.
+    fn norm_sqr_restricted(
+        &self,
+        restrict: Option<(usize, usize)>,
+    ) -> T {
+        let mut env = vec![Complex::<T>::one()];
+        for (k, tensor) in self.tensors.iter().enumerate() {
+            let (l_dim, r_dim) = (self.bonds[k], self.bonds[k + 1]);
+            let mut next = vec![Complex::zero(); r_dim * r_dim];
+            for s in 0..2 {
+                if restrict.is_some_and(|(q, o)| q == k && o != s) {
+                    continue;
+                }
+                for a in 0..l_dim {
+                    for b in 0..l_dim {
+                        let e = env[a * l_dim + b];
+                        if e.is_zero() {
+                            continue;
+                        }
+                        for x in 0..r_dim {
+                            let ax = tensor[(a * 2 + s) * r_dim + x].conj() * e;
+                            for y in 0..r_dim {
+                                next[x * r_dim + y] +=
+                                    ax * tensor[(b * 2 + s) * r_dim + y];
+                            }
+                        }
+                    }
+                }
+            }
+            env = next;
+        }
+        env[0].re
+    }
+
+    /// Probability of measuring qubit `index` in the state `ONE`.
+    ///
+    /// Returns `None`, if index is larger or equal than `self.num_qubits()`
+    #[must_use]
+    pub fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T> {
+        if index >= self.num_qubits.get() {
+            return None;
+        }
+        let one = self.norm_sqr_restricted(Some((index.into(), 1)));
+        Some(one / self.norm_sqr_restricted(None))
+    }
+
+    /// Amplitude of the computational basis state given by `bits`, where
+    /// `bits[k]` is the value of qubit `k`.
+    ///
+    /// Returns `None`, if the length of `bits` is not `self.num_qubits()`
+    #[must_use]
+    pub fn amplitude(
+        &self,
+        bits: &[Bit],
+    ) -> Option<Complex<T>> {
+        if bits.len() != usize::from(self.num_qubits.get()) {
+            return None;
+        }
+        let mut vec = vec![Complex::one()];
+        for (k, (tensor, &bit)) in self.tensors.iter().zip(bits).enumerate() {
+            let (l_dim, r_dim) = (self.bonds[k], self.bonds[k + 1]);
+            let s = usize::from(bool::from(bit));
+            vec = (0..r_dim)
+                .map(|r| {
+                    (0..l_dim)
+                        .map(|l| vec[l] * tensor[(l * 2 + s) * r_dim + r])
+                        .sum()
+                })
+                .collect();
+        }
+        Some(vec[0])
+    }
+
+    /// Measure qubit `index` and collapse the state.
+    pub(crate) fn measure(
+        &mut self,
+        index: u16,
+    ) -> bool {
+        let k = usize::from(index);
+        self.move_center(k);
+        let (l_dim, r_dim) = (self.bonds[k], self.bonds[k + 1]);
+        let tensor = &mut self.tensors[k];
+
+        let weight = |s: usize, t: &[Complex<T>]| -> T {
+            (0..l_dim)
+                .flat_map(|l| (0..r_dim).map(move |r| (l * 2 + s) * r_dim + r))
+                .map(|i| t[i].norm_sqr())
+                .sum()
+        };
+        let (w0, w1) = (weight(0, tensor), weight(1, tensor));
+        let p1 = (w1 / (w0 + w1)).to_f64().unwrap();
+        let outcome = self.rng.gen::<f64>() < p1;
+
+        let (keep, drop, w) = if outcome { (1, 0, w1) } else { (0, 1, w0) };
+        let scale = w.sqrt().recip();
+        for l in 0..l_dim {
+            for r in 0..r_dim {
+                tensor[(l * 2 + drop) * r_dim + r] = Complex::zero();
+                tensor[(l * 2 + keep) * r_dim + r] *= scale;
+            }
+        }
+        outcome
+    }
+
+    /// Get a qubit.
+    ///
+    /// Returns `None`, if index is larger or equal than `self.num_qubits()`
+    pub fn qubit(
+        &mut self,
+        index: u16,
+    ) -> Option<MpsQubit<'_, T>> {
+        BackendQubit::new(self, index)
+    }
+
+    /// Get a pair of qubits.
+    ///
+    /// Returns `None`
+    /// - if any of indices is larger or equal than `self.num_qubits()`
+    /// - if indices are equal
+    pub fn qubit_pair(
+        &mut self,
+        index1: u16,
+        index2: u16,
+    ) -> Option<(MpsQubit<'_, T>, MpsQubit<'_, T>)> {
+        BackendQubit::new_pair(self, index1, index2)
+    }
+
+    /// Iterate over all qubits in the system.
+    pub fn qubit_iter(&mut self) -> impl Iterator<Item = MpsQubit<'_, T>> {
+        BackendQubit::new_iter(self)
+    }
+}
+
+/// Number of numerically non-zero singular values, at least one.
+fn nonzero<T>(s: &[T]) -> usize
+where
+    T: Float,
+{
+    let zero = T::epsilon() * s[0];
+    s.iter().take_while(|&&s| s > zero).count().max(1)
+}
+
+/// A representation of a qubit in a quantum system in a matrix product
+/// state.
+///
+/// See [`BackendQubit`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Bit, Gate, MpsSystem};
+/// let num_qubits = NonZeroU16::new(2).unwrap();
+/// let mut stm: MpsSystem<f64> = MpsSystem::new(num_qubits, 123);
+/// stm.apply(&Gate::X(1));
+/// let mut qubit = stm.qubit(1).unwrap();
+///
+/// assert_eq!(qubit.measure(), Bit::ONE);
+/// ```
+pub type MpsQubit<'a, T> = BackendQubit<'a, T, MpsSystem<T>>;
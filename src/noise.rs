@@ -0,0 +1,219 @@
+use std::fmt;
+
+use crate::{
+    Channel,
+    Float,
+    Gate,
+    GateKind,
+    ReadoutModel,
+};
+
+/// Error type returned when configuring a noise model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoiseModelError {
+    /// Channel acts on more qubits than the gate it is attached to, or idle
+    /// noise is not a single-qubit channel.
+    InvalidChannel,
+    /// Qubit indices do not match the gate, or are not distinct.
+    InvalidQubits,
+}
+
+impl fmt::Display for NoiseModelError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::InvalidChannel => {
+                write!(f, "invalid number of channel qubits")
+            }
+            Self::InvalidQubits => write!(f, "invalid qubit indices"),
+        }
+    }
+}
+
+impl std::error::Error for NoiseModelError {}
+
+/// Channel attached to a kind of gate.
+#[derive(Debug, Clone, PartialEq)]
+struct GateNoise<T> {
+    kind:    GateKind,
+    qubits:  Option<Vec<u16>>,
+    channel: Channel<T>,
+}
+
+/// Noise applied automatically when a circuit is executed.
+///
+/// The model consists of:
+/// - channels applied after gates of a given kind, either on any qubits or only
+///   when the gate acts on specified qubits,
+/// - a single-qubit channel applied to idle qubits: after each gate, to every
+///   qubit of the circuit that the gate does not act on,
+/// - readout errors applied to outcomes of measurements.
+///
+/// On a pure [`System`], channels are applied by sampling Kraus operators,
+/// see [`System::apply_channel()`].  On a [`DensityMatrixSystem`] they are
+/// applied exactly.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Channel, Circuit, DensityMatrixSystem, Gate, GateKind, NoiseModel};
+/// let num_qubits = NonZeroU16::new(3).unwrap();
+/// let mut noise = NoiseModel::new();
+/// noise
+///     .add_gate_noise(
+///         GateKind::CNOT,
+///         Some(&[0, 1]),
+///         Channel::depolarizing2(0.1).unwrap(),
+///     )
+///     .unwrap();
+/// noise
+///     .set_idle_noise(Some(Channel::amplitude_damping(0.01).unwrap()))
+///     .unwrap();
+///
+/// let mut circuit = Circuit::new(num_qubits);
+/// circuit.apply(Gate::H(0));
+/// circuit.apply(Gate::CNOT(0, 1));
+///
+/// let mut stm: DensityMatrixSystem<f64> =
+///     DensityMatrixSystem::new(num_qubits, 123);
+/// stm.run_noisy(&circuit, &noise);
+///
+/// assert!(stm.purity() < 1.);
+/// ```
+///
+/// [`System`]: crate::System
+/// [`System::apply_channel()`]: crate::System::apply_channel
+/// [`DensityMatrixSystem`]: crate::DensityMatrixSystem
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseModel<T> {
+    gates:   Vec<GateNoise<T>>,
+    idle:    Option<Channel<T>>,
+    readout: Option<ReadoutModel<T>>,
+}
+
+impl<T> Default for NoiseModel<T> {
+    fn default() -> Self {
+        Self {
+            gates:   Vec::new(),
+            idle:    None,
+            readout: None,
+        }
+    }
+}
+
+impl<T> NoiseModel<T>
+where
+    T: Float,
+{
+    /// Noiseless model.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply `channel` after every gate of the given kind.
+    ///
+    /// If `qubits` is `Some`, the channel is applied only after gates acting
+    /// on exactly these qubits, in this order.  A two-qubit channel is
+    /// applied to the qubits of the gate, in the order of the gate's
+    /// arguments.  A single-qubit channel attached to a two-qubit gate is
+    /// applied to each of the qubits separately.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error
+    /// - if the channel acts on more qubits than the gate
+    /// - if `qubits` do not match the number of qubits of the gate, or are not
+    ///   distinct
+    pub fn add_gate_noise(
+        &mut self,
+        kind: GateKind,
+        qubits: Option<&[u16]>,
+        channel: Channel<T>,
+    ) -> Result<(), NoiseModelError> {
+        if channel.num_qubits() > kind.num_qubits() {
+            return Err(NoiseModelError::InvalidChannel);
+        }
+        if let Some(qubits) = qubits {
+            if qubits.len() != usize::from(kind.num_qubits())
+                || (qubits.len() == 2 && qubits[0] == qubits[1])
+            {
+                return Err(NoiseModelError::InvalidQubits);
+            }
+        }
+        self.gates.push(GateNoise {
+            kind,
+            qubits: qubits.map(<[u16]>::to_vec),
+            channel,
+        });
+        Ok(())
+    }
+
+    /// Set the channel applied to idle qubits.
+    ///
+    /// After each gate, the channel is applied to every qubit of the circuit
+    /// the gate does not act on.  Use `None` to disable idle noise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if the channel is not a single-qubit channel.
+    pub fn set_idle_noise(
+        &mut self,
+        channel: Option<Channel<T>>,
+    ) -> Result<(), NoiseModelError> {
+        if channel.as_ref().is_some_and(|c| c.num_qubits() != 1) {
+            return Err(NoiseModelError::InvalidChannel);
+        }
+        self.idle = channel;
+        Ok(())
+    }
+
+    /// Set readout errors applied to outcomes of measurements.
+    ///
+    /// Readout errors are applied in addition to the readout model of the
+    /// system, if any.
+    pub fn set_readout(
+        &mut self,
+        model: Option<ReadoutModel<T>>,
+    ) {
+        self.readout = model;
+    }
+
+    /// Get readout errors applied to outcomes of measurements.
+    #[must_use]
+    pub fn readout(&self) -> Option<&ReadoutModel<T>> {
+        self.readout.as_ref()
+    }
+
+    /// Channels to be applied after `gate` in a circuit of `num_qubits`
+    /// qubits, together with qubits they act on.
+    pub(crate) fn channels_after(
+        &self,
+        gate: &Gate<T>,
+        num_qubits: u16,
+    ) -> Vec<(&Channel<T>, Vec<u16>)> {
+        let targets = gate.qubits();
+        let mut channels = Vec::new();
+        for noise in self.gates.iter().filter(|n| {
+            n.kind == gate.kind()
+                && n.qubits.as_ref().is_none_or(|q| *q == targets)
+        }) {
+            if noise.channel.num_qubits() == 1 {
+                for &q in &targets {
+                    channels.push((&noise.channel, vec![q]));
+                }
+            } else {
+                channels.push((&noise.channel, targets.clone()));
+            }
+        }
+        if let Some(idle) = &self.idle {
+            for q in (0..num_qubits).filter(|q| !targets.contains(q)) {
+                channels.push((idle, vec![q]));
+            }
+        }
+        channels
+    }
+}
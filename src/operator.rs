@@ -0,0 +1,238 @@
+use std::fmt;
+
+use num::{
+    Complex,
+    One,
+    Zero,
+};
+use rayon::prelude::{
+    IndexedParallelIterator,
+    IntoParallelRefMutIterator,
+    ParallelIterator,
+};
+
+use crate::{
+    Float,
+    PauliSum,
+};
+
+/// Hermitian operator acting on state vectors by matrix-vector products.
+///
+/// Implemented for [`PauliSum`] and [`SparseMatrix`].  Algorithms using
+/// this trait, e.g. [`krylov::expm_multiply()`], never construct the matrix
+/// of the operator.
+///
+/// [`krylov::expm_multiply()`]: crate::krylov::expm_multiply
+pub trait LinearOperator<T>
+where
+    T: Float,
+{
+    /// Check if the operator can act on vectors of length `dim`.
+    fn is_valid_dim(
+        &self,
+        dim: usize,
+    ) -> bool;
+
+    /// Compute `dst = A src`.
+    ///
+    /// Both slices have the same length, valid for the operator.
+    fn mul_vec(
+        &self,
+        src: &[Complex<T>],
+        dst: &mut [Complex<T>],
+    );
+}
+
+impl<T> LinearOperator<T> for PauliSum<T>
+where
+    T: Float,
+{
+    /// The dimension must be a power of two, large enough for all qubits the
+    /// operator acts on.
+    fn is_valid_dim(
+        &self,
+        dim: usize,
+    ) -> bool {
+        dim.is_power_of_two()
+            && dim.trailing_zeros() >= u32::from(self.num_qubits())
+    }
+
+    fn mul_vec(
+        &self,
+        src: &[Complex<T>],
+        dst: &mut [Complex<T>],
+    ) {
+        dst.par_iter_mut().for_each(|x| *x = Complex::zero());
+        self.mul_add(Complex::one(), src, dst, 0);
+    }
+}
+
+/// Error type returned when constructing a sparse matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SparseMatrixError {
+    /// Row or column index is out of bounds.
+    InvalidIndex,
+    /// Matrix is not Hermitian.
+    NotHermitian,
+}
+
+impl fmt::Display for SparseMatrixError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::InvalidIndex => write!(f, "invalid index"),
+            Self::NotHermitian => write!(f, "matrix is not Hermitian"),
+        }
+    }
+}
+
+impl std::error::Error for SparseMatrixError {}
+
+/// Hermitian matrix in compressed sparse row (CSR) format.
+///
+/// # Examples
+///
+/// ```rust
+/// # use num::Complex;
+/// # use qn::SparseMatrix;
+/// // Pauli X
+/// let one = Complex::from(1.);
+/// let x: SparseMatrix<f64> =
+///     SparseMatrix::from_triplets(2, &[(0, 1, one), (1, 0, one)]).unwrap();
+///
+/// assert_eq!(x.dim(), 2);
+/// assert_eq!(x.nnz(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix<T> {
+    dim:     usize,
+    row_ptr: Vec<usize>,
+    cols:    Vec<usize>,
+    values:  Vec<Complex<T>>,
+}
+
+impl<T> SparseMatrix<T>
+where
+    T: Float,
+{
+    /// Construct a `dim x dim` matrix from `(row, column, value)` entries.
+    ///
+    /// Entries with the same row and column are summed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error
+    /// - if any of the indices is larger or equal than `dim`
+    /// - if the matrix is not Hermitian, up to the tolerance of
+    ///   `T::epsilon().sqrt()` times the largest entry
+    pub fn from_triplets(
+        dim: usize,
+        entries: &[(usize, usize, Complex<T>)],
+    ) -> Result<Self, SparseMatrixError> {
+        if entries.iter().any(|&(r, c, _)| r >= dim || c >= dim) {
+            return Err(SparseMatrixError::InvalidIndex);
+        }
+
+        let mut sorted = entries.to_vec();
+        sorted.sort_by_key(|&(r, c, _)| (r, c));
+        let mut row_ptr = vec![0; dim + 1];
+        let mut cols: Vec<usize> = Vec::with_capacity(sorted.len());
+        let mut values: Vec<Complex<T>> = Vec::with_capacity(sorted.len());
+        let mut last = None;
+        for (r, c, v) in sorted {
+            if last == Some((r, c)) {
+                *values.last_mut().expect("entry exists") += v;
+            } else {
+                cols.push(c);
+                values.push(v);
+                row_ptr[r + 1] += 1;
+                last = Some((r, c));
+            }
+        }
+        for r in 0..dim {
+            row_ptr[r + 1] += row_ptr[r];
+        }
+
+        let mat = Self {
+            dim,
+            row_ptr,
+            cols,
+            values,
+        };
+        let scale = mat.values.iter().map(|z| z.norm()).fold(T::zero(), T::max);
+        let tol = T::epsilon().sqrt() * scale;
+        for r in 0..dim {
+            for (c, v) in mat.row(r) {
+                if (mat.get(c, r) - v.conj()).norm() > tol {
+                    return Err(SparseMatrixError::NotHermitian);
+                }
+            }
+        }
+        Ok(mat)
+    }
+
+    /// Get the dimension of the matrix.
+    #[must_use]
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Get the number of stored entries.
+    #[must_use]
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Get the entry in row `row` and column `col`.
+    ///
+    /// Returns zero for indices out of bounds.
+    #[must_use]
+    pub fn get(
+        &self,
+        row: usize,
+        col: usize,
+    ) -> Complex<T> {
+        if row >= self.dim {
+            return Complex::zero();
+        }
+        let (lo, hi) = (self.row_ptr[row], self.row_ptr[row + 1]);
+        self.cols[lo..hi]
+            .binary_search(&col)
+            .map_or(Complex::zero(), |i| self.values[lo + i])
+    }
+
+    fn row(
+        &self,
+        row: usize,
+    ) -> impl Iterator<Item = (usize, Complex<T>)> + '_ {
+        let (lo, hi) = (self.row_ptr[row], self.row_ptr[row + 1]);
+        self.cols[lo..hi]
+            .iter()
+            .copied()
+            .zip(self.values[lo..hi].iter().copied())
+    }
+}
+
+impl<T> LinearOperator<T> for SparseMatrix<T>
+where
+    T: Float,
+{
+    fn is_valid_dim(
+        &self,
+        dim: usize,
+    ) -> bool {
+        dim == self.dim
+    }
+
+    fn mul_vec(
+        &self,
+        src: &[Complex<T>],
+        dst: &mut [Complex<T>],
+    ) {
+        dst.par_iter_mut().enumerate().for_each(|(r, d)| {
+            *d = self.row(r).map(|(c, v)| v * src[c]).sum();
+        });
+    }
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use rayon::ThreadPool;
+
+/// Default number of amplitudes below which kernels run sequentially.
+const DEFAULT_SEQUENTIAL_THRESHOLD: usize = 1 << 12;
+
+/// Threads used by a [`System`] to run its kernels.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::{num::NonZeroU16, sync::Arc};
+/// # use qn::{Gate, Parallelism, System};
+/// let pool = rayon::ThreadPoolBuilder::new()
+///     .num_threads(2)
+///     .build()
+///     .unwrap();
+///
+/// let num_qubits = NonZeroU16::new(16).unwrap();
+/// let mut stm: System<f64> = System::new(num_qubits, 123);
+/// stm.set_parallelism(Parallelism::Pool(Arc::new(pool)));
+/// stm.apply(&Gate::H(15));
+/// assert!((stm.probability(15).unwrap() - 0.5).abs() < 1e-12);
+/// ```
+///
+/// [`System`]: crate::System
+#[derive(Debug, Clone, Default)]
+pub enum Parallelism {
+    /// Rayon's global thread pool (the default).
+    #[default]
+    Global,
+    /// Custom thread pool.
+    Pool(Arc<ThreadPool>),
+    /// Current thread only.  No threads are spawned, and rayon is not used.
+    Sequential,
+}
+
+/// Choice between parallel and sequential kernels.
+#[derive(Debug, Clone)]
+pub(crate) struct Executor {
+    pub(crate) parallelism: Parallelism,
+    pub(crate) threshold:   usize,
+}
+
+impl Default for Executor {
+    fn default() -> Self {
+        Self {
+            parallelism: Parallelism::Global,
+            threshold:   DEFAULT_SEQUENTIAL_THRESHOLD,
+        }
+    }
+}
+
+impl Executor {
+    /// Run `op` on data of `len` elements.
+    ///
+    /// `op` is given `true`, if it should use rayon's parallel iterators, in
+    /// which case it is run in the configured thread pool.  Otherwise, it
+    /// must not use rayon.
+    pub(crate) fn run<R, F>(
+        &self,
+        len: usize,
+        op: F,
+    ) -> R
+    where
+        R: Send,
+        F: FnOnce(bool) -> R + Send,
+    {
+        if len < self.threshold {
+            return op(false);
+        }
+        match &self.parallelism {
+            Parallelism::Global => op(true),
+            Parallelism::Pool(pool) => pool.install(|| op(true)),
+            Parallelism::Sequential => op(false),
+        }
+    }
+}
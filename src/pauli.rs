@@ -0,0 +1,298 @@
+use num::{
+    Complex,
+    One,
+};
+use rayon::prelude::{
+    IndexedParallelIterator,
+    IntoParallelRefMutIterator,
+    ParallelIterator,
+};
+
+use crate::Float;
+
+/// Single-qubit Pauli operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pauli {
+    I,
+    X,
+    Y,
+    Z,
+}
+
+/// Tensor product of Pauli operators acting on distinct qubits.
+///
+/// Qubits not mentioned explicitly are acted on by the identity.  The string
+/// is stored as two bit masks, so that its action on a computational basis
+/// state `|k>` is `P|k> = i^(#Y) (-1)^popcount(k & z) |k ^ x>`, where `x`
+/// marks qubits acted on by X or Y, and `z` marks qubits acted on by Z or Y.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PauliString {
+    x: usize,
+    z: usize,
+}
+
+impl PauliString {
+    /// Pauli string acting on qubits given as `(index, operator)` pairs.
+    ///
+    /// Returns `None`
+    /// - if any of the indices is repeated
+    /// - if any of the indices is larger or equal than `usize::BITS`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use qn::{Pauli, PauliString};
+    /// let zz = PauliString::new(&[(0, Pauli::Z), (1, Pauli::Z)]).unwrap();
+    ///
+    /// assert_eq!(zz.get(1), Some(Pauli::Z));
+    /// assert_eq!(zz.get(2), Some(Pauli::I));
+    /// assert!(PauliString::new(&[(0, Pauli::X), (0, Pauli::Z)]).is_none());
+    /// ```
+    #[must_use]
+    pub fn new(ops: &[(u16, Pauli)]) -> Option<Self> {
+        let mut seen = 0usize;
+        let mut string = Self::default();
+        for &(index, op) in ops {
+            if u32::from(index) >= usize::BITS {
+                return None;
+            }
+            let bit = 1usize << index;
+            if seen & bit != 0 {
+                return None;
+            }
+            seen |= bit;
+            match op {
+                Pauli::I => (),
+                Pauli::X => string.x |= bit,
+                Pauli::Y => {
+                    string.x |= bit;
+                    string.z |= bit;
+                }
+                Pauli::Z => string.z |= bit,
+            }
+        }
+        Some(string)
+    }
+
+    /// Identity operator.
+    #[must_use]
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    /// Operator acting on qubit `index`.
+    ///
+    /// Returns `None`, if index is larger or equal than `usize::BITS`
+    #[must_use]
+    pub fn get(
+        &self,
+        index: u16,
+    ) -> Option<Pauli> {
+        if u32::from(index) >= usize::BITS {
+            return None;
+        }
+        let bit = 1usize << index;
+        Some(match (self.x & bit != 0, self.z & bit != 0) {
+            (false, false) => Pauli::I,
+            (true, false) => Pauli::X,
+            (true, true) => Pauli::Y,
+            (false, true) => Pauli::Z,
+        })
+    }
+
+    /// Number of qubits acted on by a non-identity operator.
+    #[must_use]
+    pub fn weight(&self) -> u32 {
+        (self.x | self.z).count_ones()
+    }
+
+    /// Smallest number of qubits of a system the string can act on.
+    #[must_use]
+    pub fn num_qubits(&self) -> u16 {
+        // The result is at most usize::BITS.
+        #[allow(clippy::cast_possible_truncation)]
+        let n = (usize::BITS - (self.x | self.z).leading_zeros()) as u16;
+        n
+    }
+
+    /// Check if two strings commute.
+    #[must_use]
+    pub fn commutes_with(
+        &self,
+        other: &Self,
+    ) -> bool {
+        ((self.x & other.z) ^ (self.z & other.x))
+            .count_ones()
+            .is_multiple_of(2)
+    }
+
+    /// Bit mask of qubits acted on by X or Y.
+    pub(crate) fn x_mask(&self) -> usize {
+        self.x
+    }
+
+    /// Bit mask of qubits acted on by Z or Y.
+    pub(crate) fn z_mask(&self) -> usize {
+        self.z
+    }
+
+    /// The factor `i^(#Y)`.
+    pub(crate) fn y_phase<T>(&self) -> Complex<T>
+    where
+        T: Float,
+    {
+        match (self.x & self.z).count_ones() % 4 {
+            0 => Complex::one(),
+            1 => Complex::i(),
+            2 => -Complex::one(),
+            _ => -Complex::i(),
+        }
+    }
+
+    /// Add `scale * P src` to `dst`, where the string is shifted by `shift`
+    /// bits.
+    ///
+    /// With `shift` equal to the number of qubits `n`, this multiplies a
+    /// density matrix of `n` qubits by the string from the left.
+    pub(crate) fn mul_add<T>(
+        &self,
+        scale: Complex<T>,
+        src: &[Complex<T>],
+        dst: &mut [Complex<T>],
+        shift: u16,
+    ) where
+        T: Float,
+    {
+        let (x, z) = (self.x << shift, self.z << shift);
+        let scale = scale * self.y_phase();
+        dst.par_iter_mut().enumerate().for_each(|(j, d)| {
+            let k = j ^ x;
+            if (k & z).count_ones().is_multiple_of(2) {
+                *d += scale * src[k];
+            } else {
+                *d -= scale * src[k];
+            }
+        });
+    }
+
+    /// Add `scale * rho P` to `dst`, where `rho` is a density matrix.
+    pub(crate) fn mul_add_right<T>(
+        &self,
+        scale: Complex<T>,
+        rho: &[Complex<T>],
+        dst: &mut [Complex<T>],
+    ) where
+        T: Float,
+    {
+        // (rho P)[r][c] = rho[r][c ^ x] P[c ^ x][c], and P is symmetric up
+        // to the sign (-1)^(#Y).
+        let scale = if (self.x & self.z).count_ones().is_multiple_of(2) {
+            scale
+        } else {
+            -scale
+        };
+        self.mul_add(scale, rho, dst, 0);
+    }
+}
+
+/// Linear combination of Pauli strings with real coefficients.
+///
+/// Represents a Hermitian operator, e.g. a Hamiltonian.
+///
+/// # Examples
+///
+/// ```rust
+/// # use qn::{Pauli, PauliString, PauliSum};
+/// // Transverse-field Ising model on two qubits.
+/// let mut ham = PauliSum::new();
+/// ham.add_term(
+///     -1.,
+///     PauliString::new(&[(0, Pauli::Z), (1, Pauli::Z)]).unwrap(),
+/// );
+/// ham.add_term(-0.5, PauliString::new(&[(0, Pauli::X)]).unwrap());
+/// ham.add_term(-0.5, PauliString::new(&[(1, Pauli::X)]).unwrap());
+///
+/// assert_eq!(ham.terms().len(), 3);
+/// assert_eq!(ham.num_qubits(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PauliSum<T> {
+    terms: Vec<(T, PauliString)>,
+}
+
+impl<T> Default for PauliSum<T> {
+    fn default() -> Self {
+        Self {
+            terms: Vec::new()
+        }
+    }
+}
+
+impl<T> PauliSum<T>
+where
+    T: Float,
+{
+    /// Empty sum, i.e. the zero operator.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add term `coeff * string`.
+    pub fn add_term(
+        &mut self,
+        coeff: T,
+        string: PauliString,
+    ) {
+        self.terms.push((coeff, string));
+    }
+
+    /// Get terms of the sum.
+    #[must_use]
+    pub fn terms(&self) -> &[(T, PauliString)] {
+        &self.terms
+    }
+
+    /// Smallest number of qubits of a system the operator can act on.
+    #[must_use]
+    pub fn num_qubits(&self) -> u16 {
+        self.terms
+            .iter()
+            .map(|(_, s)| s.num_qubits())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Upper bound on the operator norm: the sum of absolute values of the
+    /// coefficients.
+    #[must_use]
+    pub fn norm_bound(&self) -> T {
+        self.terms.iter().map(|(c, _)| c.abs()).sum()
+    }
+
+    /// Add `scale * H src` to `dst`, where the operator is shifted by `shift`
+    /// bits.
+    pub(crate) fn mul_add(
+        &self,
+        scale: Complex<T>,
+        src: &[Complex<T>],
+        dst: &mut [Complex<T>],
+        shift: u16,
+    ) {
+        for (coeff, string) in &self.terms {
+            string.mul_add(scale * coeff, src, dst, shift);
+        }
+    }
+
+    /// Add `scale * rho H` to `dst`, where `rho` is a density matrix.
+    pub(crate) fn mul_add_right(
+        &self,
+        scale: Complex<T>,
+        rho: &[Complex<T>],
+        dst: &mut [Complex<T>],
+    ) {
+        for (coeff, string) in &self.terms {
+            string.mul_add_right(scale * coeff, rho, dst);
+        }
+    }
+}
@@ -1,33 +1,36 @@
-use std::sync::{
-    Arc,
-    Mutex,
+use std::{
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+    thread::{
+        self,
+        ThreadId,
+    },
 };
 
 use num::{
     Complex,
     Zero,
 };
-use rayon::prelude::{
-    IndexedParallelIterator,
-    IntoParallelRefIterator,
-    IntoParallelRefMutIterator,
-    ParallelIterator,
-};
 
-// use rayon::{
-//     prelude::{
-//         IndexedParallelIterator,
-//         ParallelIterator,
-//     },
-//     slice::ParallelSlice,
-// };
 use crate::{
+    gate::Kernel,
     Float,
+    Gate,
+    Interleaved,
+    Layout,
+    Matrix2,
     System,
 };
 
 /// Classical bit with two possible values (ZERO and ONE)
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Bit {
     ZERO,
     ONE,
@@ -43,31 +46,165 @@ impl From<bool> for Bit {
     }
 }
 
+impl From<Bit> for bool {
+    fn from(value: Bit) -> Self {
+        value == Bit::ONE
+    }
+}
+
 /// A representation of a qubit in a quantum system.
-pub struct Qubit<'a, T>
+///
+/// Qubits of the same system can be moved to different threads.  A
+/// single-qubit gate applied to a qubit with [`Qubit::apply()`] is put in the
+/// queue of that qubit, where it is fused with other gates waiting there, and
+/// the call returns without waiting for the system: gates on different qubits
+/// commute, so they can be applied in any order.  Whichever thread holds the
+/// lock on the system applies all queued gates before releasing it.
+///
+/// Two-qubit gates and measurements wait for the lock and apply all queued
+/// gates first, so the order of operations on each qubit, as well as
+/// outcomes and the collapse of the state, are consistent across threads.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Bit, Gate, System};
+/// let num_qubits = NonZeroU16::new(8).unwrap();
+/// let mut stm: System<f64> = System::new(num_qubits, 123);
+///
+/// std::thread::scope(|s| {
+///     for mut qubit in stm.qubit_iter() {
+///         s.spawn(move || {
+///             let k = qubit.index();
+///             qubit.apply(&Gate::H(k));
+///             qubit.apply(&Gate::H(k));
+///             qubit.apply(&Gate::X(k));
+///             assert_eq!(qubit.measure(), Bit::ONE);
+///         });
+///     }
+/// });
+/// assert!((stm.as_slice()[255].re - 1.).abs() < 1e-12);
+///
+/// let (mut qb0, mut qb1) = stm.qubit_pair(0, 1).unwrap();
+/// qb0.apply(&Gate::CNOT(0, 1));
+/// assert_eq!(qb0.measure(), Bit::ONE);
+/// assert_eq!(qb1.measure(), Bit::ZERO);
+/// ```
+pub struct Qubit<'a, T, L = Interleaved>
 where
     T: Float,
+    L: Layout,
 {
-    stm:   Arc<Mutex<&'a mut System<T>>>,
+    stm:   Arc<Shared<'a, T, L>>,
     index: u16,
 }
 
-impl<'a, T> Qubit<'a, T>
+/// Fused gates waiting in the queue of a qubit, with the gates in the order
+/// they were queued and the threads that queued them.
+type Pending<T> = Option<(Matrix2<T>, Vec<(Gate<T>, ThreadId)>)>;
+
+/// System shared by qubits, with queues of gates waiting to be applied.
+struct Shared<'a, T, L>
 where
     T: Float,
+    L: Layout,
+{
+    stm:         Mutex<&'a mut System<T, L>>,
+    num_qubits:  u16,
+    /// Fused gates waiting in the queue of each qubit.
+    queues:      Vec<Mutex<Pending<T>>>,
+    num_pending: AtomicUsize,
+}
+
+impl<'a, T, L> Shared<'a, T, L>
+where
+    T: Float,
+    L: Layout,
+{
+    fn new(stm: &'a mut System<T, L>) -> Arc<Self> {
+        let num_qubits = stm.num_qubits().get();
+        Arc::new(Self {
+            stm: Mutex::new(stm),
+            num_qubits,
+            queues: (0..num_qubits).map(|_| Mutex::new(None)).collect(),
+            num_pending: AtomicUsize::new(0),
+        })
+    }
+
+    /// Put `gate`, with the matrix `m`, in the queue of qubit `index`.
+    fn enqueue(
+        &self,
+        index: u16,
+        gate: &Gate<T>,
+        m: &Matrix2<T>,
+    ) {
+        let mut queue = self.queues[usize::from(index)].lock().unwrap();
+        let entry = (*gate, thread::current().id());
+        match &mut *queue {
+            Some((p, gates)) => {
+                *p = mul(m, p);
+                gates.push(entry);
+            }
+            None => {
+                self.num_pending.fetch_add(1, Ordering::SeqCst);
+                *queue = Some((*m, vec![entry]));
+            }
+        }
+    }
+
+    /// Apply queued gates, unless another thread holds the lock, in which
+    /// case that thread applies them before releasing the lock.
+    fn try_apply_pending(&self) {
+        while self.num_pending.load(Ordering::SeqCst) > 0 {
+            let Ok(mut stm) = self.stm.try_lock() else {
+                return;
+            };
+            self.apply_pending(&mut stm);
+        }
+    }
+
+    /// Lock the system and apply queued gates.
+    fn lock(&self) -> MutexGuard<'_, &'a mut System<T, L>> {
+        let mut stm = self.stm.lock().unwrap();
+        self.apply_pending(&mut stm);
+        stm
+    }
+
+    fn apply_pending(
+        &self,
+        stm: &mut System<T, L>,
+    ) {
+        if self.num_pending.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+        for (index, queue) in (0..).zip(&self.queues) {
+            let pending = queue.lock().unwrap().take();
+            if let Some((m, gates)) = pending {
+                self.num_pending.fetch_sub(1, Ordering::SeqCst);
+                stm.apply_fused(index, &m, &gates);
+            }
+        }
+    }
+}
+
+impl<'a, T, L> Qubit<'a, T, L>
+where
+    T: Float,
+    L: Layout,
 {
     /// Derive a single qubit from a quantum system.
     ///
     /// Returns `None`, if index is larger or equal than `stm.num_qubits()`
     pub fn new(
-        stm: &'a mut System<T>,
+        stm: &'a mut System<T, L>,
         index: u16,
-    ) -> Option<Qubit<'_, T>> {
+    ) -> Option<Qubit<'a, T, L>> {
         if index >= stm.num_qubits().get() {
             None
         } else {
             Some(Self {
-                stm: Arc::new(Mutex::new(stm)),
+                stm: Shared::new(stm),
                 index,
             })
         }
@@ -81,10 +218,10 @@ where
     /// - if any of indices is larger or equal than `stm.num_qubits()`
     /// - if indices are equal
     pub(crate) fn new_pair(
-        stm: &'a mut System<T>,
+        stm: &'a mut System<T, L>,
         index1: u16,
         index2: u16,
-    ) -> Option<(Qubit<'_, T>, Qubit<'_, T>)> {
+    ) -> Option<(Qubit<'a, T, L>, Qubit<'a, T, L>)> {
         if index1 >= stm.num_qubits().get()
             || index2 >= stm.num_qubits().get()
             || index1 == index2
@@ -92,7 +229,7 @@ where
             return None;
         }
 
-        let lock = Arc::new(Mutex::new(stm));
+        let lock = Shared::new(stm);
         let qb1 = Self {
             stm:   lock.clone(),
             index: index1,
@@ -106,10 +243,10 @@ where
 
     /// Get iterator over all qubits in system
     pub(crate) fn new_iter(
-        stm: &'a mut System<T>
-    ) -> impl Iterator<Item = Qubit<'a, T>> {
+        stm: &'a mut System<T, L>
+    ) -> impl Iterator<Item = Qubit<'a, T, L>> {
         let num_qubits = stm.num_qubits().get();
-        let lock = Arc::new(Mutex::new(stm));
+        let lock = Shared::new(stm);
 
         (0..num_qubits).map(move |i| Self {
             stm:   lock.clone(),
@@ -140,10 +277,10 @@ where
     #[must_use]
     pub fn is_from_same_stm(
         &self,
-        other_qubit: &Qubit<'a, T>,
+        other_qubit: &Qubit<'a, T, L>,
     ) -> bool {
         // Two qubits belong to the same system, if and only if they share
-        // the same mutex guarding access to &mut System.
+        // the same state guarding access to &mut System.
         Arc::<_>::as_ptr(&self.stm) == Arc::<_>::as_ptr(&other_qubit.stm)
     }
 
@@ -163,39 +300,59 @@ where
     /// ```
     #[must_use]
     pub fn measure(&mut self) -> Bit {
-        let mut stm = self.stm.lock().unwrap();
+        let mut stm = self.stm.lock();
+        let outcome = stm.measure(self.index);
+        let bit = stm.read_out_bit(self.index, outcome).into();
+        drop(stm);
+        self.stm.try_apply_pending();
+        bit
+    }
 
-        let mask = 1usize << self.index;
-        let amp_sq_1 = stm
-            .as_slice()
-            .par_iter()
-            .enumerate()
-            .filter(|(i, _)| i & mask == mask)
-            .map(|(_, a)| a.norm_sqr())
-            .fold(|| T::zero(), |acc, a| acc + a)
-            .sum();
-
-        // project the state onto random outcome
-        let p = T::to_f64(&amp_sq_1).unwrap();
-        let outcome = stm.bernoulli(p).unwrap();
-
-        // zero the amplitudes corresponding to (1-outcome), normalize the rest
-        let norm_factor = if outcome {
-            amp_sq_1.sqrt()
-        } else {
-            (T::one() - amp_sq_1).sqrt()
-        };
-        let outcome_shifted = if outcome { mask } else { 0 };
-        stm.as_mut_slice()
-            .par_iter_mut()
-            .enumerate()
-            .for_each(|(i, a)| {
-                if i & mask == outcome_shifted {
-                    *a /= norm_factor;
-                } else {
-                    *a = Complex::zero();
-                }
-            });
-        outcome.into()
+    /// Apply a gate acting on the qubit.
+    ///
+    /// A single-qubit gate does not wait for other threads using the system:
+    /// the gate is queued, and applied by whichever thread holds the lock on
+    /// the system.  A two-qubit gate acting on this qubit and another qubit of
+    /// the system waits for the lock, and is applied after all queued gates,
+    /// see [`Qubit`].
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the gate does not act on this qubit, or if it is not valid
+    /// for the system, see [`Gate::is_valid()`].
+    pub fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        let valid = gate.is_valid(self.stm.num_qubits);
+        match gate.kernel() {
+            Kernel::One(k, m) if k == self.index => {
+                self.stm.enqueue(self.index, gate, &m);
+            }
+            Kernel::Two(i, j, _)
+                if valid && (i == self.index || j == self.index) =>
+            {
+                self.stm.lock().apply(gate);
+            }
+            _ => panic!("invalid gate: {gate:?}"),
+        }
+        self.stm.try_apply_pending();
+    }
+}
+
+/// Product of single-qubit operators `a * b`.
+fn mul<T>(
+    a: &Matrix2<T>,
+    b: &Matrix2<T>,
+) -> Matrix2<T>
+where
+    T: Float,
+{
+    let mut c = [[Complex::zero(); 2]; 2];
+    for (i, row) in c.iter_mut().enumerate() {
+        for (j, x) in row.iter_mut().enumerate() {
+            *x = a[i][0] * b[0][j] + a[i][1] * b[1][j];
+        }
     }
+    c
 }
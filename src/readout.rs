@@ -0,0 +1,440 @@
+use std::fmt;
+
+use num::Complex;
+use rand::Rng;
+
+use crate::{
+    linalg,
+    Float,
+};
+
+/// Error type returned when configuring a readout error model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadoutModelError {
+    /// A probability is outside of the interval `[0, 1]`.
+    InvalidProbability,
+    /// Columns of a confusion matrix do not sum to one.
+    NotStochastic,
+    /// Confusion matrix has wrong size for the number of qubits.
+    InvalidDimension,
+    /// Qubit indices are not distinct, a qubit has already been assigned a
+    /// readout error, or a qubit is outside of the system.
+    InvalidQubits,
+}
+
+impl fmt::Display for ReadoutModelError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::InvalidProbability => {
+                write!(f, "probability outside of range [0, 1]")
+            }
+            Self::NotStochastic => {
+                write!(f, "confusion matrix is not column-stochastic")
+            }
+            Self::InvalidDimension => {
+                write!(f, "invalid dimension of confusion matrix")
+            }
+            Self::InvalidQubits => write!(f, "invalid qubit indices"),
+        }
+    }
+}
+
+impl std::error::Error for ReadoutModelError {}
+
+/// Readout error of a single qubit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReadoutError<T> {
+    p01: T,
+    p10: T,
+}
+
+impl<T> ReadoutError<T>
+where
+    T: Float,
+{
+    /// Readout error with probabilities `p01 = P(read 1 | 0)` and `p10 =
+    /// P(read 0 | 1)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if any of the probabilities is outside of the
+    /// interval `[0, 1]`.
+    pub fn new(
+        p01: T,
+        p10: T,
+    ) -> Result<Self, ReadoutModelError> {
+        let range = T::zero()..=T::one();
+        if !range.contains(&p01) || !range.contains(&p10) {
+            return Err(ReadoutModelError::InvalidProbability);
+        }
+        Ok(Self {
+            p01,
+            p10,
+        })
+    }
+
+    /// Probability of reading `ONE`, if the outcome was `ZERO`.
+    #[must_use]
+    pub fn p01(&self) -> T {
+        self.p01
+    }
+
+    /// Probability of reading `ZERO`, if the outcome was `ONE`.
+    #[must_use]
+    pub fn p10(&self) -> T {
+        self.p10
+    }
+
+    /// Probability that the outcome is misreported.
+    pub(crate) fn flip_probability(
+        &self,
+        outcome: bool,
+    ) -> T {
+        if outcome {
+            self.p10
+        } else {
+            self.p01
+        }
+    }
+
+    /// Confusion matrix: `P(read | outcome)`, in row-major order.
+    fn confusion(&self) -> Vec<T> {
+        vec![T::one() - self.p01, self.p10, self.p01, T::one() - self.p10]
+    }
+}
+
+/// Correlated readout error of a group of qubits.
+#[derive(Debug, Clone, PartialEq)]
+struct CorrelatedReadout<T> {
+    qubits:    Vec<u16>,
+    confusion: Vec<T>,
+}
+
+/// Model of readout errors of a quantum system.
+///
+/// Each qubit can be assigned either an independent [`ReadoutError`], or
+/// belong to a group of qubits with a correlated confusion matrix.  Qubits
+/// not mentioned in the model are read out perfectly.
+///
+/// The model affects only reported outcomes: the state of the system is
+/// always projected onto the actual outcome of the measurement.  Correlated
+/// confusion matrices describe joint readout of a group of qubits, hence
+/// they apply only when all qubits are sampled at once, see
+/// [`System::sample()`].  A single qubit measured with [`Qubit::measure()`]
+/// is subject only to its independent readout error.
+///
+/// [`System::sample()`]: crate::System::sample
+/// [`Qubit::measure()`]: crate::Qubit::measure
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Bit, ReadoutError, ReadoutModel, System};
+/// let num_qubits = NonZeroU16::new(1).unwrap();
+/// let mut stm: System<f64> = System::new(num_qubits, 123);
+///
+/// let mut model = ReadoutModel::new();
+/// model
+///     .set_qubit(0, ReadoutError::new(1., 0.).unwrap())
+///     .unwrap();
+/// stm.set_readout_model(Some(model)).unwrap();
+///
+/// assert_eq!(stm.qubit(0).unwrap().measure(), Bit::ONE);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadoutModel<T> {
+    qubits: Vec<(u16, ReadoutError<T>)>,
+    groups: Vec<CorrelatedReadout<T>>,
+}
+
+impl<T> Default for ReadoutModel<T> {
+    fn default() -> Self {
+        Self {
+            qubits: Vec::new(),
+            groups: Vec::new(),
+        }
+    }
+}
+
+impl<T> ReadoutModel<T>
+where
+    T: Float,
+{
+    /// Model with perfect readout of all qubits.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if qubit `index` belongs to a correlated group.
+    fn is_correlated(
+        &self,
+        index: u16,
+    ) -> bool {
+        self.groups.iter().any(|g| g.qubits.contains(&index))
+    }
+
+    /// Assign an independent readout error to qubit `index`.
+    ///
+    /// Replaces previously assigned independent error of this qubit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if the qubit belongs to a correlated group.
+    pub fn set_qubit(
+        &mut self,
+        index: u16,
+        error: ReadoutError<T>,
+    ) -> Result<(), ReadoutModelError> {
+        if self.is_correlated(index) {
+            return Err(ReadoutModelError::InvalidQubits);
+        }
+        self.qubits.retain(|(i, _)| *i != index);
+        self.qubits.push((index, error));
+        Ok(())
+    }
+
+    /// Readout error of qubit `index`, if it was assigned an independent
+    /// one.
+    #[must_use]
+    pub fn qubit(
+        &self,
+        index: u16,
+    ) -> Option<&ReadoutError<T>> {
+        self.qubits
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, e)| e)
+    }
+
+    /// Assign a correlated confusion matrix to a group of qubits.
+    ///
+    /// The entry `confusion[read * 2^k + outcome]` is the probability of
+    /// reporting `read` if the actual outcome was `outcome`, where `k =
+    /// qubits.len()`.  The `j`-th listed qubit corresponds to the `j`-th bit
+    /// of `read` and `outcome`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error
+    /// - if `qubits` is empty or the indices are not distinct
+    /// - if any of the qubits has already been assigned a readout error
+    /// - if `confusion` is not a `2^k x 2^k` column-stochastic matrix
+    pub fn set_correlated(
+        &mut self,
+        qubits: &[u16],
+        confusion: Vec<T>,
+    ) -> Result<(), ReadoutModelError> {
+        if qubits.is_empty()
+            || qubits.iter().enumerate().any(|(k, q)| {
+                qubits[..k].contains(q)
+                    || self.is_correlated(*q)
+                    || self.qubit(*q).is_some()
+            })
+        {
+            return Err(ReadoutModelError::InvalidQubits);
+        }
+        let dim = 1usize << qubits.len();
+        if confusion.len() != dim * dim {
+            return Err(ReadoutModelError::InvalidDimension);
+        }
+        if confusion
+            .iter()
+            .any(|p| !(T::zero()..=T::one()).contains(p))
+        {
+            return Err(ReadoutModelError::InvalidProbability);
+        }
+        let tolerance = T::epsilon().sqrt();
+        for col in 0..dim {
+            let sum = (0..dim).map(|row| confusion[row * dim + col]).sum::<T>();
+            if (sum - T::one()).abs() > tolerance {
+                return Err(ReadoutModelError::NotStochastic);
+            }
+        }
+        self.groups.push(CorrelatedReadout {
+            qubits: qubits.to_vec(),
+            confusion,
+        });
+        Ok(())
+    }
+
+    /// Report the outcome of measuring qubit `index`, subject to its
+    /// independent readout error.
+    pub(crate) fn read_bit<R>(
+        &self,
+        index: u16,
+        outcome: bool,
+        rng: &mut R,
+    ) -> bool
+    where
+        R: Rng,
+    {
+        let Some(error) = self.qubit(index) else {
+            return outcome;
+        };
+        let p = T::to_f64(&error.flip_probability(outcome)).unwrap();
+        outcome ^ (rng.gen::<f64>() < p)
+    }
+
+    /// Report the outcome of measuring all qubits, where `index` is the
+    /// actual computational basis state.
+    pub(crate) fn read<R>(
+        &self,
+        index: usize,
+        rng: &mut R,
+    ) -> usize
+    where
+        R: Rng,
+    {
+        let mut read = index;
+        for (q, _) in &self.qubits {
+            let outcome = index >> q & 1 == 1;
+            if self.read_bit(*q, outcome, rng) != outcome {
+                read ^= 1 << q;
+            }
+        }
+        for group in &self.groups {
+            let dim = 1usize << group.qubits.len();
+            let outcome = gather(index, &group.qubits);
+            let draw = T::from(rng.gen::<f64>()).unwrap();
+            let mut cumulative = T::zero();
+            let mut reported = outcome;
+            for r in 0..dim {
+                let p = group.confusion[r * dim + outcome];
+                if p > T::zero() {
+                    cumulative += p;
+                    reported = r;
+                    if draw < cumulative {
+                        break;
+                    }
+                }
+            }
+            read = scatter(read, &group.qubits, reported);
+        }
+        read
+    }
+
+    /// Check if the model can be applied to a system of `num_qubits`
+    /// qubits.
+    ///
+    /// Returns `false`, if any qubit index in the model is larger or equal
+    /// than `num_qubits`.
+    #[must_use]
+    pub fn is_valid(
+        &self,
+        num_qubits: u16,
+    ) -> bool {
+        self.qubits
+            .iter()
+            .map(|(q, _)| q)
+            .chain(self.groups.iter().flat_map(|g| &g.qubits))
+            .all(|&q| q < num_qubits)
+    }
+
+    /// Mitigate readout errors in a histogram of measured outcomes.
+    ///
+    /// The entry `histogram[i]` is the number of shots, in which the
+    /// computational basis state `i` was reported.  The result is the
+    /// estimated distribution of actual outcomes, obtained by applying the
+    /// inverse of the confusion matrices to the normalized histogram.  It is
+    /// a quasi-probability distribution: the entries sum to one, but some of
+    /// them can be negative.
+    ///
+    /// Returns `None`
+    /// - if the length of `histogram` is not a power of two, or the histogram
+    ///   is empty
+    /// - if the model refers to qubits outside of the histogram
+    /// - if any of confusion matrices is singular
+    #[must_use]
+    pub fn mitigate(
+        &self,
+        histogram: &[u64],
+    ) -> Option<Vec<T>> {
+        if !histogram.len().is_power_of_two() {
+            return None;
+        }
+        let num_qubits = histogram.len().trailing_zeros();
+        let total = histogram.iter().sum::<u64>();
+        if total == 0 {
+            return None;
+        }
+
+        let total = T::from(total).unwrap();
+        let mut probs: Vec<T> = histogram
+            .iter()
+            .map(|&x| T::from(x).unwrap() / total)
+            .collect();
+
+        let groups = self
+            .qubits
+            .iter()
+            .map(|(q, e)| (vec![*q], e.confusion()))
+            .chain(
+                self.groups
+                    .iter()
+                    .map(|g| (g.qubits.clone(), g.confusion.clone())),
+            );
+        for (qubits, confusion) in groups {
+            if qubits.iter().any(|&q| u32::from(q) >= num_qubits) {
+                return None;
+            }
+            let dim = 1usize << qubits.len();
+            let mat: Vec<_> =
+                confusion.into_iter().map(Complex::from).collect();
+            let inv: Vec<_> = linalg::inverse(&mat, dim)?
+                .into_iter()
+                .map(|z| z.re)
+                .collect();
+            apply_matrix(&mut probs, &qubits, &inv);
+        }
+        Some(probs)
+    }
+}
+
+/// Collect bits `qubits` of `index`.
+fn gather(
+    index: usize,
+    qubits: &[u16],
+) -> usize {
+    qubits
+        .iter()
+        .enumerate()
+        .fold(0, |acc, (k, &q)| acc | (index >> q & 1) << k)
+}
+
+/// Replace bits `qubits` of `index` with bits of `value`.
+fn scatter(
+    index: usize,
+    qubits: &[u16],
+    value: usize,
+) -> usize {
+    qubits.iter().enumerate().fold(index, |acc, (k, &q)| {
+        (acc & !(1 << q)) | (value >> k & 1) << q
+    })
+}
+
+/// Apply a real `2^k x 2^k` matrix to `qubits` of a vector of probabilities.
+fn apply_matrix<T>(
+    probs: &mut [T],
+    qubits: &[u16],
+    mat: &[T],
+) where
+    T: Float,
+{
+    let dim = 1usize << qubits.len();
+    let mask = qubits.iter().fold(0, |acc, &q| acc | 1usize << q);
+    let mut block = vec![T::zero(); dim];
+    for base in (0..probs.len()).filter(|i| i & mask == 0) {
+        for (j, b) in block.iter_mut().enumerate() {
+            *b = probs[scatter(base, qubits, j)];
+        }
+        for r in 0..dim {
+            probs[scatter(base, qubits, r)] =
+                (0..dim).map(|c| mat[r * dim + c] * block[c]).sum();
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::ops::Range;
+
+use rayon::iter::{
+    IntoParallelIterator,
+    ParallelIterator,
+};
+
+use crate::Float;
+
+/// Number of amplitudes summed sequentially in reproducible reductions.
+const CHUNK: usize = 1 << 12;
+
+/// Sum `f(range)` over consecutive ranges of `0..len` of `CHUNK` elements.
+///
+/// The ranges are fixed and their partial sums are added sequentially, with
+/// compensated (Kahan) summation, so the result is bit-identical regardless of
+/// the number of threads, as long as `f` is deterministic.  The chunks are
+/// processed in parallel, if `parallel` is `true`.
+pub(crate) fn sum_chunks<T, F>(
+    len: usize,
+    parallel: bool,
+    f: F,
+) -> T
+where
+    T: Float,
+    F: Fn(Range<usize>) -> T + Sync,
+{
+    let range = |c: usize| c * CHUNK..((c + 1) * CHUNK).min(len);
+    let num_chunks = len.div_ceil(CHUNK);
+    if parallel {
+        let partial: Vec<T> = (0..num_chunks)
+            .into_par_iter()
+            .map(|c| f(range(c)))
+            .collect();
+        kahan_sum(partial)
+    } else {
+        kahan_sum((0..num_chunks).map(|c| f(range(c))))
+    }
+}
+
+/// Sum numbers in order, with compensated (Kahan) summation.
+fn kahan_sum<T>(xs: impl IntoIterator<Item = T>) -> T
+where
+    T: Float,
+{
+    let (mut sum, mut c) = (T::zero(), T::zero());
+    for x in xs {
+        let y = x - c;
+        let t = sum + y;
+        c = (t - sum) - y;
+        sum = t;
+    }
+    sum
+}
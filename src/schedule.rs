@@ -0,0 +1,363 @@
+use std::num::NonZeroU16;
+
+use num::Complex;
+use rayon::{
+    prelude::{
+        IndexedParallelIterator,
+        ParallelIterator,
+    },
+    slice::ParallelSliceMut,
+};
+
+use crate::{
+    gate::{
+        self,
+        Kernel,
+    },
+    soa,
+    Float,
+    Gate,
+};
+
+/// Default number of qubits of a block: `2^14` amplitudes, i.e. 256 KiB for
+/// `f64`, fit in the L2 cache of most CPUs.
+const DEFAULT_BLOCK_QUBITS: u16 = 14;
+
+/// Sequence of gates scheduled for cache-blocked execution on a state vector.
+///
+/// The state vector is split into blocks of `2^b` consecutive amplitudes,
+/// where `b` is the number of block qubits.  Gates acting only on qubits
+/// `0..b` are grouped into stages, and each stage is applied to one block at
+/// a time, so that the block stays in cache while all gates of the stage are
+/// applied.  Blocks are processed in parallel.
+///
+/// A gate acting on a qubit `>= b` is brought into a block by swapping that
+/// qubit with one of the low qubits.  The low qubit chosen is the one whose
+/// next use is farthest in the future.  Each swap is a single sweep over the
+/// state vector.  The original order of qubits is restored at the end of the
+/// schedule.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Gate, Schedule, System};
+/// let num_qubits = NonZeroU16::new(8).unwrap();
+/// let gates: Vec<_> = (0..8)
+///     .map(Gate::H)
+///     .chain((1..8).map(|k| Gate::CNOT(k - 1, k)))
+///     .collect();
+/// let schedule = Schedule::with_block_qubits(num_qubits, 4, &gates);
+///
+/// let mut stm: System<f64> = System::new(num_qubits, 123);
+/// stm.apply_schedule(&schedule);
+///
+/// let mut expected: System<f64> = System::new(num_qubits, 123);
+/// for gate in &gates {
+///     expected.apply(gate);
+/// }
+/// for (a, b) in stm.as_slice().iter().zip(expected.as_slice()) {
+///     assert!((a - b).norm() < 1e-12);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Schedule<T> {
+    num_qubits:   NonZeroU16,
+    block_qubits: u16,
+    stages:       Vec<Stage<T>>,
+    gates:        Vec<Gate<T>>,
+}
+
+#[derive(Debug, Clone)]
+enum Stage<T> {
+    /// Kernels acting on the block qubits, applied to each block in turn.
+    Local(Vec<Kernel<T>>),
+    /// Swap of two qubits of the state vector.
+    Swap(u16, u16),
+}
+
+impl<T> Schedule<T>
+where
+    T: Float,
+{
+    /// Schedule `gates` for a system of `num_qubits` qubits, with the default
+    /// block size of `2^14` amplitudes.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if any of the gates is not valid for the system, see
+    /// [`Gate::is_valid()`].
+    #[must_use]
+    pub fn new(
+        num_qubits: NonZeroU16,
+        gates: &[Gate<T>],
+    ) -> Self {
+        Self::with_block_qubits(num_qubits, DEFAULT_BLOCK_QUBITS, gates)
+    }
+
+    /// Schedule `gates` for a system of `num_qubits` qubits, with blocks of
+    /// `2^block_qubits` amplitudes.
+    ///
+    /// If `block_qubits` is larger than `num_qubits`, the whole state vector
+    /// is a single block.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `block_qubits` is less than 2, or if any of the gates is
+    /// not valid for the system, see [`Gate::is_valid()`].
+    #[must_use]
+    pub fn with_block_qubits(
+        num_qubits: NonZeroU16,
+        block_qubits: u16,
+        gates: &[Gate<T>],
+    ) -> Self {
+        assert!(block_qubits >= 2, "invalid block size: {block_qubits}");
+        let n = num_qubits.get();
+        let b = block_qubits.min(n);
+        for gate in gates {
+            assert!(gate.is_valid(n), "invalid gate: {gate:?}");
+        }
+
+        let mut plan = Plan {
+            phys:    (0..n).collect(),
+            logical: (0..n).collect(),
+            stages:  Vec::new(),
+            local:   Vec::new(),
+        };
+        for (i, gate) in gates.iter().enumerate() {
+            let qubits = gate.qubits();
+            for &q in &qubits {
+                if plan.phys(q) < b {
+                    continue;
+                }
+                let victim = farthest_next_use(
+                    &gates[i + 1..],
+                    (0..b)
+                        .map(|p| plan.logical[usize::from(p)])
+                        .filter(|l| !qubits.contains(l)),
+                );
+                plan.swap(plan.phys(q), plan.phys(victim));
+            }
+            let kernel = match gate.kernel() {
+                Kernel::One(q, m) => Kernel::One(plan.phys(q), m),
+                Kernel::Two(q0, q1, m) => {
+                    Kernel::Two(plan.phys(q0), plan.phys(q1), m)
+                }
+            };
+            plan.local.push(kernel);
+        }
+
+        // Restore the original order of qubits.
+        for q in 0..n {
+            let p = plan.phys(q);
+            if p != q {
+                plan.swap(q, p);
+            }
+        }
+        plan.flush();
+
+        Self {
+            num_qubits,
+            block_qubits: b,
+            stages: plan.stages,
+            gates: gates.to_vec(),
+        }
+    }
+
+    /// Get the number of qubits of the system.
+    #[must_use]
+    pub fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits
+    }
+
+    /// Get the number of qubits of a block.
+    ///
+    /// This is at most the number of qubits of the system.
+    #[must_use]
+    pub fn block_qubits(&self) -> u16 {
+        self.block_qubits
+    }
+
+    /// Get the number of sweeps over the state vector: one per stage of
+    /// gates acting on blocks, and one per qubit swap.
+    #[must_use]
+    pub fn num_sweeps(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Get the number of qubit swaps.
+    #[must_use]
+    pub fn num_swaps(&self) -> usize {
+        self.stages
+            .iter()
+            .filter(|stage| matches!(stage, Stage::Swap(..)))
+            .count()
+    }
+
+    /// Get the gates in the order they were scheduled.
+    pub(crate) fn gates(&self) -> &[Gate<T>] {
+        &self.gates
+    }
+
+    /// Apply the scheduled gates to the state vector `amp`.  Blocks are
+    /// processed in parallel, if `parallel` is `true`.
+    pub(crate) fn execute(
+        &self,
+        amp: &mut [Complex<T>],
+        parallel: bool,
+    ) {
+        for stage in &self.stages {
+            match stage {
+                Stage::Local(kernels) => {
+                    let apply = |block: &mut [Complex<T>]| {
+                        for kernel in kernels {
+                            gate::apply_kernel_seq(block, kernel);
+                        }
+                    };
+                    let size = 1 << self.block_qubits;
+                    if parallel {
+                        amp.par_chunks_mut(size).for_each(apply);
+                    } else {
+                        amp.chunks_mut(size).for_each(apply);
+                    }
+                }
+                Stage::Swap(p, q) => swap_qubits(amp, *p, *q, parallel),
+            }
+        }
+    }
+
+    /// Apply the scheduled gates to the state vector with real parts `re` and
+    /// imaginary parts `im`, see [`Schedule::execute()`].
+    pub(crate) fn execute_split(
+        &self,
+        re: &mut [T],
+        im: &mut [T],
+        parallel: bool,
+    ) {
+        for stage in &self.stages {
+            match stage {
+                Stage::Local(kernels) => {
+                    let apply = |(re, im): (&mut [T], &mut [T])| {
+                        for kernel in kernels {
+                            soa::apply_kernel_seq(re, im, kernel);
+                        }
+                    };
+                    let size = 1 << self.block_qubits;
+                    if parallel {
+                        re.par_chunks_mut(size)
+                            .zip(im.par_chunks_mut(size))
+                            .for_each(apply);
+                    } else {
+                        re.chunks_mut(size)
+                            .zip(im.chunks_mut(size))
+                            .for_each(apply);
+                    }
+                }
+                Stage::Swap(p, q) => {
+                    swap_qubits(re, *p, *q, parallel);
+                    swap_qubits(im, *p, *q, parallel);
+                }
+            }
+        }
+    }
+}
+
+/// Schedule under construction.
+struct Plan<T> {
+    /// Position of each qubit in the state vector.
+    phys:    Vec<u16>,
+    /// Qubit at each position.
+    logical: Vec<u16>,
+    stages:  Vec<Stage<T>>,
+    /// Kernels of the current local stage.
+    local:   Vec<Kernel<T>>,
+}
+
+impl<T> Plan<T> {
+    fn phys(
+        &self,
+        q: u16,
+    ) -> u16 {
+        self.phys[usize::from(q)]
+    }
+
+    /// Swap qubits at positions `p` and `q`.
+    fn swap(
+        &mut self,
+        p: u16,
+        q: u16,
+    ) {
+        self.flush();
+        self.stages.push(Stage::Swap(p, q));
+        let (i, j) =
+            (self.logical[usize::from(p)], self.logical[usize::from(q)]);
+        self.logical.swap(usize::from(p), usize::from(q));
+        self.phys[usize::from(i)] = q;
+        self.phys[usize::from(j)] = p;
+    }
+
+    /// Close the current local stage.
+    fn flush(&mut self) {
+        if !self.local.is_empty() {
+            self.stages
+                .push(Stage::Local(std::mem::take(&mut self.local)));
+        }
+    }
+}
+
+/// Find the candidate qubit whose next use in `gates` is farthest in the
+/// future, or that is not used at all.
+fn farthest_next_use<T>(
+    gates: &[Gate<T>],
+    candidates: impl Iterator<Item = u16>,
+) -> u16
+where
+    T: Float,
+{
+    let mut candidates: Vec<u16> = candidates.collect();
+    for gate in gates {
+        if candidates.len() == 1 {
+            break;
+        }
+        for q in gate.qubits() {
+            if candidates.len() > 1 {
+                candidates.retain(|&c| c != q);
+            }
+        }
+    }
+    *candidates.first().expect("block has a free qubit")
+}
+
+/// Swap qubits `p` and `q` of the state vector `amp`, or of the real or
+/// imaginary parts of its amplitudes.
+fn swap_qubits<A>(
+    amp: &mut [A],
+    p: u16,
+    q: u16,
+    parallel: bool,
+) where
+    A: Send,
+{
+    let (half_lo, half_hi) = (1usize << p.min(q), 1usize << p.max(q));
+    // Swap states with bits (lo, hi) = (1, 0) and (0, 1).
+    let swap = |(c0, c1): (&mut [A], &mut [A])| {
+        c0[half_lo..].swap_with_slice(&mut c1[..half_lo]);
+    };
+    // Chunks are few if the higher qubit is high (a single chunk for the
+    // highest qubit), so pairs of sub-chunks are swapped in parallel as well.
+    if parallel {
+        amp.par_chunks_mut(half_hi << 1).for_each(|chunk| {
+            let (h0, h1) = chunk.split_at_mut(half_hi);
+            h0.par_chunks_mut(half_lo << 1)
+                .zip(h1.par_chunks_mut(half_lo << 1))
+                .for_each(swap);
+        });
+    } else {
+        for chunk in amp.chunks_mut(half_hi << 1) {
+            let (h0, h1) = chunk.split_at_mut(half_hi);
+            h0.chunks_mut(half_lo << 1)
+                .zip(h1.chunks_mut(half_lo << 1))
+                .for_each(swap);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use num::{
+    Complex,
+    One,
+    Zero,
+};
+use rayon::{
+    iter::{
+        IndexedParallelIterator,
+        ParallelIterator,
+    },
+    slice::ParallelSliceMut,
+};
+
+use crate::{
+    gate::{
+        self,
+        Kernel,
+    },
+    Float,
+    Matrix2,
+    Matrix4,
+    Split,
+    System,
+};
+
+/// Quantum system of qubits, with real and imaginary parts of amplitudes
+/// stored in separate vectors (structure of arrays).
+///
+/// This is [`System`] with the [`Split`] layout: all its operations,
+/// including qubits, channels, readout errors and the journal, are
+/// available, but kernels operate on contiguous runs of real numbers, which
+/// vectorise better than interleaved complex numbers.  Use
+/// [`System::amplitude()`] or [`System::amplitudes()`] in place of
+/// [`System::as_slice()`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Gate, SoaSystem, Split, System};
+/// let num_qubits = NonZeroU16::new(2).unwrap();
+/// let mut stm: SoaSystem<f64> = System::with_layout(num_qubits, 123, Split);
+/// stm.apply(&Gate::H(0));
+/// stm.apply(&Gate::CNOT(0, 1));
+///
+/// let amp = stm.amplitude(0b11).unwrap();
+/// assert!((amp.re - 0.5f64.sqrt()).abs() < 1e-12);
+///
+/// let (mut qb0, mut qb1) = stm.qubit_pair(0, 1).unwrap();
+/// assert_eq!(qb0.measure(), qb1.measure());
+/// ```
+pub type SoaSystem<T> = System<T, Split>;
+
+/// Real and imaginary parts of amplitudes of a state vector.
+pub struct Parts<T> {
+    pub(crate) re: Vec<T>,
+    pub(crate) im: Vec<T>,
+}
+
+impl<T> System<T, Split>
+where
+    T: Float,
+{
+    /// Convert a [`System`] to structure-of-arrays storage.
+    ///
+    /// The RNG state, the readout model, the reproducible mode and the
+    /// parallelism are carried over.  The journal and the observer are not.
+    #[must_use]
+    pub fn from_system(stm: &System<T>) -> Self {
+        stm.to_layout(Parts {
+            re: stm.as_slice().iter().map(|a| a.re).collect(),
+            im: stm.as_slice().iter().map(|a| a.im).collect(),
+        })
+    }
+
+    /// Convert the state to a [`System`] with the default layout.
+    ///
+    /// The RNG state, the readout model, the reproducible mode, the
+    /// parallelism, the journal and the observer are carried over.
+    #[must_use]
+    pub fn into_system(self) -> System<T> {
+        let amp = self.amplitudes().collect();
+        self.into_layout(amp)
+    }
+
+    /// Get real parts of amplitudes of the computational basis states.
+    #[must_use]
+    pub fn re(&self) -> &[T] {
+        &self.amp().re
+    }
+
+    /// Get imaginary parts of amplitudes of the computational basis states.
+    #[must_use]
+    pub fn im(&self) -> &[T] {
+        &self.amp().im
+    }
+}
+
+/// Apply a gate kernel to the state vector with real parts `re` and
+/// imaginary parts `im`.
+pub(crate) fn apply_kernel<T>(
+    re: &mut [T],
+    im: &mut [T],
+    kernel: &Kernel<T>,
+) where
+    T: Float,
+{
+    match kernel {
+        Kernel::One(k, m) => {
+            let half = 1usize << k;
+            re.par_chunks_mut(half << 1)
+                .zip(im.par_chunks_mut(half << 1))
+                .for_each(|(re, im)| apply_one_chunk(re, im, half, m));
+        }
+        Kernel::Two(i, j, m) => {
+            let (half_lo, half_hi, m) = gate::reorder(*i, *j, m);
+            re.par_chunks_mut(half_hi << 1)
+                .zip(im.par_chunks_mut(half_hi << 1))
+                .for_each(|(re, im)| {
+                    apply_two_chunk(re, im, half_lo, half_hi, &m);
+                });
+        }
+    }
+}
+
+/// Apply a gate kernel to the state vector with real parts `re` and
+/// imaginary parts `im` on the current thread.
+///
+/// Used to apply kernels to chunks of a larger state processed in parallel.
+pub(crate) fn apply_kernel_seq<T>(
+    re: &mut [T],
+    im: &mut [T],
+    kernel: &Kernel<T>,
+) where
+    T: Float,
+{
+    match kernel {
+        Kernel::One(k, m) => {
+            let half = 1usize << k;
+            re.chunks_mut(half << 1)
+                .zip(im.chunks_mut(half << 1))
+                .for_each(|(re, im)| apply_one_chunk(re, im, half, m));
+        }
+        Kernel::Two(i, j, m) => {
+            let (half_lo, half_hi, m) = gate::reorder(*i, *j, m);
+            re.chunks_mut(half_hi << 1)
+                .zip(im.chunks_mut(half_hi << 1))
+                .for_each(|(re, im)| {
+                    apply_two_chunk(re, im, half_lo, half_hi, &m);
+                });
+        }
+    }
+}
+
+fn apply_one_chunk<T>(
+    re: &mut [T],
+    im: &mut [T],
+    half: usize,
+    m: &Matrix2<T>,
+) where
+    T: Float,
+{
+    let (re0, re1) = re.split_at_mut(half);
+    let (im0, im1) = im.split_at_mut(half);
+    if m[0][1].is_zero() && m[1][0].is_zero() {
+        // Diagonal phases.
+        if !m[0][0].is_one() {
+            T::scale_split(re0, im0, m[0][0]);
+        }
+        if !m[1][1].is_one() {
+            T::scale_split(re1, im1, m[1][1]);
+        }
+    } else {
+        T::rotate_split(re0, im0, re1, im1, m);
+    }
+}
+
+fn apply_two_chunk<T>(
+    re: &mut [T],
+    im: &mut [T],
+    half_lo: usize,
+    half_hi: usize,
+    m: &Matrix4<T>,
+) where
+    T: Float,
+{
+    // Offsets of the four amplitudes of each group, in the order of the
+    // matrix index `b_lo + 2 * b_hi`.
+    let offsets = [0, half_lo, half_hi, half_lo + half_hi];
+    for base in (0..half_hi).step_by(half_lo << 1) {
+        for i in base..base + half_lo {
+            let x = offsets.map(|o| Complex::new(re[i + o], im[i + o]));
+            let y = m.map(|row| {
+                row.iter().zip(&x).map(|(r, v)| r * v).sum::<Complex<T>>()
+            });
+            for (o, y) in offsets.iter().zip(y) {
+                (re[i + o], im[i + o]) = (y.re, y.im);
+            }
+        }
+    }
+}
@@ -0,0 +1,372 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    num::NonZeroU16,
+};
+
+use num::{
+    Complex,
+    Zero,
+};
+use rand::{
+    distributions::{
+        Bernoulli,
+        Distribution,
+    },
+    SeedableRng,
+};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    gate::Kernel,
+    Float,
+    Gate,
+    System,
+};
+
+/// Quantum system of qubits stored as a map from basis states to non-zero
+/// amplitudes.
+///
+/// Memory and time of each operation grow with the number of non-zero
+/// amplitudes, instead of `2^n`.  Once the number of non-zero amplitudes
+/// exceeds the dense threshold, the state is converted to a dense
+/// [`System`], and stays dense until [`reset()`].  The default threshold is
+/// `2^n / 64`.
+///
+/// Amplitudes whose squared modulus drops to `T::epsilon()^2` or below are
+/// removed from the map.
+///
+/// Amplitudes are kept in the order of basis states, so probabilities are
+/// summed in the same order in every run, and measurement outcomes depend
+/// only on the seed.  They agree with [`System`], up to rounding of
+/// probabilities.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Gate, SparseSystem};
+/// let num_qubits = NonZeroU16::new(40).unwrap();
+/// let mut stm: SparseSystem<f64> = SparseSystem::new(num_qubits, 123);
+///
+/// // GHZ state: two non-zero amplitudes.
+/// stm.apply(&Gate::H(0));
+/// for k in 1..40 {
+///     stm.apply(&Gate::CNOT(k - 1, k));
+/// }
+/// assert_eq!(stm.num_nonzero(), 2);
+/// assert!(!stm.is_dense());
+/// assert!((stm.probability(39).unwrap() - 0.5).abs() < 1e-12);
+/// ```
+///
+/// [`reset()`]: SparseSystem::reset
+pub struct SparseSystem<T>
+where
+    T: Float,
+{
+    num_qubits:      NonZeroU16,
+    dense_threshold: usize,
+    state:           State<T>,
+}
+
+enum State<T>
+where
+    T: Float,
+{
+    Sparse {
+        rng: ChaCha8Rng,
+        amp: BTreeMap<usize, Complex<T>>,
+    },
+    Dense(System<T>),
+}
+
+impl<T> SparseSystem<T>
+where
+    T: Float,
+{
+    /// Initialize a new quantum system of `n` qubits in the zero state.
+    ///
+    /// Seed internal RNG with `seed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if basis states of the system do not fit in `usize`, i.e. if
+    /// `n >= usize::BITS`.
+    #[must_use]
+    pub fn new(
+        num_qubits: NonZeroU16,
+        seed: u64,
+    ) -> Self {
+        assert!(
+            u32::from(num_qubits.get()) < usize::BITS,
+            "system does not fit in usize"
+        );
+        Self {
+            num_qubits,
+            dense_threshold: ((1usize << num_qubits.get()) >> 6).max(1),
+            state: State::Sparse {
+                rng: ChaCha8Rng::seed_from_u64(seed),
+                amp: BTreeMap::from([(0, Complex::from(T::one()))]),
+            },
+        }
+    }
+
+    /// Get the number of qubits.
+    #[must_use]
+    pub fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits
+    }
+
+    /// Set the largest number of non-zero amplitudes stored sparsely.
+    ///
+    /// If the state has more non-zero amplitudes than `threshold`, it is
+    /// converted to a dense state immediately.
+    pub fn set_dense_threshold(
+        &mut self,
+        threshold: usize,
+    ) {
+        self.dense_threshold = threshold;
+        self.densify_if_needed();
+    }
+
+    /// Get the largest number of non-zero amplitudes stored sparsely.
+    #[must_use]
+    pub fn dense_threshold(&self) -> usize {
+        self.dense_threshold
+    }
+
+    /// Check if the state has been converted to a dense state.
+    #[must_use]
+    pub fn is_dense(&self) -> bool {
+        matches!(self.state, State::Dense(_))
+    }
+
+    /// Get the number of stored amplitudes: the number of non-zero
+    /// amplitudes, or `2^n` if the state is dense.
+    #[must_use]
+    pub fn num_nonzero(&self) -> usize {
+        match &self.state {
+            State::Sparse {
+                amp, ..
+            } => amp.len(),
+            State::Dense(stm) => stm.as_slice().len(),
+        }
+    }
+
+    /// Get the amplitude of the computational basis state `index`.
+    ///
+    /// Returns `None`, if index is larger or equal than `2^n`.
+    #[must_use]
+    pub fn amplitude(
+        &self,
+        index: usize,
+    ) -> Option<Complex<T>> {
+        if index >> self.num_qubits.get() != 0 {
+            return None;
+        }
+        Some(match &self.state {
+            State::Sparse {
+                amp, ..
+            } => amp.get(&index).copied().unwrap_or_else(Complex::zero),
+            State::Dense(stm) => stm.as_slice()[index],
+        })
+    }
+
+    /// Reset all qubits to the zero state, stored sparsely.
+    ///
+    /// The RNG and the dense threshold are not affected.
+    pub fn reset(&mut self) {
+        let rng = match &self.state {
+            State::Sparse {
+                rng, ..
+            } => rng.clone(),
+            State::Dense(stm) => stm.rng().clone(),
+        };
+        self.state = State::Sparse {
+            rng,
+            amp: BTreeMap::from([(0, Complex::from(T::one()))]),
+        };
+    }
+
+    /// Apply a gate to the system.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the gate is not valid for this system, see
+    /// [`Gate::is_valid()`].
+    pub fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        assert!(
+            gate.is_valid(self.num_qubits.get()),
+            "invalid gate: {gate:?}"
+        );
+        let amp = match &mut self.state {
+            State::Sparse {
+                amp, ..
+            } => amp,
+            State::Dense(stm) => return stm.apply(gate),
+        };
+
+        match gate.kernel() {
+            Kernel::One(q, m) => {
+                let mask = 1usize << q;
+                apply_sparse(amp, mask, |base, a| {
+                    let (a0, a1) = (a(base), a(base | mask));
+                    [
+                        (base, m[0][0] * a0 + m[0][1] * a1),
+                        (base | mask, m[1][0] * a0 + m[1][1] * a1),
+                    ]
+                });
+            }
+            Kernel::Two(q0, q1, m) => {
+                let (m0, m1) = (1usize << q0, 1usize << q1);
+                apply_sparse(amp, m0 | m1, |base, a| {
+                    let idx = [base, base | m0, base | m1, base | m0 | m1];
+                    let x = idx.map(a);
+                    let mut out = [(0, Complex::zero()); 4];
+                    for (o, (i, row)) in out.iter_mut().zip(idx.iter().zip(m)) {
+                        *o = (*i, row.iter().zip(&x).map(|(g, x)| g * x).sum());
+                    }
+                    out
+                });
+            }
+        }
+        self.densify_if_needed();
+    }
+
+    /// Probability of measuring qubit `index` in the state `ONE`.
+    ///
+    /// Returns `None`, if index is larger or equal than `self.num_qubits()`
+    #[must_use]
+    pub fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T> {
+        if index >= self.num_qubits.get() {
+            return None;
+        }
+        match &self.state {
+            State::Sparse {
+                amp, ..
+            } => {
+                let mask = 1usize << index;
+                Some(
+                    amp.iter()
+                        .filter(|(i, _)| *i & mask == mask)
+                        .map(|(_, a)| a.norm_sqr())
+                        .sum(),
+                )
+            }
+            State::Dense(stm) => stm.probability(index),
+        }
+    }
+
+    /// Measure qubit `index` and project the state onto the outcome.
+    pub(crate) fn measure(
+        &mut self,
+        index: u16,
+    ) -> bool {
+        let amp_sq_1 = self.probability(index).unwrap();
+        let (rng, amp) = match &mut self.state {
+            State::Sparse {
+                rng,
+                amp,
+            } => (rng, amp),
+            State::Dense(stm) => return stm.measure(index),
+        };
+
+        let p = T::to_f64(&amp_sq_1).unwrap().clamp(0., 1.);
+        let outcome = Bernoulli::new(p).unwrap().sample(rng);
+
+        let norm_factor = if outcome {
+            amp_sq_1.sqrt()
+        } else {
+            (T::one() - amp_sq_1).sqrt()
+        };
+        let mask = 1usize << index;
+        let outcome_shifted = if outcome { mask } else { 0 };
+        amp.retain(|i, _| i & mask == outcome_shifted);
+        for a in amp.values_mut() {
+            *a /= norm_factor;
+        }
+        outcome
+    }
+
+    /// Convert the state to a dense [`System`].
+    ///
+    /// The RNG state is carried over.
+    #[must_use]
+    pub fn into_system(self) -> System<T> {
+        match self.state {
+            State::Sparse {
+                rng,
+                amp,
+            } => to_dense(self.num_qubits, rng, &amp),
+            State::Dense(stm) => stm,
+        }
+    }
+
+    fn densify_if_needed(&mut self) {
+        if let State::Sparse {
+            rng,
+            amp,
+        } = &self.state
+        {
+            if amp.len() > self.dense_threshold {
+                self.state =
+                    State::Dense(to_dense(self.num_qubits, rng.clone(), amp));
+            }
+        }
+    }
+}
+
+/// Apply a kernel acting on the qubits in `mask` to a sparse state.
+///
+/// Basis states are grouped by `base`, the index with the bits in `mask`
+/// cleared.  For each group that has a non-zero amplitude, `f` is given
+/// `base` and a lookup of the current amplitudes, and returns the new
+/// amplitudes of the group.
+fn apply_sparse<T, F, const N: usize>(
+    amp: &mut BTreeMap<usize, Complex<T>>,
+    mask: usize,
+    f: F,
+) where
+    T: Float,
+    F: Fn(usize, &dyn Fn(usize) -> Complex<T>) -> [(usize, Complex<T>); N],
+{
+    let bases: BTreeSet<usize> = amp.keys().map(|i| i & !mask).collect();
+    let tol = T::epsilon() * T::epsilon();
+    let mut next = BTreeMap::new();
+    for base in bases {
+        let lookup =
+            |i: usize| amp.get(&i).copied().unwrap_or_else(Complex::zero);
+        for (i, a) in f(base, &lookup) {
+            if a.norm_sqr() > tol {
+                next.insert(i, a);
+            }
+        }
+    }
+    *amp = next;
+}
+
+fn to_dense<T>(
+    num_qubits: NonZeroU16,
+    rng: ChaCha8Rng,
+    amp: &BTreeMap<usize, Complex<T>>,
+) -> System<T>
+where
+    T: Float,
+{
+    let mut stm = System::new(num_qubits, 0);
+    *stm.rng_mut() = rng;
+    let dense = stm.as_mut_slice();
+    dense[0] = Complex::zero();
+    for (&i, &a) in amp {
+        dense[i] = a;
+    }
+    stm
+}
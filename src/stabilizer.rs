@@ -0,0 +1,425 @@
+use std::{
+    marker::PhantomData,
+    num::NonZeroU16,
+};
+
+use rand::{
+    Rng,
+    SeedableRng,
+};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::{
+    IndexedParallelIterator,
+    IntoParallelRefMutIterator,
+    ParallelIterator,
+};
+
+use crate::{
+    BackendQubit,
+    Float,
+    Gate,
+};
+
+/// Row of the tableau: a Pauli operator with a sign, stored as bit masks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Row {
+    x: Vec<u64>,
+    z: Vec<u64>,
+    r: bool,
+}
+
+impl Row {
+    fn new(words: usize) -> Self {
+        Self {
+            x: vec![0; words],
+            z: vec![0; words],
+            r: false,
+        }
+    }
+
+    fn x(
+        &self,
+        k: usize,
+    ) -> bool {
+        self.x[k / 64] >> (k % 64) & 1 == 1
+    }
+
+    fn z(
+        &self,
+        k: usize,
+    ) -> bool {
+        self.z[k / 64] >> (k % 64) & 1 == 1
+    }
+
+    fn set_x(
+        &mut self,
+        k: usize,
+        value: bool,
+    ) {
+        let mask = 1 << (k % 64);
+        if value {
+            self.x[k / 64] |= mask;
+        } else {
+            self.x[k / 64] &= !mask;
+        }
+    }
+
+    fn set_z(
+        &mut self,
+        k: usize,
+        value: bool,
+    ) {
+        let mask = 1 << (k % 64);
+        if value {
+            self.z[k / 64] |= mask;
+        } else {
+            self.z[k / 64] &= !mask;
+        }
+    }
+
+    /// Multiply by `other` from the left, keeping track of the sign.
+    ///
+    /// The sign is meaningful only if both rows commute, so that the phase
+    /// of the product is real.
+    fn mul_assign(
+        &mut self,
+        other: &Self,
+    ) {
+        // Exponent of i in the product, modulo 4.  For each qubit, count +1
+        // and -1 contributions of the function g from Aaronson–Gottesman.
+        let mut phase = 2 * i64::from(self.r) + 2 * i64::from(other.r);
+        for w in 0..self.x.len() {
+            let (x1, z1) = (other.x[w], other.z[w]);
+            let (x2, z2) = (self.x[w], self.z[w]);
+            let plus = (x1 & z1 & z2 & !x2)
+                | (x1 & !z1 & z2 & x2)
+                | (!x1 & z1 & x2 & !z2);
+            let minus = (x1 & z1 & x2 & !z2)
+                | (x1 & !z1 & z2 & !x2)
+                | (!x1 & z1 & x2 & z2);
+            phase +=
+                i64::from(plus.count_ones()) - i64::from(minus.count_ones());
+            self.x[w] ^= x1;
+            self.z[w] ^= z1;
+        }
+        self.r = phase.rem_euclid(4) == 2;
+    }
+}
+
+/// Quantum system of qubits in a stabilizer state.
+///
+/// The state is represented by its stabilizer tableau (Aaronson–Gottesman),
+/// which takes `O(n^2)` bits of memory for `n` qubits, instead of `2^n`
+/// amplitudes.  Only Clifford gates can be applied, see
+/// [`GateKind::is_clifford()`].  Gates take `O(n)` time, and measurements
+/// take `O(n^2)` time.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Bit, Gate, StabilizerSystem};
+/// let num_qubits = NonZeroU16::new(1000).unwrap();
+/// let mut stm: StabilizerSystem<f64> = StabilizerSystem::new(num_qubits, 123);
+///
+/// // GHZ state
+/// stm.apply(&Gate::H(0));
+/// for k in 1..1000 {
+///     stm.apply(&Gate::CNOT(k - 1, k));
+/// }
+/// assert!(!stm.is_deterministic(999).unwrap());
+///
+/// let outcome = stm.qubit(0).unwrap().measure();
+/// assert!(stm.is_deterministic(999).unwrap());
+/// assert_eq!(stm.qubit(999).unwrap().measure(), outcome);
+/// ```
+///
+/// [`GateKind::is_clifford()`]: crate::GateKind::is_clifford
+pub struct StabilizerSystem<T>
+where
+    T: Float,
+{
+    rng:        ChaCha8Rng,
+    num_qubits: NonZeroU16,
+    // Destabilizers in rows 0..n, stabilizers in rows n..2n.
+    rows:       Vec<Row>,
+    phantom:    PhantomData<T>,
+}
+
+impl<T> StabilizerSystem<T>
+where
+    T: Float,
+{
+    /// Initialize a new quantum system of `n` qubits in the zero state.
+    ///
+    /// Seed internal RNG with `seed`.
+    #[must_use]
+    pub fn new(
+        num_qubits: NonZeroU16,
+        seed: u64,
+    ) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            num_qubits,
+            rows: zero_state(num_qubits),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Reset all qubits to the zero state.
+    ///
+    /// The RNG is not affected.
+    pub fn reset(&mut self) {
+        self.rows = zero_state(self.num_qubits);
+    }
+
+    /// Get the number of qubits.
+    #[must_use]
+    pub fn num_qubits(&self) -> NonZeroU16 {
+        self.num_qubits
+    }
+
+    /// Apply a Clifford gate to the system.
+    ///
+    /// # Panics
+    ///
+    /// Panics
+    /// - if the gate is not valid for this system, see [`Gate::is_valid()`]
+    /// - if the gate is not a Clifford gate, see [`GateKind::is_clifford()`]
+    ///
+    /// [`GateKind::is_clifford()`]: crate::GateKind::is_clifford
+    pub fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        assert!(
+            gate.is_valid(self.num_qubits.get()),
+            "invalid gate: {gate:?}"
+        );
+        assert!(gate.kind().is_clifford(), "non-Clifford gate: {gate:?}");
+
+        match *gate {
+            Gate::H(k) => self.h(k.into()),
+            Gate::S(k) => self.s(k.into()),
+            Gate::X(k) => self.for_each_row(|row| row.r ^= row.z(k.into())),
+            Gate::Z(k) => self.for_each_row(|row| row.r ^= row.x(k.into())),
+            Gate::Y(k) => self.for_each_row(|row| {
+                row.r ^= row.x(k.into()) ^ row.z(k.into());
+            }),
+            Gate::CNOT(c, t) => self.cnot(c.into(), t.into()),
+            Gate::CZ(c, t) => {
+                self.h(t.into());
+                self.cnot(c.into(), t.into());
+                self.h(t.into());
+            }
+            Gate::SWAP(i, j) => {
+                let (i, j) = (usize::from(i), usize::from(j));
+                self.for_each_row(|row| {
+                    let (xi, zi) = (row.x(i), row.z(i));
+                    row.set_x(i, row.x(j));
+                    row.set_z(i, row.z(j));
+                    row.set_x(j, xi);
+                    row.set_z(j, zi);
+                });
+            }
+            _ => unreachable!("Clifford gate"),
+        }
+    }
+
+    fn for_each_row<F>(
+        &mut self,
+        f: F,
+    ) where
+        F: Fn(&mut Row) + Sync + Send,
+    {
+        self.rows.par_iter_mut().for_each(f);
+    }
+
+    fn h(
+        &mut self,
+        k: usize,
+    ) {
+        self.for_each_row(|row| {
+            let (x, z) = (row.x(k), row.z(k));
+            row.r ^= x && z;
+            row.set_x(k, z);
+            row.set_z(k, x);
+        });
+    }
+
+    fn s(
+        &mut self,
+        k: usize,
+    ) {
+        self.for_each_row(|row| {
+            let (x, z) = (row.x(k), row.z(k));
+            row.r ^= x && z;
+            row.set_z(k, z ^ x);
+        });
+    }
+
+    fn cnot(
+        &mut self,
+        c: usize,
+        t: usize,
+    ) {
+        self.for_each_row(|row| {
+            let (xc, zc, xt, zt) = (row.x(c), row.z(c), row.x(t), row.z(t));
+            row.r ^= xc && zt && !(xt ^ zc);
+            row.set_x(t, xt ^ xc);
+            row.set_z(c, zc ^ zt);
+        });
+    }
+
+    /// Index of a stabilizer anticommuting with Z on qubit `k`, if any.
+    fn anticommuting(
+        &self,
+        k: usize,
+    ) -> Option<usize> {
+        let n = usize::from(self.num_qubits.get());
+        (n..2 * n).find(|&i| self.rows[i].x(k))
+    }
+
+    /// Check if the outcome of measuring qubit `index` is deterministic.
+    ///
+    /// Returns `None`, if index is larger or equal than `self.num_qubits()`
+    #[must_use]
+    pub fn is_deterministic(
+        &self,
+        index: u16,
+    ) -> Option<bool> {
+        if index >= self.num_qubits.get() {
+            return None;
+        }
+        Some(self.anticommuting(index.into()).is_none())
+    }
+
+    /// Outcome of measuring qubit `index`, known to be deterministic.
+    fn deterministic_outcome(
+        &self,
+        k: usize,
+    ) -> bool {
+        // Z_k is, up to the sign, the product of stabilizers whose
+        // destabilizers anticommute with it.
+        let n = usize::from(self.num_qubits.get());
+        let mut scratch = Row::new(self.rows[0].x.len());
+        for i in (0..n).filter(|&i| self.rows[i].x(k)) {
+            scratch.mul_assign(&self.rows[i + n]);
+        }
+        scratch.r
+    }
+
+    /// Probability of measuring qubit `index` in the state `ONE`.
+    ///
+    /// For a stabilizer state, the probability is either `0`, `1/2` or `1`.
+    ///
+    /// Returns `None`, if index is larger or equal than `self.num_qubits()`
+    #[must_use]
+    pub fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T> {
+        if self.is_deterministic(index)? {
+            Some(if self.deterministic_outcome(index.into()) {
+                T::one()
+            } else {
+                T::zero()
+            })
+        } else {
+            T::from(0.5)
+        }
+    }
+
+    /// Measure qubit `index` and collapse the state.
+    pub(crate) fn measure(
+        &mut self,
+        index: u16,
+    ) -> bool {
+        let k = usize::from(index);
+        let n = usize::from(self.num_qubits.get());
+        let Some(p) = self.anticommuting(k) else {
+            return self.deterministic_outcome(k);
+        };
+
+        // Random outcome: make all other rows commute with Z_k.
+        let pivot = self.rows[p].clone();
+        self.rows
+            .par_iter_mut()
+            .enumerate()
+            .filter(|(i, row)| *i != p && row.x(k))
+            .for_each(|(_, row)| row.mul_assign(&pivot));
+
+        let outcome = self.rng.gen::<bool>();
+        let mut stabilizer = Row::new(pivot.x.len());
+        stabilizer.set_z(k, true);
+        stabilizer.r = outcome;
+        self.rows[p - n] = pivot;
+        self.rows[p] = stabilizer;
+        outcome
+    }
+
+    /// Get a qubit.
+    ///
+    /// Returns `None`, if index is larger or equal than `self.num_qubits()`
+    pub fn qubit(
+        &mut self,
+        index: u16,
+    ) -> Option<StabilizerQubit<'_, T>> {
+        BackendQubit::new(self, index)
+    }
+
+    /// Get a pair of qubits.
+    ///
+    /// Returns `None`
+    /// - if any of indices is larger or equal than `self.num_qubits()`
+    /// - if indices are equal
+    pub fn qubit_pair(
+        &mut self,
+        index1: u16,
+        index2: u16,
+    ) -> Option<(StabilizerQubit<'_, T>, StabilizerQubit<'_, T>)> {
+        BackendQubit::new_pair(self, index1, index2)
+    }
+
+    /// Iterate over all qubits in the system.
+    pub fn qubit_iter(
+        &mut self
+    ) -> impl Iterator<Item = StabilizerQubit<'_, T>> {
+        BackendQubit::new_iter(self)
+    }
+}
+
+/// Tableau of the zero state: destabilizers `X_k`, stabilizers `Z_k`.
+fn zero_state(num_qubits: NonZeroU16) -> Vec<Row> {
+    let n = usize::from(num_qubits.get());
+    let words = n.div_ceil(64);
+    (0..2 * n)
+        .map(|i| {
+            let mut row = Row::new(words);
+            if i < n {
+                row.set_x(i, true);
+            } else {
+                row.set_z(i - n, true);
+            }
+            row
+        })
+        .collect()
+}
+
+/// A representation of a qubit in a quantum system in a stabilizer state.
+///
+/// See [`BackendQubit`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{Bit, Gate, StabilizerSystem};
+/// let num_qubits = NonZeroU16::new(2).unwrap();
+/// let mut stm: StabilizerSystem<f64> = StabilizerSystem::new(num_qubits, 123);
+/// stm.apply(&Gate::X(1));
+/// let mut qubit = stm.qubit(1).unwrap();
+///
+/// assert_eq!(qubit.measure(), Bit::ONE);
+/// ```
+pub type StabilizerQubit<'a, T> = BackendQubit<'a, T, StabilizerSystem<T>>;
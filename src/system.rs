@@ -1,32 +1,69 @@
-use std::num::NonZeroU16;
-
-use num::{
-    Complex,
-    Zero,
+use std::{
+    num::NonZeroU16,
+    thread::ThreadId,
 };
+
+use num::Complex;
 use rand::{
     distributions::{
         Bernoulli,
         BernoulliError,
         Distribution,
     },
+    Rng,
     SeedableRng,
 };
 use rand_chacha::ChaCha8Rng;
+use rayon::prelude::{
+    IntoParallelIterator,
+    ParallelIterator,
+};
 
 use crate::{
+    circuit,
+    gate::Kernel,
+    journal::Recorder,
+    parallel::Executor,
+    reduce,
+    Bit,
+    Channel,
+    Circuit,
+    Event,
+    EventKind,
     Float,
+    Gate,
+    Interleaved,
+    Layout,
+    Matrix2,
+    NoiseModel,
+    Observer,
+    Parallelism,
     Qubit,
+    ReadoutModel,
+    ReadoutModelError,
+    Schedule,
 };
 
 /// Quantum system of qubits
-pub struct System<T>
+///
+/// Amplitudes are stored according to the layout `L`: as complex numbers
+/// ([`Interleaved`], the default), or as separate real and imaginary parts
+/// ([`Split`], see [`SoaSystem`]).  See [`Layout`].
+///
+/// [`Split`]: crate::Split
+/// [`SoaSystem`]: crate::SoaSystem
+pub struct System<T, L = Interleaved>
 where
     T: Float,
+    L: Layout,
 {
-    rng:        ChaCha8Rng,
-    num_qubits: NonZeroU16,
-    amp:        Vec<Complex<T>>,
+    rng:          ChaCha8Rng,
+    num_qubits:   NonZeroU16,
+    amp:          L::Amp<T>,
+    readout:      Option<ReadoutModel<T>>,
+    reproducible: bool,
+    exec:         Executor,
+    recorder:     Recorder<T>,
 }
 
 impl<T> System<T>
@@ -55,12 +92,104 @@ where
         num_qubits: NonZeroU16,
         seed: u64,
     ) -> Self {
-        let mut amp = vec![Complex::zero(); 1usize << num_qubits.get()];
-        amp[0] = Complex::from(T::one());
+        Self::with_layout(num_qubits, seed, Interleaved)
+    }
+
+    /// Get complex amplitudes of the computational basis states.
+    #[must_use]
+    pub fn as_slice(&self) -> &[Complex<T>] {
+        &self.amp
+    }
+
+    /// Get mutable access to complex amplitudes of the computational basis
+    /// states
+    pub fn as_mut_slice(&mut self) -> &mut [Complex<T>] {
+        &mut self.amp
+    }
+}
+
+impl<T, L> System<T, L>
+where
+    T: Float,
+    L: Layout,
+{
+    /// Initialize a new quantum system of `n` qubits in the zero state, with
+    /// amplitudes stored according to `layout`.
+    ///
+    /// Seed internal RNG with `seed`.  See [`System::new()`] for the default
+    /// layout.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::num::NonZeroU16;
+    /// # use qn::{Gate, Split, System};
+    /// let num_qubits = NonZeroU16::new(2).unwrap();
+    /// let mut stm: System<f64, Split> =
+    ///     System::with_layout(num_qubits, 123, Split);
+    /// stm.apply(&Gate::X(1));
+    ///
+    /// assert_eq!(stm.re(), &[0., 0., 1., 0.]);
+    /// ```
+    #[must_use]
+    pub fn with_layout(
+        num_qubits: NonZeroU16,
+        seed: u64,
+        _layout: L,
+    ) -> Self {
         Self {
             rng: ChaCha8Rng::seed_from_u64(seed),
             num_qubits,
+            amp: L::zero_state(num_qubits),
+            readout: None,
+            reproducible: false,
+            exec: Executor::default(),
+            recorder: Recorder::default(),
+        }
+    }
+
+    /// Get the state vector.
+    pub(crate) fn amp(&self) -> &L::Amp<T> {
+        &self.amp
+    }
+
+    /// Copy the system to layout `M`, with state vector `amp`.
+    ///
+    /// The journal and the observer are not copied.
+    pub(crate) fn to_layout<M>(
+        &self,
+        amp: M::Amp<T>,
+    ) -> System<T, M>
+    where
+        M: Layout,
+    {
+        System {
+            rng: self.rng.clone(),
+            num_qubits: self.num_qubits,
             amp,
+            readout: self.readout.clone(),
+            reproducible: self.reproducible,
+            exec: self.exec.clone(),
+            recorder: Recorder::default(),
+        }
+    }
+
+    /// Convert the system to layout `M`, with state vector `amp`.
+    pub(crate) fn into_layout<M>(
+        self,
+        amp: M::Amp<T>,
+    ) -> System<T, M>
+    where
+        M: Layout,
+    {
+        System {
+            rng: self.rng,
+            num_qubits: self.num_qubits,
+            amp,
+            readout: self.readout,
+            reproducible: self.reproducible,
+            exec: self.exec,
+            recorder: self.recorder,
         }
     }
 
@@ -74,22 +203,548 @@ where
         Ok(Bernoulli::new(p)?.sample(&mut self.rng))
     }
 
+    /// Draw a number uniformly from the interval `[0, 1)`.
+    ///
+    /// Uses internal RNG.
+    pub(crate) fn uniform(&mut self) -> f64 {
+        self.rng.gen()
+    }
+
+    /// Get access to the internal RNG.
+    pub(crate) fn rng(&self) -> &ChaCha8Rng {
+        &self.rng
+    }
+
+    /// Get mutable access to the internal RNG.
+    pub(crate) fn rng_mut(&mut self) -> &mut ChaCha8Rng {
+        &mut self.rng
+    }
+
     /// Get the number of qubits.
     #[must_use]
     pub fn num_qubits(&self) -> NonZeroU16 {
         self.num_qubits
     }
 
-    /// Get complex amplitudes of the computational basis states.
+    /// Get the amplitude of the computational basis state `index`.
+    ///
+    /// Returns `None`, if index is larger or equal than `2^n`.
     #[must_use]
-    pub fn as_slice(&self) -> &[Complex<T>] {
-        &self.amp
+    pub fn amplitude(
+        &self,
+        index: usize,
+    ) -> Option<Complex<T>> {
+        (index < L::len(&self.amp)).then(|| L::get(&self.amp, index))
     }
 
-    /// Get mutable access to complex amplitudes of the computational basis
-    /// states
-    pub fn as_mut_slice(&mut self) -> &mut [Complex<T>] {
-        &mut self.amp
+    /// Iterate over complex amplitudes of the computational basis states, in
+    /// the order of [`System::as_slice()`].
+    pub fn amplitudes(&self) -> impl Iterator<Item = Complex<T>> + '_ {
+        (0..L::len(&self.amp)).map(|i| L::get(&self.amp, i))
+    }
+
+    /// Reset all qubits to the zero state.
+    ///
+    /// The RNG, the readout model, the reproducible mode, the parallelism and
+    /// the journal are not affected.
+    pub fn reset(&mut self) {
+        let amp = &mut self.amp;
+        self.exec
+            .run(L::len(amp), |parallel| L::reset(amp, parallel));
+        self.recorder.record(None, || EventKind::Reset);
+    }
+
+    /// Apply a gate to the system.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the gate is not valid for this system, see
+    /// [`Gate::is_valid()`].
+    pub fn apply(
+        &mut self,
+        gate: &Gate<T>,
+    ) {
+        assert!(
+            gate.is_valid(self.num_qubits.get()),
+            "invalid gate: {gate:?}"
+        );
+        self.apply_kernel(&gate.kernel());
+        self.recorder.record(None, || EventKind::Gate(*gate));
+    }
+
+    /// Apply single-qubit operator `m` to qubit `index`, where `m` is the
+    /// product of `gates` acting on that qubit, and record each of the gates
+    /// with the thread that requested it.
+    pub(crate) fn apply_fused(
+        &mut self,
+        index: u16,
+        m: &Matrix2<T>,
+        gates: &[(Gate<T>, ThreadId)],
+    ) {
+        self.apply_kernel(&Kernel::One(index, *m));
+        for (gate, thread) in gates {
+            self.recorder
+                .record(Some(*thread), || EventKind::Gate(*gate));
+        }
+    }
+
+    /// Apply gates scheduled for cache-blocked execution, see [`Schedule`].
+    ///
+    /// The result is the same as applying the gates one by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the schedule is for a different number of qubits.
+    pub fn apply_schedule(
+        &mut self,
+        schedule: &Schedule<T>,
+    ) {
+        assert_eq!(
+            schedule.num_qubits(),
+            self.num_qubits,
+            "schedule does not fit in the system"
+        );
+        let amp = &mut self.amp;
+        self.exec
+            .run(L::len(amp), |parallel| L::execute(amp, schedule, parallel));
+        for gate in schedule.gates() {
+            self.recorder.record(None, || EventKind::Gate(*gate));
+        }
+    }
+
+    /// Apply a quantum channel to `qubits` by sampling one of its Kraus
+    /// operators.
+    ///
+    /// This is a single step of a quantum trajectory (Monte Carlo
+    /// wavefunction) simulation: the Kraus operator `K_k` is chosen with
+    /// probability `p_k = |K_k psi|^2` using internal RNG, and the state
+    /// becomes `K_k psi / sqrt(p_k)`.  Averaged over many trajectories, this
+    /// reproduces the action of the channel on the density matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the channel cannot be applied to `qubits`, see
+    /// [`Channel::is_valid()`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::num::NonZeroU16;
+    /// # use qn::{Bit, Channel, Gate, System};
+    /// let num_qubits = NonZeroU16::new(1).unwrap();
+    /// let mut stm: System<f64> = System::new(num_qubits, 123);
+    /// stm.apply(&Gate::X(0));
+    /// stm.apply_channel(&Channel::amplitude_damping(1.).unwrap(), &[0]);
+    ///
+    /// assert_eq!(stm.qubit(0).unwrap().measure(), Bit::ZERO);
+    /// ```
+    pub fn apply_channel(
+        &mut self,
+        channel: &Channel<T>,
+        qubits: &[u16],
+    ) {
+        assert!(
+            channel.is_valid(qubits, self.num_qubits.get()),
+            "invalid channel qubits: {qubits:?}"
+        );
+
+        let uniform = self.uniform();
+        let draw = T::from(uniform).unwrap();
+        let mut cumulative = T::zero();
+        let mut branch = None;
+        for (k, kernel) in channel.kernels(qubits).into_iter().enumerate() {
+            // p_k = <psi|K_k^dagger K_k|psi>, without applying K_k
+            let (amp, len) = (&self.amp, L::len(&self.amp));
+            let reproducible = self.reproducible;
+            let p = self.exec.run(len, |parallel| {
+                let weight = |range| L::kernel_norm_sqr(amp, &kernel, range);
+                if reproducible {
+                    reduce::sum_chunks(len, parallel, weight)
+                } else if parallel {
+                    (0..len)
+                        .into_par_iter()
+                        .fold(|| T::zero(), |acc, i| acc + weight(i..i + 1))
+                        .sum::<T>()
+                } else {
+                    weight(0..len)
+                }
+            });
+            if p <= T::zero() {
+                continue;
+            }
+            cumulative += p;
+            branch = Some((k, kernel, p));
+            if draw < cumulative {
+                break;
+            }
+        }
+
+        let (kraus, kernel, p) = branch.expect("channel annihilates the state");
+        self.apply_kernel(&kernel);
+        let norm_factor = p.sqrt();
+        let amp = &mut self.amp;
+        self.exec.run(L::len(amp), |parallel| {
+            L::unscale(amp, norm_factor, parallel);
+        });
+        self.recorder.record(None, || EventKind::Channel {
+            qubits: qubits.to_vec(),
+            kraus,
+            draw: uniform,
+        });
+    }
+
+    /// Measure qubit `index` and project the state onto the outcome.
+    ///
+    /// Returns the actual outcome, not subject to readout errors.
+    pub(crate) fn measure(
+        &mut self,
+        index: u16,
+    ) -> bool {
+        let mask = 1usize << index;
+        let amp_sq_1 = self.probability(index).unwrap();
+
+        // project the state onto random outcome; rounding errors can push
+        // the probability slightly outside of [0, 1]
+        let p = T::to_f64(&amp_sq_1).unwrap().clamp(0., 1.);
+        let rng_word_pos = self.rng.get_word_pos();
+        let outcome = self.bernoulli(p).unwrap();
+        self.recorder.record(None, || EventKind::Measure {
+            index,
+            outcome: outcome.into(),
+            probability: amp_sq_1,
+            rng_word_pos,
+        });
+
+        // zero the amplitudes corresponding to (1-outcome), normalize the rest
+        let norm_factor = if outcome {
+            amp_sq_1.sqrt()
+        } else {
+            (T::one() - amp_sq_1).sqrt()
+        };
+        let outcome_shifted = if outcome { mask } else { 0 };
+        let amp = &mut self.amp;
+        self.exec.run(L::len(amp), |parallel| {
+            L::project(amp, mask, outcome_shifted, norm_factor, parallel);
+        });
+        outcome
+    }
+
+    /// Probability of measuring qubit `index` in the state `ONE`.
+    ///
+    /// Returns `None`, if index is larger or equal than `self.num_qubits()`
+    #[must_use]
+    pub fn probability(
+        &self,
+        index: u16,
+    ) -> Option<T> {
+        if index >= self.num_qubits.get() {
+            return None;
+        }
+        let half = 1usize << index;
+        let (amp, len) = (&self.amp, L::len(&self.amp));
+        // Sum over the upper half of the chunk of `2 * half` amplitudes
+        // starting at `start`.
+        let upper = |start: usize| {
+            L::norm_sqr_sum(amp, start + half..start + (half << 1))
+        };
+        let reproducible = self.reproducible;
+        Some(self.exec.run(len, |parallel| {
+            if reproducible {
+                reduce::sum_chunks(len, parallel, |range| {
+                    if half < range.len() {
+                        range
+                            .step_by(half << 1)
+                            .map(upper)
+                            .fold(T::zero(), |acc, p| acc + p)
+                    } else if range.start & half == half {
+                        L::norm_sqr_sum(amp, range)
+                    } else {
+                        T::zero()
+                    }
+                })
+            } else if parallel {
+                (0..len / (half << 1))
+                    .into_par_iter()
+                    .map(|c| upper(c * (half << 1)))
+                    .sum()
+            } else {
+                (0..len)
+                    .step_by(half << 1)
+                    .map(upper)
+                    .fold(T::zero(), |acc, p| acc + p)
+            }
+        }))
+    }
+
+    /// Set the threads used to run kernels on the state vector.
+    ///
+    /// See also [`System::set_sequential_threshold()`].
+    pub fn set_parallelism(
+        &mut self,
+        parallelism: Parallelism,
+    ) {
+        self.exec.parallelism = parallelism;
+    }
+
+    /// Get the threads used to run kernels on the state vector.
+    #[must_use]
+    pub fn parallelism(&self) -> &Parallelism {
+        &self.exec.parallelism
+    }
+
+    /// Set the number of amplitudes below which kernels run sequentially on
+    /// the current thread, regardless of [`System::parallelism()`].
+    ///
+    /// For small systems, the cost of dispatching work to other threads
+    /// exceeds the work itself.  The default is `2^12`.
+    pub fn set_sequential_threshold(
+        &mut self,
+        threshold: usize,
+    ) {
+        self.exec.threshold = threshold;
+    }
+
+    /// Get the number of amplitudes below which kernels run sequentially.
+    #[must_use]
+    pub fn sequential_threshold(&self) -> usize {
+        self.exec.threshold
+    }
+
+    /// Enable or disable reproducible reductions (disabled by default).
+    ///
+    /// In reproducible mode, probabilities are summed over fixed chunks of the
+    /// state vector, and the partial sums are added in order with compensated
+    /// summation.  The results, and hence measurement outcomes for a given
+    /// seed, are then bit-identical regardless of the number of threads.
+    /// Otherwise, the order of summation depends on how rayon splits the
+    /// work, which is faster, but can change the last bits of the result.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::num::NonZeroU16;
+    /// # use qn::{Gate, System};
+    /// let num_qubits = NonZeroU16::new(12).unwrap();
+    /// let mut stm: System<f64> = System::new(num_qubits, 123);
+    /// stm.set_reproducible(true);
+    /// for k in 0..12 {
+    ///     stm.apply(&Gate::Rx(k, 0.1 * f64::from(k)));
+    /// }
+    ///
+    /// let pool = rayon::ThreadPoolBuilder::new()
+    ///     .num_threads(1)
+    ///     .build()
+    ///     .unwrap();
+    /// let p = pool.install(|| stm.probability(11).unwrap());
+    /// assert_eq!(p.to_bits(), stm.probability(11).unwrap().to_bits());
+    /// ```
+    pub fn set_reproducible(
+        &mut self,
+        reproducible: bool,
+    ) {
+        self.reproducible = reproducible;
+    }
+
+    /// Check if reproducible reductions are enabled, see
+    /// [`System::set_reproducible()`].
+    #[must_use]
+    pub fn reproducible(&self) -> bool {
+        self.reproducible
+    }
+
+    /// Start recording operations performed on the system in a new, empty
+    /// journal, or stop recording and discard the journal.
+    ///
+    /// Each gate, measurement, channel, readout, sampling and reset is
+    /// recorded as an [`Event`], with the thread that performed it, see
+    /// [`EventKind`].  The journal is disabled by default.
+    pub fn set_journal(
+        &mut self,
+        enabled: bool,
+    ) {
+        self.recorder.set_journal(enabled);
+    }
+
+    /// Get events recorded so far.
+    ///
+    /// Returns `None`, if the journal is disabled, see
+    /// [`System::set_journal()`].
+    #[must_use]
+    pub fn journal(&self) -> Option<&[Event<T>]> {
+        self.recorder.journal()
+    }
+
+    /// Take events recorded so far, leaving the journal empty.
+    ///
+    /// The journal stays enabled, if it was.  Returns an empty vector, if
+    /// the journal is disabled.
+    pub fn take_journal(&mut self) -> Vec<Event<T>> {
+        self.recorder.take_journal()
+    }
+
+    /// Set the observer called for each operation performed on the system,
+    /// see [`Observer`].  Use `None` to remove it (the default).
+    ///
+    /// The observer is independent of the journal: it sees the same events,
+    /// whether the journal is enabled or not.
+    pub fn set_observer(
+        &mut self,
+        observer: Option<Box<dyn Observer<T>>>,
+    ) {
+        self.recorder.set_observer(observer);
+    }
+
+    /// Set the model of readout errors.
+    ///
+    /// The model affects outcomes reported by [`Qubit::measure()`] and
+    /// [`System::sample()`].  Use `None` for perfect readout (the default).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if the model refers to qubits outside of the system,
+    /// see [`ReadoutModel::is_valid()`].  The model is not changed then.
+    pub fn set_readout_model(
+        &mut self,
+        model: Option<ReadoutModel<T>>,
+    ) -> Result<(), ReadoutModelError> {
+        if let Some(model) = &model {
+            if !model.is_valid(self.num_qubits.get()) {
+                return Err(ReadoutModelError::InvalidQubits);
+            }
+        }
+        self.readout = model;
+        Ok(())
+    }
+
+    /// Get the model of readout errors.
+    #[must_use]
+    pub fn readout_model(&self) -> Option<&ReadoutModel<T>> {
+        self.readout.as_ref()
+    }
+
+    /// Report the outcome of measuring qubit `index`, subject to readout
+    /// errors.
+    pub(crate) fn read_out_bit(
+        &mut self,
+        index: u16,
+        outcome: bool,
+    ) -> bool {
+        let Some(model) = &self.readout else {
+            return outcome;
+        };
+        read_out_bit(model, &mut self.rng, &mut self.recorder, index, outcome)
+    }
+
+    /// Report the outcome of measuring qubit `index`, subject to readout
+    /// errors of `model` instead of the readout model of the system.
+    pub(crate) fn read_out_bit_with(
+        &mut self,
+        model: &ReadoutModel<T>,
+        index: u16,
+        outcome: bool,
+    ) -> bool {
+        read_out_bit(model, &mut self.rng, &mut self.recorder, index, outcome)
+    }
+
+    /// Sample outcomes of measuring all qubits, without changing the state.
+    ///
+    /// Each sample is the computational basis state reported by the
+    /// measurement, subject to readout errors, see
+    /// [`System::set_readout_model()`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::num::NonZeroU16;
+    /// # use qn::{Gate, System};
+    /// let num_qubits = NonZeroU16::new(2).unwrap();
+    /// let mut stm: System<f64> = System::new(num_qubits, 123);
+    /// stm.apply(&Gate::H(0));
+    /// stm.apply(&Gate::CNOT(0, 1));
+    ///
+    /// for s in stm.sample(10) {
+    ///     assert!(s == 0b00 || s == 0b11);
+    /// }
+    /// ```
+    pub fn sample(
+        &mut self,
+        shots: usize,
+    ) -> Vec<usize> {
+        let cumulative: Vec<T> = self
+            .amplitudes()
+            .scan(T::zero(), |acc, a| {
+                *acc += a.norm_sqr();
+                Some(*acc)
+            })
+            .collect();
+        let total = cumulative[cumulative.len() - 1];
+        let rng_word_pos = self.rng.get_word_pos();
+        self.recorder.record(None, || EventKind::Sample {
+            shots,
+            rng_word_pos,
+        });
+        let last = cumulative.len() - 1;
+
+        (0..shots)
+            .map(|_| {
+                let draw = T::from(self.uniform()).unwrap() * total;
+                let index =
+                    cumulative.partition_point(|&c| c <= draw).min(last);
+                match &self.readout {
+                    Some(model) => model.read(index, &mut self.rng),
+                    None => index,
+                }
+            })
+            .collect()
+    }
+
+    /// Sample outcomes of measuring all qubits and count them.
+    ///
+    /// Returns a vector of length `2^n`, whose `i`-th entry is the number of
+    /// shots, in which the computational basis state `i` was reported.  See
+    /// [`System::sample()`].
+    pub fn sample_histogram(
+        &mut self,
+        shots: usize,
+    ) -> Vec<u64> {
+        let mut hist = vec![0; L::len(&self.amp)];
+        for s in self.sample(shots) {
+            hist[s] += 1;
+        }
+        hist
+    }
+
+    /// Execute a circuit on the system.
+    ///
+    /// Returns reported outcomes of measurements in the order they appear in
+    /// the circuit.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the circuit has more qubits than the system.
+    pub fn run(
+        &mut self,
+        circuit: &Circuit<T>,
+    ) -> Vec<Bit> {
+        circuit.run(self)
+    }
+
+    /// Execute a circuit on the system, subject to noise.
+    ///
+    /// See [`NoiseModel`] for how noise is applied.
+    ///
+    /// Returns reported outcomes of measurements in the order they appear in
+    /// the circuit.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the circuit has more qubits than the system.
+    pub fn run_noisy(
+        &mut self,
+        circuit: &Circuit<T>,
+        noise: &NoiseModel<T>,
+    ) -> Vec<Bit> {
+        circuit::execute_noisy(circuit, self, noise)
     }
 
     /// Get a qubit.
@@ -98,7 +753,7 @@ where
     pub fn qubit(
         &mut self,
         index: u16,
-    ) -> Option<Qubit<'_, T>> {
+    ) -> Option<Qubit<'_, T, L>> {
         Qubit::new(self, index)
     }
 
@@ -113,12 +768,53 @@ where
         &mut self,
         index1: u16,
         index2: u16,
-    ) -> Option<(Qubit<'_, T>, Qubit<'_, T>)> {
+    ) -> Option<(Qubit<'_, T, L>, Qubit<'_, T, L>)> {
         Qubit::new_pair(self, index1, index2)
     }
 
     /// Create an iterator over all qubits in system.
-    pub fn qubit_iter(&mut self) -> impl Iterator<Item = Qubit<'_, T>> {
+    pub fn qubit_iter(&mut self) -> impl Iterator<Item = Qubit<'_, T, L>> {
         Qubit::new_iter(self)
     }
+
+    /// Apply a gate kernel to the state vector, in parallel or not.
+    fn apply_kernel(
+        &mut self,
+        kernel: &Kernel<T>,
+    ) {
+        let amp = &mut self.amp;
+        self.exec.run(L::len(amp), |parallel| {
+            L::apply_kernel(amp, kernel, parallel);
+        });
+    }
+}
+
+impl<T> AsRef<[Complex<T>]> for System<T>
+where
+    T: Float,
+{
+    fn as_ref(&self) -> &[Complex<T>] {
+        self.as_slice()
+    }
+}
+
+/// Report the outcome of measuring qubit `index`, subject to readout errors
+/// of `model`, and record the readout.
+fn read_out_bit<T>(
+    model: &ReadoutModel<T>,
+    rng: &mut ChaCha8Rng,
+    recorder: &mut Recorder<T>,
+    index: u16,
+    outcome: bool,
+) -> bool
+where
+    T: Float,
+{
+    let reported = model.read_bit(index, outcome, rng);
+    recorder.record(None, || EventKind::Readout {
+        index,
+        outcome: outcome.into(),
+        reported: reported.into(),
+    });
+    reported
 }
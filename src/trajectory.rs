@@ -0,0 +1,94 @@
+//! Quantum trajectories: averaging observables over many stochastic runs.
+//!
+//! Noise applied with [`System::apply_channel()`] samples a single Kraus
+//! branch per run.  Averaging an observable over many independently seeded
+//! runs estimates its expectation value under the noisy evolution, while
+//! keeping the memory footprint of a pure state.
+
+use std::num::NonZeroU16;
+
+use rayon::prelude::{
+    IntoParallelIterator,
+    ParallelIterator,
+};
+
+use crate::{
+    Float,
+    System,
+};
+
+/// Estimate of an expectation value obtained from quantum trajectories.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate<T> {
+    /// Sample mean of the observable.
+    pub mean:      T,
+    /// Standard error of the mean.
+    pub std_error: T,
+}
+
+/// Average an observable over `num_trajectories` independent runs.
+///
+/// Each run starts with a new system of `num_qubits` in the zero state.  The
+/// `i`-th run seeds the system's RNG with `seed + i` (wrapping on overflow),
+/// so the result is reproducible for a given `seed`.  The closure `run`
+/// evolves the system (applying gates, noise channels and measurements) and
+/// returns the value of the observable for this trajectory.
+///
+/// Trajectories are simulated in parallel.  The result does not depend on
+/// the number of threads.
+///
+/// Returns `None`, if `num_trajectories` is zero.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::num::NonZeroU16;
+/// # use qn::{trajectory, Channel, Gate};
+/// let num_qubits = NonZeroU16::new(1).unwrap();
+/// let channel = Channel::amplitude_damping(0.3).unwrap();
+///
+/// let estimate = trajectory::average(num_qubits, 1000, 123, |stm| {
+///     stm.apply(&Gate::X(0));
+///     stm.apply_channel(&channel, &[0]);
+///     stm.probability(0).unwrap()
+/// })
+/// .unwrap();
+///
+/// assert!((estimate.mean - 0.7_f64).abs() < 4. * estimate.std_error);
+/// ```
+pub fn average<T, F>(
+    num_qubits: NonZeroU16,
+    num_trajectories: u64,
+    seed: u64,
+    run: F,
+) -> Option<Estimate<T>>
+where
+    T: Float,
+    F: Fn(&mut System<T>) -> T + Sync,
+{
+    if num_trajectories == 0 {
+        return None;
+    }
+
+    let values: Vec<T> = (0..num_trajectories)
+        .into_par_iter()
+        .map(|i| {
+            let mut stm = System::new(num_qubits, seed.wrapping_add(i));
+            run(&mut stm)
+        })
+        .collect();
+
+    let n = T::from(num_trajectories).unwrap();
+    let mean = values.iter().copied().sum::<T>() / n;
+    let std_error = if num_trajectories > 1 {
+        let var = values.iter().map(|&x| (x - mean) * (x - mean)).sum::<T>()
+            / (n - T::one());
+        (var / n).sqrt()
+    } else {
+        T::zero()
+    };
+    Some(Estimate {
+        mean,
+        std_error,
+    })
+}
@@ -0,0 +1,283 @@
+//! Hamiltonian time evolution with Trotter–Suzuki product formulas.
+//!
+//! The evolution operator `exp(-i H t)` of a Hamiltonian `H = sum_k h_k P_k`,
+//! given as a [`PauliSum`], is approximated by a product of exponentials of
+//! single Pauli strings.  Each factor `exp(-i theta P)` is applied directly
+//! to the amplitude vector, pairing amplitudes with bit masks, without
+//! constructing any matrices.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use std::num::NonZeroU16;
+//! # use qn::{trotter, Pauli, PauliString, PauliSum, System};
+//! let num_qubits = NonZeroU16::new(2).unwrap();
+//! let mut ham = PauliSum::new();
+//! ham.add_term(
+//!     1.,
+//!     PauliString::new(&[(0, Pauli::X), (1, Pauli::X)]).unwrap(),
+//! );
+//! ham.add_term(0.5, PauliString::new(&[(0, Pauli::Z)]).unwrap());
+//!
+//! let mut stm: System<f64> = System::new(num_qubits, 123);
+//! let error =
+//!     trotter::error_estimate(&stm, &ham, 1., 20, trotter::Order::Second);
+//! assert!(error < 1e-2);
+//!
+//! trotter::evolve(&mut stm, &ham, 1., 20, trotter::Order::Second);
+//! ```
+//!
+//! [`PauliSum`]: crate::PauliSum
+
+use num::Complex;
+use rayon::prelude::{
+    IndexedParallelIterator,
+    IntoParallelRefMutIterator,
+    ParallelIterator,
+    ParallelSliceMut,
+};
+
+use crate::{
+    Float,
+    PauliString,
+    PauliSum,
+    System,
+};
+
+/// Order of a product formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Order {
+    /// Lie–Trotter formula: terms applied in order.
+    First,
+    /// Symmetric (Strang) formula: terms applied forward and backward, each
+    /// for half of the step.
+    Second,
+    /// Fourth-order Suzuki formula, composed of five second-order steps.
+    Fourth,
+}
+
+impl Order {
+    /// Order of the formula: the error of a single step of length `dt` is
+    /// `O(dt^(p+1))`, and the error of the whole evolution is `O(dt^p)`.
+    #[must_use]
+    pub fn as_u32(&self) -> u32 {
+        match self {
+            Self::First => 1,
+            Self::Second => 2,
+            Self::Fourth => 4,
+        }
+    }
+}
+
+/// Apply `exp(-i theta P)` to the system.
+///
+/// # Panics
+///
+/// Panics, if the Pauli string acts on qubits outside of the system.
+pub fn apply_exp<T>(
+    stm: &mut System<T>,
+    string: &PauliString,
+    theta: T,
+) where
+    T: Float,
+{
+    assert!(
+        string.num_qubits() <= stm.num_qubits().get(),
+        "Pauli string does not fit in the system"
+    );
+    exp_pauli(stm.as_mut_slice(), string, theta);
+}
+
+/// Evolve the system with the Hamiltonian `ham` for the time `time`, using
+/// `steps` steps of a product formula of the given order.
+///
+/// Does nothing, if `steps` is zero.
+///
+/// # Panics
+///
+/// Panics, if the Hamiltonian acts on qubits outside of the system.
+pub fn evolve<T>(
+    stm: &mut System<T>,
+    ham: &PauliSum<T>,
+    time: T,
+    steps: usize,
+    order: Order,
+) where
+    T: Float,
+{
+    assert!(
+        ham.num_qubits() <= stm.num_qubits().get(),
+        "Hamiltonian does not fit in the system"
+    );
+    evolve_amp(stm.as_mut_slice(), ham, time, steps, order);
+}
+
+/// Estimate the error of [`evolve()`] with `steps` steps.
+///
+/// The state is evolved, without modifying the system, once with `steps` and
+/// once with `2 * steps` steps.  If `d` is the Euclidean distance between
+/// the results and `p` is the order of the formula, the error of the first
+/// evolution is estimated as `d / (1 - 2^-p)`.
+///
+/// The estimate is reliable once the step is small enough for the error to
+/// scale as `dt^p`.
+///
+/// # Panics
+///
+/// Panics, if the Hamiltonian acts on qubits outside of the system.
+#[must_use]
+pub fn error_estimate<T>(
+    stm: &System<T>,
+    ham: &PauliSum<T>,
+    time: T,
+    steps: usize,
+    order: Order,
+) -> T
+where
+    T: Float,
+{
+    assert!(
+        ham.num_qubits() <= stm.num_qubits().get(),
+        "Hamiltonian does not fit in the system"
+    );
+    let mut coarse = stm.as_slice().to_vec();
+    evolve_amp(&mut coarse, ham, time, steps, order);
+    let mut fine = stm.as_slice().to_vec();
+    evolve_amp(&mut fine, ham, time, steps.saturating_mul(2), order);
+
+    let dist = coarse
+        .iter()
+        .zip(&fine)
+        .map(|(a, b)| (a - b).norm_sqr())
+        .sum::<T>()
+        .sqrt();
+    let p = T::from(order.as_u32()).unwrap();
+    let two = T::from(2.).unwrap();
+    dist / (T::one() - two.powf(-p))
+}
+
+fn evolve_amp<T>(
+    amp: &mut [Complex<T>],
+    ham: &PauliSum<T>,
+    time: T,
+    steps: usize,
+    order: Order,
+) where
+    T: Float,
+{
+    if steps == 0 {
+        return;
+    }
+    let dt = time / T::from(steps).unwrap();
+    for _ in 0..steps {
+        match order {
+            Order::First => first_order(amp, ham, dt),
+            Order::Second => second_order(amp, ham, dt),
+            Order::Fourth => fourth_order(amp, ham, dt),
+        }
+    }
+}
+
+fn first_order<T>(
+    amp: &mut [Complex<T>],
+    ham: &PauliSum<T>,
+    dt: T,
+) where
+    T: Float,
+{
+    for (coeff, string) in ham.terms() {
+        exp_pauli(amp, string, *coeff * dt);
+    }
+}
+
+fn second_order<T>(
+    amp: &mut [Complex<T>],
+    ham: &PauliSum<T>,
+    dt: T,
+) where
+    T: Float,
+{
+    let half = dt / T::from(2.).unwrap();
+    let terms = ham.terms();
+    for (coeff, string) in terms {
+        exp_pauli(amp, string, *coeff * half);
+    }
+    for (coeff, string) in terms.iter().rev() {
+        exp_pauli(amp, string, *coeff * half);
+    }
+}
+
+fn fourth_order<T>(
+    amp: &mut [Complex<T>],
+    ham: &PauliSum<T>,
+    dt: T,
+) where
+    T: Float,
+{
+    // S4(dt) = S2(p dt)^2 S2((1 - 4p) dt) S2(p dt)^2,
+    // with p = 1 / (4 - 4^(1/3)).
+    let four = T::from(4.).unwrap();
+    let p = T::one() / (four - four.cbrt());
+    let outer = p * dt;
+    let inner = (T::one() - four * p) * dt;
+    for step in [outer, outer, inner, outer, outer] {
+        second_order(amp, ham, step);
+    }
+}
+
+/// Apply `exp(-i theta P) = cos(theta) - i sin(theta) P` to the amplitude
+/// vector.
+pub(crate) fn exp_pauli<T>(
+    amp: &mut [Complex<T>],
+    string: &PauliString,
+    theta: T,
+) where
+    T: Float,
+{
+    let (x, z) = (string.x_mask(), string.z_mask());
+    let (s, c) = theta.sin_cos();
+
+    if x == 0 {
+        // Diagonal: exp(-i theta (-1)^popcount(j & z)).
+        let even = Complex::new(c, -s);
+        let odd = even.conj();
+        amp.par_iter_mut().enumerate().for_each(|(j, a)| {
+            if (j & z).count_ones().is_multiple_of(2) {
+                *a *= even;
+            } else {
+                *a *= odd;
+            }
+        });
+        return;
+    }
+
+    // -i sin(theta) i^(#Y)
+    let coeff = Complex::new(T::zero(), -s) * string.y_phase();
+    let c = Complex::from(c);
+    let sign = |j: usize| {
+        if (j & z).count_ones().is_multiple_of(2) {
+            T::one()
+        } else {
+            -T::one()
+        }
+    };
+
+    // Amplitudes j and j ^ x are paired.  Split the vector by the highest
+    // bit of x: the partner of j in the lower half of a chunk lies in the
+    // upper half of the same chunk.
+    let half = 1usize << (usize::BITS - 1 - x.leading_zeros());
+    let low = x & (half - 1);
+    amp.par_chunks_mut(half << 1)
+        .enumerate()
+        .for_each(|(n, chunk)| {
+            let base = n * (half << 1);
+            let (lo, hi) = chunk.split_at_mut(half);
+            for i in 0..half {
+                let (j, k) = (base + i, base + half + (i ^ low));
+                let (a, b) = (lo[i], hi[i ^ low]);
+                // (P amp)[j] = i^(#Y) (-1)^popcount(k & z) amp[k]
+                lo[i] = c * a + coeff * b * sign(k);
+                hi[i ^ low] = c * b + coeff * a * sign(j);
+            }
+        });
+}
@@ -0,0 +1,274 @@
+use std::{
+    num::{
+        NonZeroU16,
+        NonZeroUsize,
+    },
+    thread,
+};
+
+use qn::{
+    Backend,
+    BackendQubit,
+    Bit,
+    Circuit,
+    DensityMatrixSystem,
+    Gate,
+    MpsSystem,
+    StabilizerSystem,
+    System,
+};
+
+const NUM_QUBITS: u16 = 4;
+
+fn num_qubits() -> NonZeroU16 {
+    NonZeroU16::new(NUM_QUBITS).unwrap()
+}
+
+/// Clifford circuit preparing `|0110> + |1001>` (up to normalization), with
+/// gates on distant qubits.
+fn clifford_circuit() -> Vec<Gate<f64>> {
+    vec![
+        Gate::H(3),
+        Gate::CNOT(3, 0),
+        Gate::X(1),
+        Gate::CNOT(3, 1),
+        Gate::CNOT(1, 2),
+        Gate::S(0),
+        Gate::S(0),
+        Gate::Z(0),
+        Gate::CZ(0, 2),
+        Gate::SWAP(0, 2),
+        Gate::SWAP(0, 2),
+    ]
+}
+
+/// Probabilities of qubits after the Clifford circuit, then reset.
+fn probabilities<B>(stm: &mut B) -> Vec<f64>
+where
+    B: Backend<f64>,
+{
+    for gate in &clifford_circuit() {
+        stm.apply(gate);
+    }
+    let probs = (0..NUM_QUBITS)
+        .map(|k| stm.probability(k).unwrap())
+        .collect();
+    assert_eq!(stm.probability(NUM_QUBITS), None);
+
+    stm.reset();
+    for k in 0..NUM_QUBITS {
+        assert!(stm.probability(k).unwrap().abs() < 1e-12);
+    }
+    probs
+}
+
+/// Measure all qubits after the Clifford circuit.
+fn measure_all<B>(stm: &mut B) -> Vec<Bit>
+where
+    B: Backend<f64>,
+{
+    stm.reset();
+    for gate in &clifford_circuit() {
+        stm.apply(gate);
+    }
+    assert_eq!(stm.measure(NUM_QUBITS), None);
+    (0..NUM_QUBITS).map(|k| stm.measure(k).unwrap()).collect()
+}
+
+fn assert_outcomes(outcomes: &[Bit]) {
+    let bits: Vec<bool> = outcomes.iter().map(|&b| b.into()).collect();
+    assert!(
+        bits == [false, true, true, false]
+            || bits == [true, false, false, true],
+        "{bits:?}"
+    );
+}
+
+#[test]
+fn num_qubits_01() {
+    assert_eq!(
+        Backend::num_qubits(&System::<f64>::new(num_qubits(), 1)),
+        num_qubits()
+    );
+    assert_eq!(
+        Backend::num_qubits(&DensityMatrixSystem::<f64>::new(num_qubits(), 1)),
+        num_qubits()
+    );
+    assert_eq!(
+        Backend::num_qubits(&StabilizerSystem::<f64>::new(num_qubits(), 1)),
+        num_qubits()
+    );
+    assert_eq!(
+        Backend::num_qubits(&MpsSystem::<f64>::new(num_qubits(), 1)),
+        num_qubits()
+    );
+}
+
+#[test]
+fn probability_01() {
+    let expected = [0.5; 4];
+    let all = [
+        probabilities(&mut System::new(num_qubits(), 1)),
+        probabilities(&mut DensityMatrixSystem::new(num_qubits(), 1)),
+        probabilities(&mut StabilizerSystem::new(num_qubits(), 1)),
+        probabilities(&mut MpsSystem::new(num_qubits(), 1)),
+    ];
+    for probs in all {
+        for (p, q) in probs.iter().zip(&expected) {
+            assert!((p - q).abs() < 1e-12, "{probs:?}");
+        }
+    }
+}
+
+#[test]
+fn measure_01() {
+    for seed in 0..10 {
+        assert_outcomes(&measure_all(&mut System::new(num_qubits(), seed)));
+        assert_outcomes(&measure_all(&mut DensityMatrixSystem::new(
+            num_qubits(),
+            seed,
+        )));
+        assert_outcomes(&measure_all(&mut StabilizerSystem::new(
+            num_qubits(),
+            seed,
+        )));
+        assert_outcomes(&measure_all(&mut MpsSystem::new(num_qubits(), seed)));
+    }
+}
+
+#[test]
+fn reset_01() {
+    let mut stm = System::<f64>::new(num_qubits(), 1);
+    stm.apply(&Gate::H(0));
+    stm.apply(&Gate::CNOT(0, 3));
+    stm.reset();
+    assert_eq!(stm.as_slice()[0].re, 1.);
+    assert!(stm.as_slice()[1..].iter().all(|a| a.norm() == 0.));
+}
+
+#[test]
+fn reset_02() {
+    let mut stm = MpsSystem::<f64>::new(num_qubits(), 1);
+    stm.set_max_bond_dim(NonZeroUsize::new(1));
+    stm.apply(&Gate::H(0));
+    stm.apply(&Gate::CNOT(0, 1));
+    assert!(stm.truncation_error() > 0.);
+    stm.reset();
+    assert_eq!(stm.truncation_error(), 0.);
+    assert_eq!(stm.bond_dims(), vec![1, 1, 1]);
+    assert!(stm.max_bond_dim().is_some());
+}
+
+#[test]
+fn circuit_run_01() {
+    let mut circuit = Circuit::new(num_qubits());
+    for gate in clifford_circuit() {
+        circuit.apply(gate);
+    }
+    for k in 0..NUM_QUBITS {
+        circuit.measure(k);
+    }
+    for seed in 0..10 {
+        let outcomes = circuit.run(&mut System::new(num_qubits(), seed));
+        assert_outcomes(&outcomes);
+        let outcomes =
+            circuit.run(&mut DensityMatrixSystem::new(num_qubits(), seed));
+        assert_outcomes(&outcomes);
+        let outcomes =
+            circuit.run(&mut StabilizerSystem::new(num_qubits(), seed));
+        assert_outcomes(&outcomes);
+        let outcomes = circuit.run(&mut MpsSystem::new(num_qubits(), seed));
+        assert_outcomes(&outcomes);
+    }
+}
+
+#[test]
+fn circuit_run_02() {
+    let mut circuit = Circuit::<f64>::new(num_qubits());
+    circuit.apply(Gate::H(0));
+    circuit.apply(Gate::CNOT(0, 1));
+    circuit.measure(0);
+    circuit.measure(1);
+
+    let mut stm = System::new(num_qubits(), 123);
+    let expected = stm.run(&circuit);
+    let mut stm = System::new(num_qubits(), 123);
+    assert_eq!(circuit.run(&mut stm), expected);
+}
+
+#[test]
+#[should_panic(expected = "circuit does not fit in the system")]
+fn circuit_run_03() {
+    let circuit = Circuit::<f64>::new(NonZeroU16::new(5).unwrap());
+    circuit.run(&mut MpsSystem::new(num_qubits(), 1));
+}
+
+#[test]
+#[should_panic(expected = "non-Clifford gate")]
+fn circuit_run_04() {
+    let mut circuit = Circuit::<f64>::new(num_qubits());
+    circuit.apply(Gate::Rx(0, 0.1));
+    circuit.run(&mut StabilizerSystem::new(num_qubits(), 1));
+}
+
+/// Apply the Clifford circuit, then measure each qubit on its own thread.
+fn measure_threads<'a, B>(
+    qubits: impl Iterator<Item = BackendQubit<'a, f64, B>>
+) -> Vec<Bit>
+where
+    B: Backend<f64> + Send + 'a,
+{
+    let mut qubits: Vec<_> = qubits.collect();
+    for gate in &clifford_circuit() {
+        let k = usize::from(gate.qubits()[0]);
+        qubits[k].apply(gate);
+    }
+    thread::scope(|s| {
+        let handles: Vec<_> = qubits
+            .into_iter()
+            .map(|mut qb| s.spawn(move || qb.measure()))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
+
+#[test]
+fn backend_qubit_01() {
+    for seed in 0..10 {
+        let mut stm = DensityMatrixSystem::new(num_qubits(), seed);
+        assert_outcomes(&measure_threads(stm.qubit_iter()));
+        let mut stm = StabilizerSystem::new(num_qubits(), seed);
+        assert_outcomes(&measure_threads(stm.qubit_iter()));
+        let mut stm = MpsSystem::new(num_qubits(), seed);
+        assert_outcomes(&measure_threads(stm.qubit_iter()));
+    }
+}
+
+#[test]
+fn backend_qubit_02() {
+    let mut stm = StabilizerSystem::<f64>::new(num_qubits(), 1);
+    let mut other_stm = StabilizerSystem::<f64>::new(num_qubits(), 1);
+    let (qb0, qb1) = stm.qubit_pair(0, 3).unwrap();
+    let other_qb = other_stm.qubit(0).unwrap();
+
+    assert_eq!((qb0.index(), qb1.index()), (0, 3));
+    assert!(qb0.is_from_same_stm(&qb1));
+    assert!(!qb0.is_from_same_stm(&other_qb));
+}
+
+#[test]
+fn backend_qubit_03() {
+    let mut stm = MpsSystem::<f64>::new(num_qubits(), 1);
+    assert!(stm.qubit(NUM_QUBITS).is_none());
+    assert!(stm.qubit_pair(1, 1).is_none());
+    assert!(stm.qubit_pair(0, NUM_QUBITS).is_none());
+    assert!(BackendQubit::new(&mut stm, NUM_QUBITS - 1).is_some());
+}
+
+#[test]
+#[should_panic(expected = "invalid gate")]
+fn backend_qubit_04() {
+    let mut stm = DensityMatrixSystem::<f64>::new(num_qubits(), 1);
+    let mut qb = stm.qubit(0).unwrap();
+    qb.apply(&Gate::X(1));
+}
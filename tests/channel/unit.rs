@@ -0,0 +1,174 @@
+use std::num::NonZeroU16;
+
+use num::{
+    Complex,
+    One,
+    Zero,
+};
+use qn::{
+    Channel,
+    ChannelError,
+    DensityMatrixSystem,
+    Gate,
+};
+
+const EPS: f64 = 1e-12;
+
+fn gen_stm(num_qubits: u16) -> DensityMatrixSystem<f64> {
+    DensityMatrixSystem::new(NonZeroU16::new(num_qubits).unwrap(), 123)
+}
+
+fn assert_rho_eq(
+    stm: &DensityMatrixSystem<f64>,
+    expected: &[Complex<f64>],
+) {
+    assert_eq!(stm.as_slice().len(), expected.len());
+    for (x, y) in stm.as_slice().iter().zip(expected) {
+        assert!((x - y).norm() < EPS, "{x} != {y}");
+    }
+}
+
+#[test]
+fn invalid_01() {
+    assert_eq!(
+        Channel::<f64>::depolarizing(1.1),
+        Err(ChannelError::InvalidProbability)
+    );
+    assert_eq!(
+        Channel::<f64>::pauli(0.5, 0.4, 0.3),
+        Err(ChannelError::InvalidProbability)
+    );
+    assert_eq!(
+        Channel::<f64>::amplitude_damping(-0.1),
+        Err(ChannelError::InvalidProbability)
+    );
+    assert_eq!(
+        Channel::<f64>::thermal_relaxation(1., 2.5, 0.1, 0.),
+        Err(ChannelError::InvalidTimes)
+    );
+    assert_eq!(Channel::<f64>::from_kraus(vec![]), Err(ChannelError::Empty));
+}
+
+#[test]
+fn from_kraus_01() {
+    let (z, o) = (Complex::zero(), Complex::one());
+    assert_eq!(
+        Channel::<f64>::from_kraus(vec![[[o, z], [z, z]]]),
+        Err(ChannelError::NotTracePreserving)
+    );
+
+    let channel =
+        Channel::<f64>::from_kraus(vec![[[o, z], [z, z]], [[z, z], [z, o]]])
+            .unwrap();
+    assert_eq!(channel.num_qubits(), 1);
+    assert!(channel.is_valid(&[1], 2));
+    assert!(!channel.is_valid(&[2], 2));
+    assert!(!channel.is_valid(&[0, 1], 2));
+}
+
+#[test]
+#[should_panic(expected = "invalid channel qubits")]
+fn apply_invalid_01() {
+    let channel = Channel::depolarizing2(0.1).unwrap();
+    gen_stm(2).apply_channel(&channel, &[1, 1]);
+}
+
+#[test]
+fn bit_flip_01() {
+    let mut stm = gen_stm(2);
+    stm.apply_channel(&Channel::bit_flip(0.25).unwrap(), &[1]);
+
+    let mut expected = vec![Complex::zero(); 16];
+    expected[0] = Complex::from(0.75);
+    expected[2 * 4 + 2] = Complex::from(0.25);
+    assert_rho_eq(&stm, &expected);
+    assert!((stm.trace() - 1.).abs() < EPS);
+}
+
+#[test]
+fn phase_flip_01() {
+    let mut stm = gen_stm(1);
+    stm.apply(&Gate::H(0));
+    stm.apply_channel(&Channel::phase_flip(0.25).unwrap(), &[0]);
+    assert_rho_eq(&stm, &[0.5, 0.25, 0.25, 0.5].map(Complex::from));
+}
+
+#[test]
+fn depolarizing_01() {
+    let mut stm = gen_stm(1);
+    stm.apply(&Gate::Ry(0, 0.7));
+    stm.apply_channel(&Channel::depolarizing(0.75).unwrap(), &[0]);
+    assert_rho_eq(&stm, &[0.5, 0., 0., 0.5].map(Complex::from));
+    assert!((stm.purity() - 0.5).abs() < EPS);
+}
+
+#[test]
+fn depolarizing2_01() {
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::H(0));
+    stm.apply(&Gate::CNOT(0, 2));
+    stm.apply_channel(&Channel::depolarizing2(15. / 16.).unwrap(), &[2, 0]);
+
+    // qubits 0 and 2 are maximally mixed, qubit 1 is in the zero state
+    let mut expected = vec![Complex::zero(); 64];
+    for i in [0, 1, 4, 5] {
+        expected[i * 8 + i] = Complex::from(0.25);
+    }
+    assert_rho_eq(&stm, &expected);
+}
+
+#[test]
+fn amplitude_damping_01() {
+    let mut stm = gen_stm(1);
+    stm.apply(&Gate::H(0));
+    stm.apply_channel(&Channel::amplitude_damping(0.36).unwrap(), &[0]);
+    assert_rho_eq(&stm, &[0.68, 0.4, 0.4, 0.32].map(Complex::from));
+}
+
+#[test]
+fn phase_damping_01() {
+    let mut stm = gen_stm(1);
+    stm.apply(&Gate::H(0));
+    stm.apply_channel(&Channel::phase_damping(0.36).unwrap(), &[0]);
+    assert_rho_eq(&stm, &[0.5, 0.4, 0.4, 0.5].map(Complex::from));
+}
+
+#[test]
+fn thermal_relaxation_01() {
+    let (t1, t2, time, pe) = (2., 1.5, 0.8, 0.1);
+    let channel = Channel::thermal_relaxation(t1, t2, time, pe).unwrap();
+
+    let mut stm = gen_stm(1);
+    stm.apply(&Gate::H(0));
+    stm.apply_channel(&channel, &[0]);
+
+    let decay = (-time / t1).exp();
+    let p1 = pe + (0.5 - pe) * decay;
+    let coherence = 0.5 * (-time / t2).exp();
+    assert_rho_eq(
+        &stm,
+        &[1. - p1, coherence, coherence, p1].map(Complex::from),
+    );
+}
+
+#[test]
+fn from_kraus2_01() {
+    // measure qubit in computational basis and forget the outcome
+    let mut ops = vec![[[Complex::zero(); 4]; 4]; 4];
+    for (i, op) in ops.iter_mut().enumerate() {
+        op[i][i] = Complex::one();
+    }
+    let channel = Channel::from_kraus2(ops).unwrap();
+    assert_eq!(channel.num_qubits(), 2);
+
+    let mut stm = gen_stm(2);
+    stm.apply(&Gate::H(0));
+    stm.apply(&Gate::H(1));
+    stm.apply_channel(&channel, &[0, 1]);
+
+    let mut expected = vec![Complex::zero(); 16];
+    for i in 0..4 {
+        expected[i * 4 + i] = Complex::from(0.25);
+    }
+    assert_rho_eq(&stm, &expected);
+}
@@ -0,0 +1,130 @@
+use std::num::NonZeroU16;
+
+use qn::{
+    Bit,
+    Circuit,
+    DensityMatrixSystem,
+    Gate,
+    Operation,
+    System,
+};
+
+const EPS: f64 = 1e-12;
+
+fn num_qubits(n: u16) -> NonZeroU16 {
+    NonZeroU16::new(n).unwrap()
+}
+
+#[test]
+fn record_01() {
+    let mut circuit = Circuit::<f64>::new(num_qubits(2));
+    circuit.apply(Gate::H(0));
+    circuit.apply(Gate::CNOT(0, 1));
+    circuit.measure(1);
+
+    assert_eq!(circuit.num_qubits(), num_qubits(2));
+    assert_eq!(
+        circuit.operations(),
+        &[
+            Operation::Gate(Gate::H(0)),
+            Operation::Gate(Gate::CNOT(0, 1)),
+            Operation::Measure(1)
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "invalid gate")]
+fn record_invalid_gate() {
+    let mut circuit = Circuit::<f64>::new(num_qubits(2));
+    circuit.apply(Gate::CNOT(0, 2));
+}
+
+#[test]
+#[should_panic(expected = "invalid qubit index")]
+fn record_invalid_measure() {
+    let mut circuit = Circuit::<f64>::new(num_qubits(2));
+    circuit.measure(2);
+}
+
+#[test]
+#[should_panic(expected = "circuit does not fit in the system")]
+fn run_too_large() {
+    let circuit = Circuit::<f64>::new(num_qubits(3));
+    let mut stm = System::new(num_qubits(2), 123);
+    let _ = stm.run(&circuit);
+}
+
+#[test]
+fn run_01() {
+    let mut circuit = Circuit::new(num_qubits(3));
+    circuit.apply(Gate::H(0));
+    circuit.apply(Gate::Rx(1, 0.3));
+    circuit.apply(Gate::CNOT(0, 2));
+    circuit.apply(Gate::Phase(2, 0.7));
+
+    let mut stm = System::new(num_qubits(3), 123);
+    let outcomes = stm.run(&circuit);
+    assert!(outcomes.is_empty());
+
+    let mut expected = System::new(num_qubits(3), 123);
+    expected.apply(&Gate::H(0));
+    expected.apply(&Gate::Rx(1, 0.3));
+    expected.apply(&Gate::CNOT(0, 2));
+    expected.apply(&Gate::Phase(2, 0.7));
+
+    for (a, b) in stm.as_ref().iter().zip(expected.as_ref()) {
+        assert!((a - b).norm() < EPS);
+    }
+}
+
+#[test]
+fn run_measure_01() {
+    let mut circuit = Circuit::new(num_qubits(2));
+    circuit.apply(Gate::X(1));
+    circuit.measure(0);
+    circuit.measure(1);
+
+    let mut stm: System<f64> = System::new(num_qubits(2), 123);
+    assert_eq!(stm.run(&circuit), vec![Bit::ZERO, Bit::ONE]);
+}
+
+#[test]
+fn run_measure_bell() {
+    let mut circuit = Circuit::new(num_qubits(2));
+    circuit.apply(Gate::H(0));
+    circuit.apply(Gate::CNOT(0, 1));
+    circuit.measure(0);
+    circuit.measure(1);
+
+    for seed in 0..20 {
+        let mut stm: System<f64> = System::new(num_qubits(2), seed);
+        let outcomes = stm.run(&circuit);
+        assert_eq!(outcomes[0], outcomes[1]);
+
+        let mut stm: DensityMatrixSystem<f64> =
+            DensityMatrixSystem::new(num_qubits(2), seed);
+        let outcomes = stm.run(&circuit);
+        assert_eq!(outcomes[0], outcomes[1]);
+    }
+}
+
+#[test]
+fn run_density_01() {
+    let mut circuit = Circuit::new(num_qubits(2));
+    circuit.apply(Gate::H(0));
+    circuit.apply(Gate::CNOT(0, 1));
+
+    let mut stm: DensityMatrixSystem<f64> =
+        DensityMatrixSystem::new(num_qubits(2), 123);
+    stm.run(&circuit);
+
+    let mut expected = DensityMatrixSystem::new(num_qubits(2), 123);
+    expected.apply(&Gate::H(0));
+    expected.apply(&Gate::CNOT(0, 1));
+
+    for (a, b) in stm.as_slice().iter().zip(expected.as_slice()) {
+        assert!((a - b).norm() < EPS);
+    }
+    assert!((stm.purity() - 1.).abs() < EPS);
+}
@@ -0,0 +1,241 @@
+use std::num::NonZeroU16;
+
+use qn::{
+    Backend,
+    Bit,
+    DecisionDiagramSystem,
+    Gate,
+    System,
+};
+
+fn gen_stm(num_qubits: u16) -> DecisionDiagramSystem<f64> {
+    DecisionDiagramSystem::new(NonZeroU16::new(num_qubits).unwrap(), 123)
+}
+
+/// Random circuit, generated with a simple linear congruential generator.
+fn random_circuit(
+    num_qubits: u16,
+    len: usize,
+    seed: u64,
+) -> Vec<Gate<f64>> {
+    let mut state = seed;
+    let mut next = |m: u16| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        u16::try_from((state >> 33) % u64::from(m)).unwrap()
+    };
+    (0..len)
+        .map(|_| {
+            let a = next(num_qubits);
+            let b = (a + 1 + next(num_qubits - 1)) % num_qubits;
+            let theta = f64::from(next(1000)) / 100.;
+            match next(8) {
+                0 => Gate::H(a),
+                1 => Gate::S(a),
+                2 => Gate::Rx(a, theta),
+                3 => Gate::Ry(a, theta),
+                4 => Gate::Phase(a, theta),
+                5 => Gate::CNOT(a, b),
+                6 => Gate::CZ(a, b),
+                _ => Gate::SWAP(a, b),
+            }
+        })
+        .collect()
+}
+
+fn bits(
+    index: usize,
+    num_qubits: u16,
+) -> Vec<Bit> {
+    (0..num_qubits)
+        .map(|k| Bit::from((index >> k) & 1 == 1))
+        .collect()
+}
+
+fn assert_matches(
+    dd: &DecisionDiagramSystem<f64>,
+    sv: &System<f64>,
+) {
+    let num_qubits = dd.num_qubits().get();
+    for (i, amp) in sv.as_slice().iter().enumerate() {
+        let a = dd.amplitude(&bits(i, num_qubits)).unwrap();
+        assert!((a - amp).norm() < 1e-10, "index {i}: {a} != {amp}");
+    }
+    for k in 0..num_qubits {
+        let (p, q) = (dd.probability(k).unwrap(), sv.probability(k).unwrap());
+        assert!((p - q).abs() < 1e-10, "qubit {k}: {p} != {q}");
+    }
+}
+
+#[test]
+fn new_01() {
+    let stm = gen_stm(5);
+    assert_eq!(stm.num_qubits().get(), 5);
+    assert_eq!(stm.num_nodes(), 5);
+    assert_eq!(stm.amplitude(&[Bit::ZERO; 5]).unwrap().re, 1.);
+    assert_eq!(stm.amplitude(&[Bit::ONE; 5]).unwrap().re, 0.);
+    assert_eq!(stm.amplitude(&[Bit::ZERO; 4]), None);
+    assert_eq!(stm.probability(4), Some(0.));
+    assert_eq!(stm.probability(5), None);
+}
+
+#[test]
+#[should_panic(expected = "invalid gate")]
+fn apply_invalid_01() {
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::X(3));
+}
+
+#[test]
+fn random_circuit_01() {
+    for seed in 0..5 {
+        let num_qubits = 6;
+        let mut dd = gen_stm(num_qubits);
+        let mut sv = System::new(NonZeroU16::new(num_qubits).unwrap(), 1);
+        for gate in random_circuit(num_qubits, 80, seed) {
+            dd.apply(&gate);
+            sv.apply(&gate);
+        }
+        assert_matches(&dd, &sv);
+        assert!(dd.num_nodes() < 1 << num_qubits);
+    }
+}
+
+#[test]
+fn two_qubit_01() {
+    // Non-symmetric two-qubit gates in both orders.
+    let mut dd = gen_stm(4);
+    let mut sv = System::new(NonZeroU16::new(4).unwrap(), 1);
+    for gate in [
+        Gate::H(0),
+        Gate::Ry(2, 0.7),
+        Gate::CNOT(0, 3),
+        Gate::CNOT(3, 1),
+        Gate::Rx(3, 0.2),
+        Gate::CNOT(2, 0),
+        Gate::SWAP(3, 0),
+    ] {
+        dd.apply(&gate);
+        sv.apply(&gate);
+    }
+    assert_matches(&dd, &sv);
+}
+
+#[test]
+fn ghz_01() {
+    let mut stm = gen_stm(60);
+    stm.apply(&Gate::H(0));
+    for k in 1..60 {
+        stm.apply(&Gate::CNOT(k - 1, k));
+    }
+    assert_eq!(stm.num_nodes(), 119);
+    assert!((stm.probability(59).unwrap() - 0.5).abs() < 1e-12);
+
+    let outcome = stm.measure(30).unwrap();
+    assert_eq!(stm.num_nodes(), 60);
+    for k in 0..60 {
+        assert_eq!(stm.measure(k), Some(outcome));
+    }
+    assert_eq!(stm.measure(60), None);
+}
+
+#[test]
+fn cluster_state_01() {
+    // One-dimensional cluster state on 50 qubits: amplitudes
+    // (-1)^(sum x_k x_{k+1}) / 2^25.
+    let n = 50;
+    let mut stm = gen_stm(n);
+    for k in 0..n {
+        stm.apply(&Gate::H(k));
+    }
+    for k in 1..n {
+        stm.apply(&Gate::CZ(k - 1, k));
+    }
+    assert!(stm.num_nodes() <= 2 * usize::from(n));
+    for k in 0..n {
+        assert!((stm.probability(k).unwrap() - 0.5).abs() < 1e-12);
+    }
+
+    let mut x = vec![Bit::ZERO; usize::from(n)];
+    x[10] = Bit::ONE;
+    x[11] = Bit::ONE;
+    x[20] = Bit::ONE;
+    let amp = stm.amplitude(&x).unwrap();
+    assert!((amp.re + 0.5f64.powi(25)).abs() < 1e-20);
+
+    // Undo the circuit.
+    for k in 1..n {
+        stm.apply(&Gate::CZ(k - 1, k));
+    }
+    for k in 0..n {
+        stm.apply(&Gate::H(k));
+    }
+    assert_eq!(stm.num_nodes(), usize::from(n));
+    let amp = stm.amplitude(&vec![Bit::ZERO; usize::from(n)]).unwrap();
+    assert!((amp.re - 1.).abs() < 1e-10);
+}
+
+#[test]
+fn measure_01() {
+    // Same outcomes as a dense system with the same seed.
+    let num_qubits = 6;
+    let gates = random_circuit(num_qubits, 40, 5);
+    let mut dd = gen_stm(num_qubits);
+    let mut sv = System::new(NonZeroU16::new(num_qubits).unwrap(), 123);
+    for (i, gate) in gates.iter().enumerate() {
+        dd.apply(gate);
+        sv.apply(gate);
+        if i % 5 == 4 {
+            let k = u16::try_from(i % 6).unwrap();
+            assert_eq!(dd.measure(k), sv.measure(k));
+        }
+    }
+    assert_matches(&dd, &sv);
+}
+
+#[test]
+fn convert_01() {
+    let num_qubits = 5;
+    let mut sv = System::new(NonZeroU16::new(num_qubits).unwrap(), 7);
+    for gate in random_circuit(num_qubits, 40, 3) {
+        sv.apply(&gate);
+    }
+    let dd = DecisionDiagramSystem::from_system(&sv);
+    assert_matches(&dd, &sv);
+
+    let back = dd.to_system();
+    for (a, b) in back.as_slice().iter().zip(sv.as_slice()) {
+        assert!((a - b).norm() < 1e-12);
+    }
+
+    // The RNG state is carried over.
+    let mut dd = DecisionDiagramSystem::from_system(&sv);
+    let outcomes: Vec<_> = (0..num_qubits).map(|k| dd.measure(k)).collect();
+    let expected: Vec<_> = (0..num_qubits).map(|k| sv.measure(k)).collect();
+    assert_eq!(outcomes, expected);
+}
+
+#[test]
+fn reset_01() {
+    let mut stm = gen_stm(8);
+    for gate in random_circuit(8, 50, 1) {
+        stm.apply(&gate);
+    }
+    stm.reset();
+    assert_eq!(stm.num_nodes(), 8);
+    assert_eq!(stm.amplitude(&[Bit::ZERO; 8]).unwrap().re, 1.);
+}
+
+#[test]
+fn garbage_collection_01() {
+    // Many gates, each creating new nodes.
+    let num_qubits = 8;
+    let mut dd = gen_stm(num_qubits);
+    let mut sv = System::new(NonZeroU16::new(num_qubits).unwrap(), 1);
+    for gate in random_circuit(num_qubits, 500, 9) {
+        dd.apply(&gate);
+        sv.apply(&gate);
+    }
+    assert_matches(&dd, &sv);
+}
@@ -0,0 +1,141 @@
+use std::{
+    f64::consts::SQRT_2,
+    num::NonZeroU16,
+};
+
+use num::Complex;
+use qn::{
+    Bit,
+    DensityMatrixSystem,
+    Gate,
+    System,
+};
+
+const EPS: f64 = 1e-12;
+
+fn gen_stm(num_qubits: u16) -> DensityMatrixSystem<f64> {
+    DensityMatrixSystem::new(NonZeroU16::new(num_qubits).unwrap(), 123)
+}
+
+fn assert_rho_eq(
+    stm: &DensityMatrixSystem<f64>,
+    expected: &[Complex<f64>],
+) {
+    assert_eq!(stm.as_slice().len(), expected.len());
+    for (x, y) in stm.as_slice().iter().zip(expected) {
+        assert!((x - y).norm() < EPS, "{x} != {y}");
+    }
+}
+
+fn bell_rho() -> Vec<Complex<f64>> {
+    let mut rho = vec![Complex::from(0.); 16];
+    for i in [0, 3, 12, 15] {
+        rho[i] = Complex::from(0.5);
+    }
+    rho
+}
+
+#[test]
+fn init_01() {
+    let stm = gen_stm(2);
+    assert_eq!(stm.num_qubits().get(), 2);
+    assert_eq!(stm.as_slice().len(), 16);
+    assert!((stm.trace() - 1.).abs() < EPS);
+    assert!((stm.purity() - 1.).abs() < EPS);
+}
+
+#[test]
+fn qubit_01() {
+    let mut stm = gen_stm(2);
+    assert!(stm.qubit(1).is_some());
+    assert!(stm.qubit(2).is_none());
+    assert!(stm.qubit_pair(0, 0).is_none());
+
+    let (qb0, qb1) = stm.qubit_pair(1, 0).unwrap();
+    assert_eq!(qb0.index(), 1);
+    assert_eq!(qb1.index(), 0);
+    assert!(qb0.is_from_same_stm(&qb1));
+}
+
+#[test]
+fn apply_01() {
+    let mut stm = gen_stm(2);
+    stm.apply(&Gate::H(0));
+    stm.apply(&Gate::CNOT(0, 1));
+    assert_rho_eq(&stm, &bell_rho());
+    assert!((stm.purity() - 1.).abs() < EPS);
+}
+
+#[test]
+fn from_pure_01() {
+    let mut pure = System::new(NonZeroU16::new(3).unwrap(), 123);
+    let mut stm = gen_stm(3);
+    let gates = [
+        Gate::H(0),
+        Gate::Ry(1, 0.3),
+        Gate::CNOT(0, 2),
+        Gate::Rz(2, 1.1),
+        Gate::S(1),
+        Gate::SWAP(1, 2),
+    ];
+    for gate in &gates {
+        pure.apply(gate);
+        stm.apply(gate);
+    }
+    assert_rho_eq(&stm, DensityMatrixSystem::from(&pure).as_slice());
+}
+
+#[test]
+fn probability_01() {
+    let mut stm = gen_stm(2);
+    stm.apply(&Gate::Ry(1, 2. * (0.3_f64).sqrt().asin()));
+    assert!(stm.probability(0).unwrap().abs() < EPS);
+    assert!((stm.probability(1).unwrap() - 0.3).abs() < EPS);
+    assert!(stm.probability(2).is_none());
+}
+
+#[test]
+fn measure_01() {
+    let mut stm = gen_stm(2);
+    stm.apply(&Gate::H(0));
+    stm.apply(&Gate::CNOT(0, 1));
+
+    let (mut qb0, mut qb1) = stm.qubit_pair(0, 1).unwrap();
+    let outcome = qb0.measure();
+    assert_eq!(qb1.measure(), outcome);
+
+    let index = if outcome == Bit::ONE { 15 } else { 0 };
+    let mut expected = vec![Complex::from(0.); 16];
+    expected[index] = Complex::from(1.);
+    assert_rho_eq(&stm, &expected);
+}
+
+#[test]
+fn measure_02() {
+    // maximally mixed state of one qubit
+    let mut histogram = [0; 2];
+    for seed in 0..200 {
+        let mut stm: DensityMatrixSystem<f64> =
+            DensityMatrixSystem::new(NonZeroU16::new(1).unwrap(), seed);
+        stm.as_mut_slice()
+            .copy_from_slice(&[0.5, 0., 0., 0.5].map(Complex::from));
+        match stm.qubit(0).unwrap().measure() {
+            Bit::ZERO => histogram[0] += 1,
+            Bit::ONE => histogram[1] += 1,
+        }
+        assert!((stm.purity() - 1.).abs() < EPS);
+    }
+    assert!(histogram[0] > 70 && histogram[1] > 70, "{histogram:?}");
+}
+
+#[test]
+fn measure_03() {
+    let mut pure = System::new(NonZeroU16::new(1).unwrap(), 1);
+    pure.as_mut_slice()
+        .copy_from_slice(&[SQRT_2.recip(), SQRT_2.recip()].map(Complex::from));
+    let mut stm = DensityMatrixSystem::from(&pure);
+
+    // the RNG is cloned from the pure system
+    let outcome = pure.qubit(0).unwrap().measure();
+    assert_eq!(stm.qubit(0).unwrap().measure(), outcome);
+}
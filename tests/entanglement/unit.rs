@@ -0,0 +1,155 @@
+use std::{
+    f64::consts::SQRT_2,
+    num::NonZeroU16,
+};
+
+use num::Complex;
+use qn::{
+    entanglement::{
+        concurrence,
+        entropy_renyi,
+        entropy_von_neumann,
+        log_negativity,
+        reduced_density_matrix,
+        schmidt_coefficients,
+        schmidt_decomposition,
+    },
+    System,
+};
+
+const EPS: f64 = 1e-10;
+
+fn gen_stm(
+    num_qubits: u16,
+    amps: &[Complex<f64>],
+) -> System<f64> {
+    let num_qubits = NonZeroU16::new(num_qubits).unwrap();
+    let mut stm = System::new(num_qubits, 123);
+    stm.as_mut_slice().copy_from_slice(amps);
+    stm
+}
+
+fn bell() -> System<f64> {
+    let h = Complex::from(SQRT_2.recip());
+    gen_stm(2, &[h, 0.0.into(), 0.0.into(), h])
+}
+
+fn ghz3() -> System<f64> {
+    let h = Complex::from(SQRT_2.recip());
+    let mut amps = vec![Complex::from(0.); 8];
+    amps[0] = h;
+    amps[7] = h;
+    gen_stm(3, &amps)
+}
+
+#[test]
+fn invalid_indices_01() {
+    let stm = bell();
+    assert!(schmidt_coefficients(&stm, &[2]).is_none());
+    assert!(schmidt_coefficients(&stm, &[0, 0]).is_none());
+    assert!(concurrence(&stm, 1, 1).is_none());
+    assert!(log_negativity(&stm, &[0], &[0]).is_none());
+    assert!(entropy_renyi(&stm, &[0], -1.).is_none());
+}
+
+#[test]
+fn reduced_density_matrix_01() {
+    let rho = reduced_density_matrix(&bell(), &[0]).unwrap();
+    let expected = [0.5, 0., 0., 0.5];
+    for (x, y) in rho.iter().zip(expected) {
+        assert!((x - y).norm() < EPS);
+    }
+}
+
+#[test]
+fn schmidt_01() {
+    let coeffs = schmidt_coefficients(&bell(), &[1]).unwrap();
+    assert!((coeffs[0] - SQRT_2.recip()).abs() < EPS);
+    assert!((coeffs[1] - SQRT_2.recip()).abs() < EPS);
+}
+
+#[test]
+fn schmidt_02() {
+    // |psi> = 0.6 |0>|+> + 0.8 |1>|->
+    let a = 0.6 / SQRT_2;
+    let b = 0.8 / SQRT_2;
+    let amps: Vec<Complex<f64>> = [a, b, a, -b].map(Complex::from).into();
+    let stm = gen_stm(2, &amps);
+
+    let decomp = schmidt_decomposition(&stm, &[0]).unwrap();
+    assert!((decomp.coefficients[0] - 0.8).abs() < EPS);
+    assert!((decomp.coefficients[1] - 0.6).abs() < EPS);
+
+    for (i, amp) in amps.iter().enumerate() {
+        let sum: Complex<f64> = (0..2)
+            .map(|k| {
+                decomp.left[k][i & 1]
+                    * decomp.right[k][i >> 1]
+                    * decomp.coefficients[k]
+            })
+            .sum();
+        assert!((sum - amp).norm() < EPS);
+    }
+}
+
+#[test]
+fn entropy_01() {
+    let mut amps = [Complex::from(0.); 8];
+    amps[5] = 1.0.into();
+    let stm = gen_stm(3, &amps);
+    assert!(entropy_von_neumann(&stm, &[0]).unwrap().abs() < EPS);
+    assert!(entropy_von_neumann(&stm, &[2, 1]).unwrap().abs() < EPS);
+}
+
+#[test]
+fn entropy_02() {
+    let stm = ghz3();
+    for part in [&[0][..], &[1], &[0, 2], &[2, 1]] {
+        assert!((entropy_von_neumann(&stm, part).unwrap() - 1.).abs() < EPS);
+        for alpha in [0., 0.5, 1., 2., 3.] {
+            let s = entropy_renyi(&stm, part, alpha).unwrap();
+            assert!((s - 1.).abs() < EPS);
+        }
+    }
+}
+
+#[test]
+fn entropy_03() {
+    // Schmidt coefficients squared: 0.36 and 0.64
+    let amps: Vec<Complex<f64>> = [0.6, 0., 0., 0.8].map(Complex::from).into();
+    let stm = gen_stm(2, &amps);
+
+    let expected = -0.36 * 0.36_f64.log2() - 0.64 * 0.64_f64.log2();
+    assert!((entropy_von_neumann(&stm, &[0]).unwrap() - expected).abs() < EPS);
+
+    let expected = -(0.36_f64.powi(2) + 0.64_f64.powi(2)).log2();
+    assert!((entropy_renyi(&stm, &[1], 2.).unwrap() - expected).abs() < EPS);
+}
+
+#[test]
+fn concurrence_01() {
+    assert!((concurrence(&bell(), 0, 1).unwrap() - 1.).abs() < EPS);
+    assert!((concurrence(&bell(), 1, 0).unwrap() - 1.).abs() < EPS);
+
+    let amps: Vec<Complex<f64>> = [0.6, 0., 0., 0.8].map(Complex::from).into();
+    let stm = gen_stm(2, &amps);
+    assert!((concurrence(&stm, 0, 1).unwrap() - 0.96).abs() < EPS);
+}
+
+#[test]
+fn concurrence_02() {
+    // product state |+>|1>
+    let h = Complex::from(SQRT_2.recip());
+    let stm = gen_stm(2, &[0.0.into(), 0.0.into(), h, h]);
+    assert!(concurrence(&stm, 0, 1).unwrap().abs() < EPS);
+
+    // reduced state of GHZ is separable
+    assert!(concurrence(&ghz3(), 0, 2).unwrap().abs() < EPS);
+}
+
+#[test]
+fn log_negativity_01() {
+    assert!((log_negativity(&bell(), &[0], &[1]).unwrap() - 1.).abs() < EPS);
+    assert!(log_negativity(&ghz3(), &[0], &[1]).unwrap().abs() < EPS);
+    assert!((log_negativity(&ghz3(), &[0], &[1, 2]).unwrap() - 1.).abs() < EPS);
+}
@@ -0,0 +1,176 @@
+use std::num::NonZeroU16;
+
+use qn::{
+    Circuit,
+    Gate,
+    Operation,
+    System,
+};
+
+fn gen_circuit(num_qubits: u16) -> Circuit<f64> {
+    Circuit::new(NonZeroU16::new(num_qubits).unwrap())
+}
+
+/// Random circuit, generated with a simple linear congruential generator.
+fn random_circuit(
+    num_qubits: u16,
+    len: usize,
+    seed: u64,
+) -> Vec<Gate<f64>> {
+    let mut state = seed;
+    let mut next = |m: u16| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        u16::try_from((state >> 33) % u64::from(m)).unwrap()
+    };
+    (0..len)
+        .map(|_| {
+            let a = next(num_qubits);
+            let b = (a + 1 + next(num_qubits - 1)) % num_qubits;
+            let theta = f64::from(next(1000)) / 100.;
+            match next(8) {
+                0 => Gate::H(a),
+                1 => Gate::X(a),
+                2 => Gate::Rx(a, theta),
+                3 => Gate::Phase(a, theta),
+                4 => Gate::Rz(a, theta),
+                5 => Gate::CNOT(a, b),
+                6 => Gate::CZ(a, b),
+                _ => Gate::SWAP(a, b),
+            }
+        })
+        .collect()
+}
+
+fn assert_equivalent(
+    circuit: &Circuit<f64>,
+    optimized: &Circuit<f64>,
+) {
+    let num_qubits = circuit.num_qubits();
+    let mut expected = System::new(num_qubits, 1);
+    let mut stm = System::new(num_qubits, 1);
+    assert_eq!(circuit.run(&mut expected), optimized.run(&mut stm));
+    for (a, b) in expected.as_slice().iter().zip(stm.as_slice()) {
+        assert!((a - b).norm() < 1e-10, "{a} != {b}");
+    }
+}
+
+#[test]
+fn cancel_01() {
+    let mut circuit = gen_circuit(2);
+    circuit.apply(Gate::H(0));
+    circuit.apply(Gate::H(0));
+    assert!(circuit.optimize(1).operations().is_empty());
+    assert!(circuit.optimize(2).operations().is_empty());
+}
+
+#[test]
+fn cancel_02() {
+    let mut circuit = gen_circuit(3);
+    circuit.apply(Gate::CNOT(0, 2));
+    circuit.apply(Gate::X(1));
+    circuit.apply(Gate::CNOT(0, 2));
+    assert_eq!(
+        circuit.optimize(1).operations(),
+        &[Operation::Gate(Gate::X(1))]
+    );
+}
+
+#[test]
+fn fuse_01() {
+    let mut circuit = gen_circuit(2);
+    circuit.apply(Gate::H(0));
+    circuit.apply(Gate::Rx(0, 0.3));
+    circuit.apply(Gate::Y(1));
+    let optimized = circuit.optimize(1);
+    assert_eq!(optimized.operations().len(), 2);
+    assert!(matches!(
+        optimized.operations()[0],
+        Operation::Gate(Gate::U(0, _))
+    ));
+    assert_eq!(optimized.operations()[1], Operation::Gate(Gate::Y(1)));
+    assert_equivalent(&circuit, &optimized);
+}
+
+#[test]
+fn fuse_02() {
+    let mut circuit = gen_circuit(3);
+    circuit.apply(Gate::CNOT(0, 1));
+    circuit.apply(Gate::H(0));
+    circuit.apply(Gate::X(2));
+    circuit.apply(Gate::Rx(1, 0.3));
+    circuit.apply(Gate::CZ(1, 0));
+    let optimized = circuit.optimize(2);
+    assert_eq!(optimized.operations().len(), 2);
+    assert!(matches!(
+        optimized.operations()[0],
+        Operation::Gate(Gate::U2(0, 1, _))
+    ));
+    assert_equivalent(&circuit, &optimized);
+}
+
+#[test]
+fn commute_diagonal_01() {
+    let mut circuit = gen_circuit(2);
+    circuit.apply(Gate::Rz(0, 0.3));
+    circuit.apply(Gate::CZ(0, 1));
+    circuit.apply(Gate::Rz(0, 0.4));
+    let optimized = circuit.optimize(1);
+    assert_eq!(optimized.operations().len(), 2);
+    assert_equivalent(&circuit, &optimized);
+
+    circuit.apply(Gate::Rz(0, -0.7));
+    assert_eq!(
+        circuit.optimize(1).operations(),
+        &[Operation::Gate(Gate::CZ(0, 1))]
+    );
+}
+
+#[test]
+fn measure_01() {
+    let mut circuit = gen_circuit(2);
+    circuit.apply(Gate::H(0));
+    circuit.measure(0);
+    circuit.apply(Gate::H(0));
+    assert_eq!(circuit.optimize(2).operations(), circuit.operations());
+}
+
+#[test]
+fn measure_02() {
+    let mut circuit = gen_circuit(2);
+    circuit.apply(Gate::H(0));
+    circuit.measure(1);
+    circuit.apply(Gate::H(0));
+    assert_eq!(circuit.optimize(2).operations(), &[Operation::Measure(1)]);
+}
+
+#[test]
+fn random_circuit_01() {
+    for seed in 0..5 {
+        let mut circuit = gen_circuit(6);
+        for (i, gate) in random_circuit(6, 200, seed).into_iter().enumerate() {
+            circuit.apply(gate);
+            if i % 50 == 49 {
+                circuit.measure(u16::try_from(i % 6).unwrap());
+            }
+        }
+        for max_qubits in 1..=2 {
+            let optimized = circuit.optimize(max_qubits);
+            assert!(optimized.operations().len() < circuit.operations().len());
+            assert_equivalent(&circuit, &optimized);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "invalid number of qubits")]
+fn optimize_panic_01() {
+    let _ = gen_circuit(2).optimize(0);
+}
+
+#[test]
+#[should_panic(expected = "invalid number of qubits")]
+fn optimize_panic_02() {
+    let _ = gen_circuit(3).optimize(3);
+}
@@ -0,0 +1,135 @@
+use std::{
+    f64::consts::{
+        PI,
+        SQRT_2,
+    },
+    num::NonZeroU16,
+};
+
+use num::{
+    Complex,
+    One,
+    Zero,
+};
+use qn::{
+    metrics::{
+        approx_eq_up_to_phase,
+        max_error,
+    },
+    Gate,
+    System,
+};
+
+const EPS: f64 = 1e-12;
+
+fn gen_stm(num_qubits: u16) -> System<f64> {
+    System::new(NonZeroU16::new(num_qubits).unwrap(), 123)
+}
+
+fn basis(
+    num_qubits: u16,
+    index: usize,
+) -> Vec<Complex<f64>> {
+    let mut amps = vec![Complex::zero(); 1 << num_qubits];
+    amps[index] = Complex::one();
+    amps
+}
+
+#[test]
+fn is_valid_01() {
+    assert!(Gate::<f64>::H(1).is_valid(2));
+    assert!(!Gate::<f64>::H(2).is_valid(2));
+    assert!(Gate::<f64>::CNOT(1, 0).is_valid(2));
+    assert!(!Gate::<f64>::CNOT(1, 1).is_valid(2));
+    assert!(!Gate::<f64>::SWAP(0, 2).is_valid(2));
+}
+
+#[test]
+#[should_panic(expected = "invalid gate")]
+fn apply_invalid_01() {
+    gen_stm(2).apply(&Gate::CZ(0, 0));
+}
+
+#[test]
+fn single_qubit_01() {
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::X(1));
+    assert!(max_error(&stm, &basis(3, 2)[..]).unwrap() < EPS);
+
+    stm.apply(&Gate::Y(2));
+    let mut expected = basis(3, 6);
+    expected[6] = Complex::i();
+    assert!(max_error(&stm, &expected).unwrap() < EPS);
+
+    stm.apply(&Gate::Z(1));
+    stm.apply(&Gate::S(2));
+    expected[6] = Complex::from(1.);
+    assert!(max_error(&stm, &expected).unwrap() < EPS);
+}
+
+#[test]
+fn single_qubit_02() {
+    let mut stm = gen_stm(2);
+    stm.apply(&Gate::H(0));
+    let h = Complex::from(SQRT_2.recip());
+    let expected = [h, h, Complex::zero(), Complex::zero()];
+    assert!(max_error(&stm, &expected[..]).unwrap() < EPS);
+
+    stm.apply(&Gate::H(0));
+    assert!(max_error(&stm, &basis(2, 0)[..]).unwrap() < EPS);
+}
+
+#[test]
+fn rotations_01() {
+    let mut stm = gen_stm(1);
+    stm.apply(&Gate::Rx(0, PI));
+    assert!(approx_eq_up_to_phase(&stm, &basis(1, 1)[..], EPS));
+
+    stm.apply(&Gate::Ry(0, PI));
+    assert!(approx_eq_up_to_phase(&stm, &basis(1, 0)[..], EPS));
+
+    stm.apply(&Gate::Ry(0, PI / 2.));
+    stm.apply(&Gate::Rz(0, PI / 2.));
+    stm.apply(&Gate::Phase(0, -PI / 2.));
+    let h = Complex::from(SQRT_2.recip());
+    assert!(approx_eq_up_to_phase(&stm, &[h, h][..], EPS));
+}
+
+#[test]
+fn two_qubit_01() {
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::H(2));
+    stm.apply(&Gate::CNOT(2, 0));
+    let h = Complex::from(SQRT_2.recip());
+    let mut expected = vec![Complex::zero(); 8];
+    expected[0] = h;
+    expected[5] = h;
+    assert!(max_error(&stm, &expected).unwrap() < EPS);
+
+    stm.apply(&Gate::CZ(0, 2));
+    expected[5] = -h;
+    assert!(max_error(&stm, &expected).unwrap() < EPS);
+
+    stm.apply(&Gate::SWAP(2, 1));
+    expected.swap(5, 3);
+    assert!(max_error(&stm, &expected).unwrap() < EPS);
+}
+
+#[test]
+fn two_qubit_02() {
+    // CNOT given as a general two-qubit unitary, with reversed qubit order
+    let (o, z) = (Complex::one(), Complex::zero());
+    let cnot = [[o, z, z, z], [z, z, z, o], [z, z, o, z], [z, o, z, z]];
+    for (control, target) in [(0, 1), (1, 0), (0, 2), (2, 0)] {
+        let mut stm = gen_stm(3);
+        stm.apply(&Gate::X(control));
+        let mut other = gen_stm(3);
+        other.apply(&Gate::X(control));
+
+        stm.apply(&Gate::CNOT(control, target));
+        other.apply(&Gate::U2(control, target, cnot));
+        let index = (1 << control) | (1 << target);
+        assert!(max_error(&stm, &basis(3, index)[..]).unwrap() < EPS);
+        assert!(max_error(&stm, &other).unwrap() < EPS);
+    }
+}
@@ -0,0 +1,152 @@
+use std::num::NonZeroU16;
+
+use qn::{
+    hybrid::{
+        self,
+        HybridError,
+    },
+    Bit,
+    Circuit,
+    Gate,
+    System,
+};
+
+/// Random circuit, generated with a simple linear congruential generator.
+fn random_circuit(
+    num_qubits: u16,
+    len: usize,
+    seed: u64,
+) -> Circuit<f64> {
+    let mut state = seed;
+    let mut next = |m: u16| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        u16::try_from((state >> 33) % u64::from(m)).unwrap()
+    };
+    let mut circuit = Circuit::new(NonZeroU16::new(num_qubits).unwrap());
+    for _ in 0..len {
+        let a = next(num_qubits);
+        let b = (a + 1 + next(num_qubits - 1)) % num_qubits;
+        let theta = f64::from(next(1000)) / 100.;
+        circuit.apply(match next(8) {
+            0 => Gate::H(a),
+            1 => Gate::S(a),
+            2 => Gate::Rx(a, theta),
+            3 => Gate::Ry(a, theta),
+            4 => Gate::Phase(a, theta),
+            5 => Gate::CNOT(a, b),
+            6 => Gate::CZ(a, b),
+            _ => Gate::SWAP(a, b),
+        });
+    }
+    circuit
+}
+
+fn bits(
+    index: usize,
+    num_qubits: u16,
+) -> Vec<Bit> {
+    (0..num_qubits)
+        .map(|k| Bit::from((index >> k) & 1 == 1))
+        .collect()
+}
+
+#[test]
+fn invalid_01() {
+    let mut circuit = Circuit::<f64>::new(NonZeroU16::new(4).unwrap());
+    circuit.apply(Gate::H(0));
+    assert_eq!(hybrid::num_paths(&circuit, 0), Err(HybridError::InvalidCut));
+    assert_eq!(hybrid::num_paths(&circuit, 4), Err(HybridError::InvalidCut));
+    assert_eq!(
+        hybrid::amplitudes(&circuit, 2, &[vec![Bit::ZERO; 3]]),
+        Err(HybridError::InvalidBitstring)
+    );
+
+    circuit.measure(0);
+    assert_eq!(
+        hybrid::num_paths(&circuit, 2),
+        Err(HybridError::Measurement)
+    );
+    assert_eq!(
+        hybrid::amplitudes(&circuit, 2, &[vec![Bit::ZERO; 4]]),
+        Err(HybridError::Measurement)
+    );
+}
+
+#[test]
+fn num_paths_01() {
+    let mut circuit = Circuit::<f64>::new(NonZeroU16::new(4).unwrap());
+    circuit.apply(Gate::H(0));
+    circuit.apply(Gate::CNOT(0, 1));
+    circuit.apply(Gate::CZ(3, 2));
+    assert_eq!(hybrid::num_paths(&circuit, 2), Ok(1));
+
+    circuit.apply(Gate::CNOT(2, 1));
+    circuit.apply(Gate::CZ(0, 3));
+    assert_eq!(hybrid::num_paths(&circuit, 2), Ok(4));
+
+    circuit.apply(Gate::SWAP(1, 2));
+    assert_eq!(hybrid::num_paths(&circuit, 2), Ok(16));
+    assert_eq!(hybrid::num_paths(&circuit, 1), Ok(4));
+    assert_eq!(hybrid::num_paths(&circuit, 3), Ok(4));
+}
+
+#[test]
+fn random_circuit_01() {
+    let num_qubits = 7;
+    for seed in 0..3 {
+        let circuit = random_circuit(num_qubits, 30, seed);
+        let mut stm = System::new(NonZeroU16::new(num_qubits).unwrap(), 1);
+        stm.run(&circuit);
+
+        let bitstrings: Vec<_> =
+            (0..1 << num_qubits).map(|i| bits(i, num_qubits)).collect();
+        for cut in [1, 3, 6] {
+            let amp = hybrid::amplitudes(&circuit, cut, &bitstrings).unwrap();
+            for (a, b) in amp.iter().zip(stm.as_slice()) {
+                assert!((a - b).norm() < 1e-10, "cut {cut}: {a} != {b}");
+            }
+        }
+    }
+}
+
+#[test]
+fn no_bitstrings_01() {
+    let circuit = random_circuit(4, 10, 1);
+    assert_eq!(hybrid::amplitudes(&circuit, 2, &[]), Ok(vec![]));
+}
+
+#[test]
+fn wide_circuit_01() {
+    // Graph state on a ring of 32 qubits: amplitudes
+    // (-1)^(sum x_k x_{k+1}) / 2^16.
+    let n = 32;
+    let mut circuit = Circuit::<f64>::new(NonZeroU16::new(n).unwrap());
+    for k in 0..n {
+        circuit.apply(Gate::H(k));
+    }
+    for k in 0..n {
+        circuit.apply(Gate::CZ(k, (k + 1) % n));
+    }
+    // Two edges of the ring cross the cut.
+    assert_eq!(hybrid::num_paths(&circuit, 16), Ok(4));
+
+    let mut x = vec![Bit::ZERO; usize::from(n)];
+    let mut y = x.clone();
+    y[15] = Bit::ONE;
+    y[16] = Bit::ONE;
+    let mut z = y.clone();
+    z[31] = Bit::ONE;
+    z[0] = Bit::ONE;
+    z[1] = Bit::ONE;
+    x[5] = Bit::ONE;
+
+    let amp = hybrid::amplitudes(&circuit, 16, &[x, y, z]).unwrap();
+    let scale = 0.5f64.powi(16);
+    assert!((amp[0].re - scale).abs() < 1e-14);
+    assert!((amp[1].re + scale).abs() < 1e-14);
+    // Edges (15, 16), (31, 0), (0, 1)
+    assert!((amp[2].re + scale).abs() < 1e-14);
+    assert!(amp.iter().all(|a| a.im.abs() < 1e-14));
+}
@@ -0,0 +1,392 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroU16,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    thread,
+};
+
+use qn::{
+    Bit,
+    Channel,
+    Circuit,
+    Event,
+    EventKind,
+    Gate,
+    NoiseModel,
+    ReadoutError,
+    ReadoutModel,
+    Schedule,
+    System,
+};
+
+#[test]
+fn journal_01() {
+    let num_qubits = NonZeroU16::new(2).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 123);
+    stm.apply(&Gate::H(0));
+    assert!(stm.journal().is_none());
+    assert!(stm.take_journal().is_empty());
+
+    stm.set_journal(true);
+    assert_eq!(stm.journal(), Some(&[][..]));
+
+    stm.set_journal(false);
+    stm.apply(&Gate::H(0));
+    assert!(stm.journal().is_none());
+}
+
+#[test]
+fn journal_02() {
+    let num_qubits = NonZeroU16::new(2).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 123);
+    stm.set_journal(true);
+
+    stm.apply(&Gate::H(0));
+    stm.apply(&Gate::CNOT(0, 1));
+    let outcome = stm.qubit(0).unwrap().measure();
+    stm.reset();
+
+    let journal = stm.journal().unwrap();
+    assert_eq!(journal.len(), 4);
+    for (k, event) in (0..).zip(journal) {
+        assert_eq!(event.seq, k);
+        assert_eq!(event.thread, thread::current().id());
+    }
+    assert_eq!(journal[0].kind, EventKind::Gate(Gate::H(0)));
+    assert_eq!(journal[1].kind, EventKind::Gate(Gate::CNOT(0, 1)));
+    match journal[2].kind {
+        EventKind::Measure {
+            index,
+            outcome: recorded,
+            probability,
+            ..
+        } => {
+            assert_eq!(index, 0);
+            assert_eq!(recorded, outcome);
+            assert!((probability - 0.5).abs() < 1e-12);
+        }
+        _ => panic!("expected measurement, got: {:?}", journal[2].kind),
+    }
+    assert_eq!(journal[3].kind, EventKind::Reset);
+}
+
+#[test]
+fn take_journal_01() {
+    let num_qubits = NonZeroU16::new(1).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 123);
+    stm.set_journal(true);
+
+    stm.apply(&Gate::X(0));
+    let events = stm.take_journal();
+    assert_eq!(events.len(), 1);
+    assert_eq!(stm.journal(), Some(&[][..]));
+
+    stm.apply(&Gate::X(0));
+    let events = stm.take_journal();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].seq, 1);
+}
+
+#[test]
+fn channel_01() {
+    let num_qubits = NonZeroU16::new(2).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 123);
+    stm.set_journal(true);
+
+    stm.apply(&Gate::X(1));
+    stm.apply_channel(&Channel::amplitude_damping(1.).unwrap(), &[1]);
+
+    match &stm.journal().unwrap()[1].kind {
+        EventKind::Channel {
+            qubits,
+            kraus,
+            draw,
+        } => {
+            assert_eq!(qubits, &[1]);
+            assert_eq!(*kraus, 1);
+            assert!((0. ..1.).contains(draw));
+        }
+        kind => panic!("expected channel, got: {kind:?}"),
+    }
+}
+
+#[test]
+fn readout_01() {
+    let num_qubits = NonZeroU16::new(1).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 123);
+    stm.set_journal(true);
+    assert_eq!(stm.qubit(0).unwrap().measure(), Bit::ZERO);
+    assert_eq!(stm.journal().unwrap().len(), 1);
+
+    let mut model = ReadoutModel::new();
+    model
+        .set_qubit(0, ReadoutError::new(1., 0.).unwrap())
+        .unwrap();
+    stm.set_readout_model(Some(model)).unwrap();
+    assert_eq!(stm.qubit(0).unwrap().measure(), Bit::ONE);
+
+    let journal = stm.journal().unwrap();
+    assert_eq!(journal.len(), 3);
+    assert!(matches!(
+        journal[1].kind,
+        EventKind::Measure {
+            index: 0,
+            outcome: Bit::ZERO,
+            ..
+        }
+    ));
+    assert_eq!(
+        journal[2].kind,
+        EventKind::Readout {
+            index:    0,
+            outcome:  Bit::ZERO,
+            reported: Bit::ONE,
+        }
+    );
+}
+
+#[test]
+fn readout_02() {
+    // Readout errors of a noise model are recorded as well.
+    let num_qubits = NonZeroU16::new(2).unwrap();
+    let mut model = ReadoutModel::new();
+    model
+        .set_qubit(1, ReadoutError::new(1., 0.).unwrap())
+        .unwrap();
+    let mut noise = NoiseModel::new();
+    noise.set_readout(Some(model));
+    let mut circuit = Circuit::new(num_qubits);
+    circuit.measure(0);
+    circuit.measure(1);
+
+    let mut stm = System::<f64>::new(num_qubits, 123);
+    stm.set_journal(true);
+    assert_eq!(stm.run_noisy(&circuit, &noise), vec![Bit::ZERO, Bit::ONE]);
+
+    let journal = stm.journal().unwrap();
+    assert_eq!(journal.len(), 4);
+    assert!(matches!(
+        journal[2].kind,
+        EventKind::Measure {
+            index: 1,
+            ..
+        }
+    ));
+    assert_eq!(
+        journal[3].kind,
+        EventKind::Readout {
+            index:    1,
+            outcome:  Bit::ZERO,
+            reported: Bit::ONE,
+        }
+    );
+}
+
+#[test]
+fn sample_01() {
+    let num_qubits = NonZeroU16::new(1).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 123);
+    stm.set_journal(true);
+    let _ = stm.sample(10);
+    let _ = stm.sample(5);
+
+    let journal = stm.journal().unwrap();
+    let (pos0, pos1) = match (&journal[0].kind, &journal[1].kind) {
+        (
+            EventKind::Sample {
+                shots: 10,
+                rng_word_pos: pos0,
+            },
+            EventKind::Sample {
+                shots: 5,
+                rng_word_pos: pos1,
+            },
+        ) => (*pos0, *pos1),
+        _ => panic!("expected samples, got: {journal:?}"),
+    };
+    assert!(pos0 < pos1);
+}
+
+#[test]
+fn schedule_01() {
+    let num_qubits = NonZeroU16::new(4).unwrap();
+    let gates: Vec<_> = (0..4)
+        .map(Gate::H)
+        .chain((1..4).map(|k| Gate::CNOT(k - 1, k)))
+        .collect();
+    let schedule = Schedule::with_block_qubits(num_qubits, 2, &gates);
+
+    let mut stm = System::<f64>::new(num_qubits, 123);
+    stm.set_journal(true);
+    stm.apply_schedule(&schedule);
+
+    let recorded: Vec<_> = stm
+        .journal()
+        .unwrap()
+        .iter()
+        .map(|event| event.kind.clone())
+        .collect();
+    let expected: Vec<_> = gates.into_iter().map(EventKind::Gate).collect();
+    assert_eq!(recorded, expected);
+}
+
+#[test]
+fn observer_01() {
+    let num_qubits = NonZeroU16::new(2).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 123);
+    let observed = Arc::new(Mutex::new(Vec::new()));
+    let events = Arc::clone(&observed);
+    stm.set_observer(Some(Box::new(move |event: &Event<f64>| {
+        events.lock().unwrap().push(event.clone());
+    })));
+
+    stm.apply(&Gate::H(0));
+    let _ = stm.qubit(0).unwrap().measure();
+    assert!(stm.journal().is_none());
+    assert_eq!(observed.lock().unwrap().len(), 2);
+
+    stm.set_journal(true);
+    stm.apply(&Gate::H(1));
+    stm.set_observer(None);
+    stm.apply(&Gate::H(1));
+
+    let observed = observed.lock().unwrap();
+    assert_eq!(observed.len(), 3);
+    assert_eq!(stm.journal().unwrap().len(), 2);
+    assert_eq!(stm.journal().unwrap()[0], observed[2]);
+}
+
+#[test]
+fn replay_01() {
+    let num_qubits = NonZeroU16::new(3).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 123);
+    stm.set_journal(true);
+    for k in 0..3 {
+        stm.apply(&Gate::H(k));
+    }
+    for mut qubit in stm.qubit_iter() {
+        let _ = qubit.measure();
+    }
+
+    let journal = stm.take_journal();
+    let mut replay = System::<f64>::new(num_qubits, 123);
+    replay.set_journal(true);
+    for event in &journal {
+        match event.kind {
+            EventKind::Gate(gate) => replay.apply(&gate),
+            EventKind::Measure {
+                index, ..
+            } => {
+                let _ = replay.qubit(index).unwrap().measure();
+            }
+            _ => panic!("unexpected event: {event:?}"),
+        }
+    }
+    assert_eq!(replay.journal().unwrap(), &journal[..]);
+}
+
+#[test]
+fn threads_01() {
+    let num_qubits = NonZeroU16::new(4).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 123);
+    stm.set_journal(true);
+
+    let threads: HashMap<_, _> = thread::scope(|s| {
+        let handles: Vec<_> = stm
+            .qubit_iter()
+            .map(|mut qubit| {
+                s.spawn(move || {
+                    let k = qubit.index();
+                    qubit.apply(&Gate::X(k));
+                    assert_eq!(qubit.measure(), Bit::ONE);
+                    (k, thread::current().id())
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let journal = stm.journal().unwrap();
+    assert_eq!(journal.len(), 8);
+    for (k, event) in (0..).zip(journal) {
+        assert_eq!(event.seq, k);
+        let index = match event.kind {
+            EventKind::Gate(Gate::X(index)) => index,
+            EventKind::Measure {
+                index,
+                outcome: Bit::ONE,
+                ..
+            } => index,
+            _ => panic!("unexpected event: {event:?}"),
+        };
+        assert_eq!(event.thread, threads[&index]);
+    }
+    for k in 0..4 {
+        let gate = journal.iter().position(
+            |e| matches!(e.kind, EventKind::Gate(Gate::X(i)) if i == k),
+        );
+        let measure = journal.iter().position(|e| {
+            matches!(e.kind, EventKind::Measure { index, .. } if index == k)
+        });
+        assert!(gate.unwrap() < measure.unwrap());
+    }
+}
+
+#[test]
+fn threads_02() {
+    // Gates queued by qubits are fused, but recorded one by one.
+    let num_qubits = NonZeroU16::new(6).unwrap();
+    let gates = |k: u16| -> Vec<Gate<f64>> {
+        (0..50)
+            .flat_map(|i| {
+                let theta = 0.01 * f64::from(i);
+                [Gate::H(k), Gate::Rz(k, theta), Gate::Rx(k, -theta)]
+            })
+            .collect()
+    };
+    let mut stm = System::<f64>::new(num_qubits, 123);
+    stm.set_journal(true);
+    let threads: HashMap<_, _> = thread::scope(|s| {
+        let handles: Vec<_> = stm
+            .qubit_iter()
+            .map(|mut qubit| {
+                s.spawn(move || {
+                    let k = qubit.index();
+                    for gate in gates(k) {
+                        qubit.apply(&gate);
+                    }
+                    (k, thread::current().id())
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let journal = stm.take_journal();
+    let mut replay = System::<f64>::new(num_qubits, 123);
+    for k in 0..6 {
+        let recorded: Vec<_> = journal
+            .iter()
+            .filter_map(|e| match e.kind {
+                EventKind::Gate(gate) if gate.qubits() == [k] => {
+                    assert_eq!(e.thread, threads[&k]);
+                    Some(gate)
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(recorded, gates(k));
+    }
+    assert_eq!(journal.len(), 6 * 150);
+    for event in &journal {
+        if let EventKind::Gate(gate) = event.kind {
+            replay.apply(&gate);
+        }
+    }
+    for (a, b) in stm.as_slice().iter().zip(replay.as_slice()) {
+        assert!((a - b).norm() < 1e-12);
+    }
+}
@@ -0,0 +1,316 @@
+use std::num::NonZeroU16;
+
+use num::Complex;
+use qn::{
+    krylov::{
+        self,
+        KrylovError,
+    },
+    trotter,
+    Gate,
+    LinearOperator,
+    Pauli,
+    PauliString,
+    PauliSum,
+    SparseMatrix,
+    System,
+};
+
+const TOL: f64 = 1e-10;
+
+fn gen_stm(num_qubits: u16) -> System<f64> {
+    let mut stm = System::new(NonZeroU16::new(num_qubits).unwrap(), 123);
+    for k in 0..num_qubits {
+        stm.apply(&Gate::Ry(k, 0.3 + f64::from(k)));
+        stm.apply(&Gate::Rz(k, 0.7 * f64::from(k)));
+    }
+    stm
+}
+
+fn string(ops: &[(u16, Pauli)]) -> PauliString {
+    PauliString::new(ops).unwrap()
+}
+
+fn chain(num_qubits: u16) -> PauliSum<f64> {
+    let mut ham = PauliSum::new();
+    for k in 0..num_qubits - 1 {
+        ham.add_term(1., string(&[(k, Pauli::X), (k + 1, Pauli::X)]));
+        ham.add_term(0.8, string(&[(k, Pauli::Y), (k + 1, Pauli::Y)]));
+        ham.add_term(0.6, string(&[(k, Pauli::Z), (k + 1, Pauli::Z)]));
+    }
+    for k in 0..num_qubits {
+        ham.add_term(0.4, string(&[(k, Pauli::X)]));
+    }
+    ham
+}
+
+/// Sparse matrix of an operator, built column by column.
+fn to_sparse(
+    op: &impl LinearOperator<f64>,
+    dim: usize,
+) -> SparseMatrix<f64> {
+    let mut entries = Vec::new();
+    for col in 0..dim {
+        let mut src = vec![Complex::from(0.); dim];
+        src[col] = Complex::from(1.);
+        let mut dst = vec![Complex::from(0.); dim];
+        op.mul_vec(&src, &mut dst);
+        for (row, v) in dst.into_iter().enumerate() {
+            if v.norm() > 0. {
+                entries.push((row, col, v));
+            }
+        }
+    }
+    SparseMatrix::from_triplets(dim, &entries).unwrap()
+}
+
+fn distance(
+    a: &System<f64>,
+    b: &System<f64>,
+) -> f64 {
+    a.as_slice()
+        .iter()
+        .zip(b.as_slice())
+        .map(|(x, y)| (x - y).norm_sqr())
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[test]
+fn invalid_01() {
+    let mut stm = gen_stm(2);
+    let ham = chain(2);
+    assert_eq!(
+        krylov::expm_multiply(&mut stm, &ham, f64::NAN, TOL),
+        Err(KrylovError::InvalidTime)
+    );
+    assert_eq!(
+        krylov::expm_multiply(&mut stm, &ham, 1., 0.),
+        Err(KrylovError::InvalidTolerance)
+    );
+    assert_eq!(krylov::expm_multiply(&mut stm, &ham, 0., TOL), Ok(0));
+    assert!(distance(&stm, &gen_stm(2)) < TOL);
+}
+
+#[test]
+#[should_panic(expected = "operator does not fit in the system")]
+fn invalid_02() {
+    let mut stm = gen_stm(2);
+    let _ = krylov::expm_multiply(&mut stm, &chain(3), 1., TOL);
+}
+
+#[test]
+fn eigenstate_01() {
+    // |1> is an eigenstate of Z: the Krylov subspace is invariant.
+    let mut ham = PauliSum::new();
+    ham.add_term(0.5, string(&[(0, Pauli::Z)]));
+
+    let mut stm: System<f64> = System::new(NonZeroU16::new(1).unwrap(), 123);
+    stm.apply(&Gate::X(0));
+    assert_eq!(krylov::expm_multiply(&mut stm, &ham, 30., TOL), Ok(1));
+
+    let expected = Complex::from_polar(1., 15.);
+    assert!((stm.as_slice()[1] - expected).norm() < TOL);
+}
+
+#[test]
+fn chain_01() {
+    let (n, time) = (4, 1.3);
+    let ham = chain(n);
+    let mut stm = gen_stm(n);
+    krylov::expm_multiply(&mut stm, &ham, time, TOL).unwrap();
+
+    let mut expected = gen_stm(n);
+    trotter::evolve(&mut expected, &ham, time, 400, trotter::Order::Fourth);
+
+    assert!(distance(&stm, &expected) < 1e-8);
+}
+
+#[test]
+fn chain_long_time() {
+    let (n, time) = (6, 20.);
+    let ham = chain(n);
+    let mut stm = gen_stm(n);
+    let steps = krylov::expm_multiply(&mut stm, &ham, time, TOL).unwrap();
+    assert!(steps > 1);
+
+    let mut expected = gen_stm(n);
+    trotter::evolve(&mut expected, &ham, time, 800, trotter::Order::Fourth);
+
+    assert!(distance(&stm, &expected) < 1e-6);
+    let norm: f64 = stm.as_slice().iter().map(|z| z.norm_sqr()).sum();
+    assert!((norm - 1.).abs() < 1e-10);
+}
+
+#[test]
+fn backward_01() {
+    let n = 5;
+    let ham = chain(n);
+    let mut stm = gen_stm(n);
+    krylov::expm_multiply(&mut stm, &ham, 3., TOL).unwrap();
+    assert!(distance(&stm, &gen_stm(n)) > 0.1);
+    krylov::expm_multiply(&mut stm, &ham, -3., TOL).unwrap();
+
+    assert!(distance(&stm, &gen_stm(n)) < 1e-8);
+}
+
+#[test]
+fn sparse_01() {
+    let n = 3;
+    let ham = chain(n);
+    let mat = to_sparse(&ham, 1 << n);
+
+    let mut stm = gen_stm(n);
+    krylov::expm_multiply(&mut stm, &ham, 2., TOL).unwrap();
+    let mut expected = gen_stm(n);
+    krylov::expm_multiply(&mut expected, &mat, 2., TOL).unwrap();
+
+    assert!(distance(&stm, &expected) < 1e-8);
+}
+
+/// Sum of independent single-qubit terms `a_k Z_k + b_k X_k`.
+fn product_ham(num_qubits: u16) -> (PauliSum<f64>, Vec<f64>) {
+    let mut ham = PauliSum::new();
+    let mut spectrum = vec![0.];
+    for k in 0..num_qubits {
+        let (a, b) = (1. / f64::from(k + 1), 0.3 + 0.1 * f64::from(k));
+        ham.add_term(a, string(&[(k, Pauli::Z)]));
+        ham.add_term(b, string(&[(k, Pauli::X)]));
+        let e = a.hypot(b);
+        spectrum = spectrum.iter().flat_map(|s| [s - e, s + e]).collect();
+    }
+    spectrum.sort_by(f64::total_cmp);
+    (ham, spectrum)
+}
+
+fn residual(
+    op: &impl LinearOperator<f64>,
+    stm: &System<f64>,
+    energy: f64,
+) -> f64 {
+    let psi = stm.as_slice();
+    let mut h_psi = vec![Complex::from(0.); psi.len()];
+    op.mul_vec(psi, &mut h_psi);
+    h_psi
+        .iter()
+        .zip(psi)
+        .map(|(x, y)| (x - y * energy).norm_sqr())
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[test]
+fn eigen_invalid_01() {
+    let ham = chain(2);
+    let mut stm = gen_stm(2);
+    assert_eq!(
+        krylov::lowest_eigenvalues(&mut stm, &ham, 0, TOL),
+        Err(KrylovError::InvalidCount)
+    );
+    assert_eq!(
+        krylov::lowest_eigenvalues(&mut stm, &ham, 5, TOL),
+        Err(KrylovError::InvalidCount)
+    );
+    assert_eq!(
+        krylov::lowest_eigenvalues(&mut stm, &ham, 1, -1.),
+        Err(KrylovError::InvalidTolerance)
+    );
+    assert_eq!(
+        krylov::ground_state(&mut stm, &ham, 0.),
+        Err(KrylovError::InvalidTolerance)
+    );
+    assert!(distance(&stm, &gen_stm(2)) < TOL);
+}
+
+#[test]
+#[should_panic(expected = "operator does not fit in the system")]
+fn eigen_invalid_02() {
+    let mut stm = gen_stm(2);
+    let _ = krylov::lowest_eigenvalues(&mut stm, &chain(3), 1, TOL);
+}
+
+#[test]
+fn eigen_degenerate_01() {
+    // Z0 on two qubits: eigenvalues -1 and 1, both doubly degenerate.
+    let mut ham = PauliSum::new();
+    ham.add_term(1., string(&[(0, Pauli::Z)]));
+    let mut stm = gen_stm(2);
+
+    let values = krylov::lowest_eigenvalues(&mut stm, &ham, 2, TOL).unwrap();
+    assert!((values[0] + 1.).abs() < TOL);
+    assert!((values[1] - 1.).abs() < TOL);
+    assert_eq!(
+        krylov::lowest_eigenvalues(&mut stm, &ham, 3, TOL),
+        Err(KrylovError::NotConverged)
+    );
+}
+
+#[test]
+fn eigen_heisenberg_01() {
+    // Singlet at -3, triplet at 1.
+    let mut ham = PauliSum::new();
+    for op in [Pauli::X, Pauli::Y, Pauli::Z] {
+        ham.add_term(1., string(&[(0, op), (1, op)]));
+    }
+    let mut stm = gen_stm(2);
+    let values = krylov::lowest_eigenvalues(&mut stm, &ham, 2, TOL).unwrap();
+    assert!((values[0] + 3.).abs() < TOL);
+    assert!((values[1] - 1.).abs() < TOL);
+
+    let energy = krylov::ground_state(&mut stm, &ham, TOL).unwrap();
+    assert!((energy + 3.).abs() < TOL);
+    let (a, b) = (stm.as_slice()[1], stm.as_slice()[2]);
+    assert!((a + b).norm() < 1e-8);
+    assert!((a.norm_sqr() - 0.5).abs() < 1e-8);
+}
+
+#[test]
+fn eigen_product_01() {
+    let n = 8;
+    let (ham, spectrum) = product_ham(n);
+    let mut stm = gen_stm(n);
+    let values = krylov::lowest_eigenvalues(&mut stm, &ham, 5, TOL).unwrap();
+
+    assert_eq!(values.len(), 5);
+    for (x, y) in values.iter().zip(&spectrum) {
+        assert!((x - y).abs() < 1e-9, "{x} != {y}");
+    }
+}
+
+#[test]
+fn eigen_product_sparse() {
+    let n = 6;
+    let (ham, spectrum) = product_ham(n);
+    let mat = to_sparse(&ham, 1 << n);
+    let mut stm = gen_stm(n);
+    let values = krylov::lowest_eigenvalues(&mut stm, &mat, 3, TOL).unwrap();
+
+    for (x, y) in values.iter().zip(&spectrum) {
+        assert!((x - y).abs() < 1e-9, "{x} != {y}");
+    }
+}
+
+#[test]
+fn ground_state_01() {
+    let n = 7;
+    let ham = chain(n);
+    let mut stm = gen_stm(n);
+    let values = krylov::lowest_eigenvalues(&mut stm, &ham, 1, TOL).unwrap();
+    let energy = krylov::ground_state(&mut stm, &ham, TOL).unwrap();
+
+    assert!((energy - values[0]).abs() < 1e-9);
+    assert!(residual(&ham, &stm, energy) < 1e-8);
+    let norm: f64 = stm.as_slice().iter().map(|z| z.norm_sqr()).sum();
+    assert!((norm - 1.).abs() < 1e-12);
+}
+
+#[test]
+fn ground_state_reproducible() {
+    let ham = chain(5);
+    let mut stm1 = gen_stm(5);
+    let mut stm2 = gen_stm(5);
+    krylov::ground_state(&mut stm1, &ham, TOL).unwrap();
+    krylov::ground_state(&mut stm2, &ham, TOL).unwrap();
+
+    assert!(distance(&stm1, &stm2) < 1e-10);
+}
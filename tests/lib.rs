@@ -1,4 +1,29 @@
+mod backend;
+mod channel;
+mod circuit;
+mod decision_diagram;
+mod density;
+mod entanglement;
+mod fusion;
+mod gate;
+mod hybrid;
+mod journal;
+mod krylov;
+mod lindblad;
 mod measure;
+mod metrics;
+mod mps;
+mod noise;
 
+mod operator;
+mod pauli;
 mod qubit;
+mod readout;
+mod schedule;
+mod simd;
+mod soa;
+mod sparse;
+mod stabilizer;
 mod system;
+mod trajectory;
+mod trotter;
@@ -0,0 +1,207 @@
+use std::num::NonZeroU16;
+
+use num::Complex;
+use qn::{
+    DensityMatrixSystem,
+    Gate,
+    LindbladError,
+    Lindbladian,
+    Pauli,
+    PauliString,
+    PauliSum,
+};
+
+const TOL: f64 = 1e-10;
+const EPS: f64 = 1e-8;
+
+fn gen_stm(num_qubits: u16) -> DensityMatrixSystem<f64> {
+    DensityMatrixSystem::new(NonZeroU16::new(num_qubits).unwrap(), 123)
+}
+
+fn assert_rho_eq(
+    a: &DensityMatrixSystem<f64>,
+    b: &DensityMatrixSystem<f64>,
+) {
+    for (x, y) in a.as_slice().iter().zip(b.as_slice()) {
+        assert!((x - y).norm() < EPS, "{x} != {y}");
+    }
+}
+
+fn single(
+    index: u16,
+    op: Pauli,
+) -> PauliString {
+    PauliString::new(&[(index, op)]).unwrap()
+}
+
+#[test]
+fn invalid_01() {
+    let mut lindbladian = Lindbladian::new(PauliSum::new());
+    assert_eq!(
+        lindbladian.add_decay(0, -1.),
+        Err(LindbladError::InvalidRate)
+    );
+    assert_eq!(
+        lindbladian.add_dephasing(0, f64::NAN),
+        Err(LindbladError::InvalidRate)
+    );
+    assert!(lindbladian.jumps().is_empty());
+
+    let mut stm = gen_stm(1);
+    assert_eq!(
+        stm.evolve(&lindbladian, -1., TOL),
+        Err(LindbladError::InvalidTime)
+    );
+    assert_eq!(
+        stm.evolve(&lindbladian, 1., 0.),
+        Err(LindbladError::InvalidTolerance)
+    );
+    assert_eq!(stm.evolve(&lindbladian, 0., TOL), Ok(0));
+}
+
+#[test]
+#[should_panic(expected = "Lindbladian does not fit in the system")]
+fn invalid_02() {
+    let mut lindbladian = Lindbladian::new(PauliSum::new());
+    lindbladian.add_decay(2, 1.).unwrap();
+    assert_eq!(lindbladian.num_qubits(), 3);
+
+    let mut stm = gen_stm(2);
+    let _ = stm.evolve(&lindbladian, 1., TOL);
+}
+
+#[test]
+fn hamiltonian_rotations() {
+    let (omega, time) = (1.3, 0.9);
+    for (op, gate) in [
+        (Pauli::X, Gate::Rx(1, omega * time)),
+        (Pauli::Y, Gate::Ry(1, omega * time)),
+        (Pauli::Z, Gate::Rz(1, omega * time)),
+    ] {
+        let mut ham = PauliSum::new();
+        ham.add_term(omega / 2., single(1, op));
+        let lindbladian = Lindbladian::new(ham);
+
+        let prepare = || {
+            let mut stm = gen_stm(2);
+            stm.apply(&Gate::H(0));
+            stm.apply(&Gate::Ry(1, 0.4));
+            stm
+        };
+        let mut stm = prepare();
+        let mut expected = prepare();
+
+        let steps = stm.evolve(&lindbladian, time, TOL).unwrap();
+        assert!(steps > 0);
+        expected.apply(&gate);
+
+        assert_rho_eq(&stm, &expected);
+    }
+}
+
+#[test]
+fn last_step_01() {
+    // Stationary state: the step grows five-fold, and the last step, clipped
+    // to the end of the interval, covers more than half of it.  Adding it
+    // to the time elapsed falls one ulp short of `time`.
+    let mut ham = PauliSum::new();
+    ham.add_term(0.7, single(0, Pauli::Z));
+    let lindbladian = Lindbladian::new(ham);
+    let time = 1858. / 997.;
+
+    let mut stm = gen_stm(1);
+    assert_eq!(stm.evolve(&lindbladian, time, TOL), Ok(3));
+    assert!((stm.probability(0).unwrap()).abs() < EPS);
+}
+
+#[test]
+fn hamiltonian_zz() {
+    let (coupling, time) = (0.7, 1.1);
+    let mut ham = PauliSum::new();
+    ham.add_term(
+        coupling,
+        PauliString::new(&[(0, Pauli::Z), (2, Pauli::Z)]).unwrap(),
+    );
+    let lindbladian = Lindbladian::new(ham);
+
+    let prepare = || {
+        let mut stm = gen_stm(3);
+        for k in 0..3 {
+            stm.apply(&Gate::H(k));
+        }
+        stm
+    };
+    let mut stm = prepare();
+    let mut expected = prepare();
+
+    stm.evolve(&lindbladian, time, TOL).unwrap();
+    expected.apply(&Gate::CNOT(0, 2));
+    expected.apply(&Gate::Rz(2, 2. * coupling * time));
+    expected.apply(&Gate::CNOT(0, 2));
+
+    assert_rho_eq(&stm, &expected);
+    assert!((stm.purity() - 1.).abs() < EPS);
+}
+
+#[test]
+fn decay_01() {
+    let gamma: f64 = 0.8;
+    let mut lindbladian = Lindbladian::new(PauliSum::new());
+    lindbladian.add_decay(1, gamma).unwrap();
+
+    for t in [0.5, 1.0, 2.0] {
+        let mut stm = gen_stm(2);
+        stm.apply(&Gate::X(0));
+        stm.apply(&Gate::X(1));
+        stm.evolve(&lindbladian, t, TOL).unwrap();
+
+        let p = stm.probability(1).unwrap();
+        assert!((p - (-gamma * t).exp()).abs() < EPS);
+        assert!((stm.probability(0).unwrap() - 1.).abs() < EPS);
+        assert!((stm.trace() - 1.).abs() < EPS);
+    }
+}
+
+#[test]
+fn dephasing_01() {
+    let (gamma, time): (f64, f64) = (0.6, 1.5);
+    let mut lindbladian = Lindbladian::new(PauliSum::new());
+    lindbladian.add_dephasing(0, gamma).unwrap();
+
+    let mut stm = gen_stm(1);
+    stm.apply(&Gate::H(0));
+    stm.evolve(&lindbladian, time, TOL).unwrap();
+
+    let rho = stm.as_slice();
+    let coherence = 0.5 * (-gamma * time).exp();
+    assert!((rho[0] - Complex::from(0.5)).norm() < EPS);
+    assert!((rho[1] - Complex::from(coherence)).norm() < EPS);
+    assert!((rho[2] - Complex::from(coherence)).norm() < EPS);
+    assert!((rho[3] - Complex::from(0.5)).norm() < EPS);
+}
+
+#[test]
+fn driven_decay_01() {
+    // Driven, damped qubit relaxes to a steady state that is Hermitian and
+    // has unit trace.
+    let mut ham = PauliSum::new();
+    ham.add_term(1., single(0, Pauli::X));
+    ham.add_term(0.3, single(0, Pauli::Z));
+    let mut lindbladian = Lindbladian::new(ham);
+    lindbladian.add_decay(0, 1.).unwrap();
+    lindbladian.add_dephasing(0, 0.2).unwrap();
+
+    let mut stm = gen_stm(1);
+    stm.evolve(&lindbladian, 20., TOL).unwrap();
+    let rho = stm.as_slice().to_vec();
+
+    assert!((stm.trace() - 1.).abs() < EPS);
+    assert!((rho[1] - rho[2].conj()).norm() < EPS);
+    assert!(stm.purity() < 1.);
+
+    // Stationary: further evolution does not change the state.
+    stm.evolve(&lindbladian, 5., TOL).unwrap();
+    for (x, y) in stm.as_slice().iter().zip(&rho) {
+        assert!((x - y).norm() < 1e-6);
+    }
+}
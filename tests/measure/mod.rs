@@ -42,8 +42,10 @@ fn generate_stm_set_real(
 }
 
 mod one_qubit;
+#[allow(clippy::neg_multiply)]
 mod one_qubit_histogram;
 mod one_qubit_imag;
 mod one_qubit_large_sys;
+#[allow(clippy::legacy_numeric_constants)]
 mod two_qubits;
 mod two_qubits_nonlocal;
@@ -0,0 +1,105 @@
+use std::{
+    f64::consts::SQRT_2,
+    num::NonZeroU16,
+};
+
+use num::Complex;
+use qn::{
+    metrics::{
+        approx_eq_up_to_phase,
+        fidelity,
+        inner_product,
+        max_error,
+        trace_distance,
+    },
+    System,
+};
+
+const EPS: f64 = 1e-12;
+
+fn gen_stm(amps: &[Complex<f64>]) -> System<f64> {
+    let num_qubits = amps.len().trailing_zeros() as u16;
+    let mut stm = System::new(NonZeroU16::new(num_qubits).unwrap(), 123);
+    stm.as_mut_slice().copy_from_slice(amps);
+    stm
+}
+
+fn plus() -> System<f64> {
+    gen_stm(&[Complex::from(SQRT_2.recip()); 2])
+}
+
+#[test]
+fn dimension_mismatch_01() {
+    let stm = plus();
+    let other = gen_stm(&[Complex::from(0.5); 4]);
+    assert!(inner_product(&stm, &other).is_none());
+    assert!(fidelity(&stm, &other).is_none());
+    assert!(trace_distance(&stm, &other).is_none());
+    assert!(max_error(&stm, &other).is_none());
+    assert!(!approx_eq_up_to_phase(&stm, &other, 1.));
+}
+
+#[test]
+fn inner_product_01() {
+    let stm = plus();
+    let minus = [
+        Complex::from(SQRT_2.recip()),
+        Complex::from(-SQRT_2.recip()),
+    ];
+    let zero = gen_stm(&[Complex::from(1.), Complex::from(0.)]);
+
+    assert!((inner_product(&stm, &stm).unwrap() - 1.).norm() < EPS);
+    assert!(inner_product(&stm, &minus[..]).unwrap().norm() < EPS);
+
+    let z = inner_product(&zero, &stm).unwrap();
+    assert!((z - SQRT_2.recip()).norm() < EPS);
+}
+
+#[test]
+fn inner_product_02() {
+    // <phi|psi> is antilinear in phi
+    let phi = [Complex::new(0., 1.), Complex::from(0.)];
+    let psi = [Complex::from(1.), Complex::from(0.)];
+    let z = inner_product(&phi[..], &psi[..]).unwrap();
+    assert!((z - Complex::new(0., -1.)).norm() < EPS);
+}
+
+#[test]
+fn fidelity_01() {
+    let stm = plus();
+    let zero = gen_stm(&[Complex::from(1.), Complex::from(0.)]);
+
+    assert!((fidelity(&stm, &stm).unwrap() - 1.).abs() < EPS);
+    assert!((fidelity(&stm, &zero).unwrap() - 0.5).abs() < EPS);
+    assert!(
+        (trace_distance(&stm, &zero).unwrap() - SQRT_2.recip()).abs() < EPS
+    );
+    assert!(trace_distance(&stm, &stm).unwrap().abs() < EPS.sqrt());
+}
+
+#[test]
+fn max_error_01() {
+    let stm = plus();
+    let mut other = plus();
+    other.as_mut_slice()[1] += Complex::new(0., 0.25);
+
+    assert!(max_error(&stm, &stm).unwrap().abs() < EPS);
+    assert!((max_error(&stm, &other).unwrap() - 0.25).abs() < EPS);
+}
+
+#[test]
+fn approx_eq_01() {
+    let stm = plus();
+    let phase = Complex::from_polar(1., 0.789);
+    let rotated: Vec<_> = stm.as_slice().iter().map(|a| a * phase).collect();
+
+    assert!(max_error(&stm, &rotated[..]).unwrap() > 0.1);
+    assert!(approx_eq_up_to_phase(&stm, &rotated[..], EPS));
+    assert!(approx_eq_up_to_phase(&rotated[..], &stm, EPS));
+
+    let minus = [
+        Complex::from(SQRT_2.recip()),
+        Complex::from(-SQRT_2.recip()),
+    ];
+    assert!(!approx_eq_up_to_phase(&stm, &minus[..], 0.1));
+}
@@ -0,0 +1,279 @@
+use std::num::{
+    NonZeroU16,
+    NonZeroUsize,
+};
+
+use qn::{
+    Bit,
+    Gate,
+    MpsError,
+    MpsSystem,
+    System,
+};
+
+fn gen_stm(num_qubits: u16) -> MpsSystem<f64> {
+    MpsSystem::new(NonZeroU16::new(num_qubits).unwrap(), 123)
+}
+
+/// Random circuit, generated with a simple linear congruential generator.
+fn random_circuit(
+    num_qubits: u16,
+    len: usize,
+    seed: u64,
+) -> Vec<Gate<f64>> {
+    let mut state = seed;
+    let mut next = |m: u16| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        u16::try_from((state >> 33) % u64::from(m)).unwrap()
+    };
+    (0..len)
+        .map(|_| {
+            let a = next(num_qubits);
+            let b = (a + 1 + next(num_qubits - 1)) % num_qubits;
+            let theta = f64::from(next(1000)) / 100.;
+            match next(8) {
+                0 => Gate::H(a),
+                1 => Gate::S(a),
+                2 => Gate::Rx(a, theta),
+                3 => Gate::Ry(a, theta),
+                4 => Gate::Phase(a, theta),
+                5 => Gate::CNOT(a, b),
+                6 => Gate::CZ(a, b),
+                _ => Gate::SWAP(a, b),
+            }
+        })
+        .collect()
+}
+
+fn bits(
+    index: usize,
+    num_qubits: u16,
+) -> Vec<Bit> {
+    (0..num_qubits)
+        .map(|k| Bit::from((index >> k) & 1 == 1))
+        .collect()
+}
+
+/// Compare all amplitudes with a state vector simulation.
+fn assert_matches(
+    mps: &MpsSystem<f64>,
+    sv: &System<f64>,
+    tol: f64,
+) {
+    let num_qubits = mps.num_qubits().get();
+    for (i, amp) in sv.as_slice().iter().enumerate() {
+        let a = mps.amplitude(&bits(i, num_qubits)).unwrap();
+        assert!((a - amp).norm() < tol, "index {i}: {a} != {amp}");
+    }
+    for k in 0..num_qubits {
+        let (p, q) = (mps.probability(k).unwrap(), sv.probability(k).unwrap());
+        assert!((p - q).abs() < tol, "qubit {k}: {p} != {q}");
+    }
+}
+
+#[test]
+fn new_01() {
+    let stm = gen_stm(4);
+    assert_eq!(stm.num_qubits().get(), 4);
+    assert_eq!(stm.bond_dims(), vec![1, 1, 1]);
+    assert_eq!(stm.max_bond_dim(), None);
+    assert_eq!(stm.truncation_threshold(), 0.);
+    assert_eq!(stm.truncation_error(), 0.);
+    assert_eq!(stm.amplitude(&[Bit::ZERO; 4]).unwrap().re, 1.);
+    assert_eq!(stm.amplitude(&[Bit::ZERO; 3]), None);
+    assert_eq!(stm.probability(3), Some(0.));
+    assert_eq!(stm.probability(4), None);
+}
+
+#[test]
+fn set_truncation_01() {
+    let mut stm = gen_stm(2);
+    assert_eq!(stm.set_truncation_threshold(1e-8), Ok(()));
+    assert_eq!(stm.truncation_threshold(), 1e-8);
+    assert_eq!(
+        stm.set_truncation_threshold(-1e-8),
+        Err(MpsError::InvalidThreshold)
+    );
+    assert_eq!(
+        stm.set_truncation_threshold(1.),
+        Err(MpsError::InvalidThreshold)
+    );
+    assert_eq!(
+        stm.set_truncation_threshold(f64::NAN),
+        Err(MpsError::InvalidThreshold)
+    );
+    assert_eq!(stm.truncation_threshold(), 1e-8);
+
+    let dim = NonZeroUsize::new(4);
+    stm.set_max_bond_dim(dim);
+    assert_eq!(stm.max_bond_dim(), dim);
+    stm.set_max_bond_dim(None);
+    assert_eq!(stm.max_bond_dim(), None);
+}
+
+#[test]
+#[should_panic(expected = "invalid gate")]
+fn apply_invalid_01() {
+    let mut stm = gen_stm(2);
+    stm.apply(&Gate::CNOT(0, 2));
+}
+
+#[test]
+fn single_qubit_01() {
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::X(0));
+    stm.apply(&Gate::H(2));
+
+    let amp = stm.amplitude(&[Bit::ONE, Bit::ZERO, Bit::ONE]).unwrap();
+    assert!((amp.re - 0.5f64.sqrt()).abs() < 1e-12);
+    assert!((stm.probability(0).unwrap() - 1.).abs() < 1e-12);
+    assert!(stm.probability(1).unwrap().abs() < 1e-12);
+    assert!((stm.probability(2).unwrap() - 0.5).abs() < 1e-12);
+    assert_eq!(stm.bond_dims(), vec![1, 1]);
+}
+
+#[test]
+fn random_circuit_01() {
+    for seed in 0..5 {
+        let num_qubits = 6;
+        let mut mps = gen_stm(num_qubits);
+        let mut sv = System::new(NonZeroU16::new(num_qubits).unwrap(), 1);
+        for gate in random_circuit(num_qubits, 60, seed) {
+            mps.apply(&gate);
+            sv.apply(&gate);
+        }
+        assert_matches(&mps, &sv, 1e-10);
+        assert!(mps.truncation_error() < 1e-20);
+        assert!(mps.bond_dims().iter().all(|&d| d <= 8));
+    }
+}
+
+#[test]
+fn distant_gate_01() {
+    let mut mps = gen_stm(5);
+    let mut sv = System::new(NonZeroU16::new(5).unwrap(), 1);
+    for gate in [
+        Gate::H(4),
+        Gate::CNOT(4, 0),
+        Gate::Ry(2, 0.3),
+        Gate::CZ(0, 2),
+        Gate::SWAP(1, 4),
+    ] {
+        mps.apply(&gate);
+        sv.apply(&gate);
+    }
+    assert_matches(&mps, &sv, 1e-12);
+}
+
+#[test]
+fn ghz_01() {
+    let mut stm = gen_stm(200);
+    stm.apply(&Gate::H(0));
+    for k in 1..200 {
+        stm.apply(&Gate::CNOT(k - 1, k));
+    }
+    assert!(stm.bond_dims().iter().all(|&d| d == 2));
+    assert!((stm.probability(150).unwrap() - 0.5).abs() < 1e-12);
+
+    let outcome = stm.qubit(100).unwrap().measure();
+    for mut qubit in stm.qubit_iter() {
+        assert_eq!(qubit.measure(), outcome);
+    }
+    assert!(stm.bond_dims().iter().all(|&d| d == 1));
+}
+
+#[test]
+fn measure_01() {
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::H(0));
+    stm.apply(&Gate::CNOT(0, 2));
+    stm.apply(&Gate::X(1));
+
+    let (mut qb1, mut qb2) = stm.qubit_pair(2, 1).unwrap();
+    assert!(qb1.is_from_same_stm(&qb2));
+    let outcome = qb1.measure();
+    assert_eq!(qb2.measure(), Bit::ONE);
+
+    let p = if outcome == Bit::ONE { 1. } else { 0. };
+    assert!((stm.probability(0).unwrap() - p).abs() < 1e-12);
+    let mut expected = [outcome, Bit::ONE, outcome];
+    assert!((stm.amplitude(&expected).unwrap().norm() - 1.).abs() < 1e-12);
+    expected[0] = Bit::from(!bool::from(outcome));
+    assert!(stm.amplitude(&expected).unwrap().norm() < 1e-12);
+}
+
+#[test]
+fn measure_statistics_01() {
+    let mut ones = 0;
+    for seed in 0..400 {
+        let mut stm = MpsSystem::new(NonZeroU16::new(2).unwrap(), seed);
+        stm.apply(&Gate::Ry(1, 1.));
+        if stm.qubit(1).unwrap().measure() == Bit::ONE {
+            ones += 1;
+        }
+    }
+    // p = sin^2(1/2) ~ 0.23
+    let p = f64::from(ones) / 400.;
+    assert!((p - 0.5f64.sin().powi(2)).abs() < 0.06, "{p}");
+}
+
+#[test]
+fn reproducible_01() {
+    let run = || {
+        let mut stm = gen_stm(8);
+        for gate in random_circuit(8, 40, 7) {
+            stm.apply(&gate);
+        }
+        stm.qubit_iter()
+            .map(|mut q| q.measure())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn truncation_01() {
+    let num_qubits = 10;
+    let gates = random_circuit(num_qubits, 150, 3);
+
+    let mut exact = gen_stm(num_qubits);
+    let mut sv = System::new(NonZeroU16::new(num_qubits).unwrap(), 1);
+    let mut capped = gen_stm(num_qubits);
+    capped.set_max_bond_dim(NonZeroUsize::new(4));
+    for gate in &gates {
+        exact.apply(gate);
+        sv.apply(gate);
+        capped.apply(gate);
+    }
+    assert_matches(&exact, &sv, 1e-10);
+    assert!(exact.bond_dims().iter().any(|&d| d > 4));
+    assert!(capped.bond_dims().iter().all(|&d| d <= 4));
+
+    // The truncation error bounds the infidelity to first order.
+    let error = capped.truncation_error();
+    assert!(error > 1e-6);
+    let overlap: num::Complex<f64> = (0..1 << num_qubits)
+        .map(|i| {
+            let b = bits(i, num_qubits);
+            capped.amplitude(&b).unwrap() * sv.as_slice()[i].conj()
+        })
+        .sum();
+    let infidelity = 1. - overlap.norm_sqr();
+    assert!(infidelity > 0.);
+    assert!(infidelity < 2. * error, "{infidelity} {error}");
+}
+
+#[test]
+fn truncation_threshold_01() {
+    let mut stm = gen_stm(2);
+    stm.set_truncation_threshold(0.1).unwrap();
+    // Schmidt weights cos^2(0.1) and sin^2(0.1): the smaller is discarded.
+    stm.apply(&Gate::Ry(0, 0.2));
+    stm.apply(&Gate::CNOT(0, 1));
+    assert_eq!(stm.bond_dims(), vec![1]);
+    assert!((stm.truncation_error() - 0.1f64.sin().powi(2)).abs() < 1e-12);
+    let amp = stm.amplitude(&[Bit::ZERO, Bit::ZERO]).unwrap();
+    assert!((amp.norm() - 1.).abs() < 1e-12);
+}
@@ -0,0 +1,225 @@
+use std::num::NonZeroU16;
+
+use qn::{
+    Bit,
+    Channel,
+    Circuit,
+    DensityMatrixSystem,
+    Gate,
+    GateKind,
+    NoiseModel,
+    NoiseModelError,
+    ReadoutError,
+    ReadoutModel,
+    System,
+};
+
+const EPS: f64 = 1e-12;
+
+fn num_qubits(n: u16) -> NonZeroU16 {
+    NonZeroU16::new(n).unwrap()
+}
+
+fn assert_rho_eq(
+    a: &DensityMatrixSystem<f64>,
+    b: &DensityMatrixSystem<f64>,
+) {
+    for (x, y) in a.as_slice().iter().zip(b.as_slice()) {
+        assert!((x - y).norm() < EPS);
+    }
+}
+
+#[test]
+fn invalid_01() {
+    let mut noise = NoiseModel::<f64>::new();
+    assert_eq!(
+        noise.add_gate_noise(
+            GateKind::H,
+            None,
+            Channel::depolarizing2(0.1).unwrap()
+        ),
+        Err(NoiseModelError::InvalidChannel)
+    );
+    assert_eq!(
+        noise.add_gate_noise(
+            GateKind::CNOT,
+            Some(&[0]),
+            Channel::depolarizing(0.1).unwrap()
+        ),
+        Err(NoiseModelError::InvalidQubits)
+    );
+    assert_eq!(
+        noise.add_gate_noise(
+            GateKind::CNOT,
+            Some(&[1, 1]),
+            Channel::depolarizing(0.1).unwrap()
+        ),
+        Err(NoiseModelError::InvalidQubits)
+    );
+    assert_eq!(
+        noise.set_idle_noise(Some(Channel::depolarizing2(0.1).unwrap())),
+        Err(NoiseModelError::InvalidChannel)
+    );
+    assert_eq!(noise, NoiseModel::new());
+}
+
+#[test]
+fn gate_noise_01() {
+    let channel = Channel::amplitude_damping(0.3).unwrap();
+    let mut noise = NoiseModel::new();
+    noise
+        .add_gate_noise(GateKind::X, None, channel.clone())
+        .unwrap();
+
+    let mut circuit = Circuit::new(num_qubits(2));
+    circuit.apply(Gate::X(0));
+    circuit.apply(Gate::H(1));
+    circuit.apply(Gate::X(1));
+
+    let mut stm = DensityMatrixSystem::new(num_qubits(2), 123);
+    stm.run_noisy(&circuit, &noise);
+
+    let mut expected = DensityMatrixSystem::new(num_qubits(2), 123);
+    expected.apply(&Gate::X(0));
+    expected.apply_channel(&channel, &[0]);
+    expected.apply(&Gate::H(1));
+    expected.apply(&Gate::X(1));
+    expected.apply_channel(&channel, &[1]);
+
+    assert_rho_eq(&stm, &expected);
+}
+
+#[test]
+fn gate_noise_qubits() {
+    let channel = Channel::depolarizing2(0.2).unwrap();
+    let mut noise = NoiseModel::new();
+    noise
+        .add_gate_noise(GateKind::CNOT, Some(&[0, 1]), channel.clone())
+        .unwrap();
+
+    let mut circuit = Circuit::new(num_qubits(3));
+    circuit.apply(Gate::H(0));
+    circuit.apply(Gate::CNOT(0, 1));
+    circuit.apply(Gate::CNOT(1, 2));
+    circuit.apply(Gate::CNOT(1, 0));
+
+    let mut stm = DensityMatrixSystem::new(num_qubits(3), 123);
+    stm.run_noisy(&circuit, &noise);
+
+    let mut expected = DensityMatrixSystem::new(num_qubits(3), 123);
+    expected.apply(&Gate::H(0));
+    expected.apply(&Gate::CNOT(0, 1));
+    expected.apply_channel(&channel, &[0, 1]);
+    expected.apply(&Gate::CNOT(1, 2));
+    expected.apply(&Gate::CNOT(1, 0));
+
+    assert_rho_eq(&stm, &expected);
+}
+
+#[test]
+fn gate_noise_single_on_two() {
+    let channel = Channel::phase_damping(0.4).unwrap();
+    let mut noise = NoiseModel::new();
+    noise
+        .add_gate_noise(GateKind::CZ, None, channel.clone())
+        .unwrap();
+
+    let mut circuit = Circuit::new(num_qubits(2));
+    circuit.apply(Gate::H(0));
+    circuit.apply(Gate::H(1));
+    circuit.apply(Gate::CZ(0, 1));
+
+    let mut stm = DensityMatrixSystem::new(num_qubits(2), 123);
+    stm.run_noisy(&circuit, &noise);
+
+    let mut expected = DensityMatrixSystem::new(num_qubits(2), 123);
+    expected.apply(&Gate::H(0));
+    expected.apply(&Gate::H(1));
+    expected.apply(&Gate::CZ(0, 1));
+    expected.apply_channel(&channel, &[0]);
+    expected.apply_channel(&channel, &[1]);
+
+    assert_rho_eq(&stm, &expected);
+}
+
+#[test]
+fn idle_noise_01() {
+    let channel = Channel::amplitude_damping(0.1).unwrap();
+    let mut noise = NoiseModel::new();
+    noise.set_idle_noise(Some(channel.clone())).unwrap();
+
+    let mut circuit = Circuit::new(num_qubits(3));
+    circuit.apply(Gate::X(0));
+    circuit.apply(Gate::X(1));
+    circuit.apply(Gate::CNOT(0, 2));
+
+    let mut stm = DensityMatrixSystem::new(num_qubits(3), 123);
+    stm.run_noisy(&circuit, &noise);
+
+    let mut expected = DensityMatrixSystem::new(num_qubits(3), 123);
+    expected.apply(&Gate::X(0));
+    expected.apply_channel(&channel, &[1]);
+    expected.apply_channel(&channel, &[2]);
+    expected.apply(&Gate::X(1));
+    expected.apply_channel(&channel, &[0]);
+    expected.apply_channel(&channel, &[2]);
+    expected.apply(&Gate::CNOT(0, 2));
+    expected.apply_channel(&channel, &[1]);
+
+    assert_rho_eq(&stm, &expected);
+}
+
+#[test]
+fn noiseless_model_01() {
+    let mut circuit = Circuit::new(num_qubits(2));
+    circuit.apply(Gate::H(0));
+    circuit.apply(Gate::CNOT(0, 1));
+
+    let mut stm = DensityMatrixSystem::new(num_qubits(2), 123);
+    stm.run_noisy(&circuit, &NoiseModel::new());
+    let mut expected = DensityMatrixSystem::new(num_qubits(2), 123);
+    expected.run(&circuit);
+
+    assert_rho_eq(&stm, &expected);
+}
+
+#[test]
+fn readout_01() {
+    let mut model = ReadoutModel::new();
+    model
+        .set_qubit(0, ReadoutError::new(1., 0.).unwrap())
+        .unwrap();
+    let mut noise = NoiseModel::new();
+    noise.set_readout(Some(model));
+    assert!(noise.readout().is_some());
+
+    let mut circuit = Circuit::new(num_qubits(2));
+    circuit.measure(0);
+    circuit.measure(1);
+
+    let mut stm: System<f64> = System::new(num_qubits(2), 123);
+    assert_eq!(stm.run_noisy(&circuit, &noise), vec![Bit::ONE, Bit::ZERO]);
+    assert_eq!(stm.run(&circuit), vec![Bit::ZERO, Bit::ZERO]);
+
+    let mut stm: DensityMatrixSystem<f64> =
+        DensityMatrixSystem::new(num_qubits(2), 123);
+    assert_eq!(stm.run_noisy(&circuit, &noise), vec![Bit::ONE, Bit::ZERO]);
+}
+
+#[test]
+fn trajectory_01() {
+    // Bit flip with certainty after each X undoes the gate.
+    let mut noise = NoiseModel::new();
+    noise
+        .add_gate_noise(GateKind::X, None, Channel::bit_flip(1.).unwrap())
+        .unwrap();
+
+    let mut circuit = Circuit::new(num_qubits(1));
+    circuit.apply(Gate::X(0));
+    circuit.measure(0);
+
+    for seed in 0..10 {
+        let mut stm: System<f64> = System::new(num_qubits(1), seed);
+        assert_eq!(stm.run_noisy(&circuit, &noise), vec![Bit::ZERO]);
+    }
+}
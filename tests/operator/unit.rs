@@ -0,0 +1,89 @@
+use num::Complex;
+use qn::{
+    LinearOperator,
+    Pauli,
+    PauliString,
+    PauliSum,
+    SparseMatrix,
+    SparseMatrixError,
+};
+
+const EPS: f64 = 1e-12;
+
+#[test]
+fn sparse_invalid_01() {
+    let one = Complex::from(1.);
+    assert_eq!(
+        SparseMatrix::<f64>::from_triplets(2, &[(0, 2, one)]),
+        Err(SparseMatrixError::InvalidIndex)
+    );
+    assert_eq!(
+        SparseMatrix::<f64>::from_triplets(2, &[(0, 1, one)]),
+        Err(SparseMatrixError::NotHermitian)
+    );
+    assert_eq!(
+        SparseMatrix::<f64>::from_triplets(
+            2,
+            &[(0, 1, Complex::i()), (1, 0, Complex::i())]
+        ),
+        Err(SparseMatrixError::NotHermitian)
+    );
+    assert_eq!(
+        SparseMatrix::<f64>::from_triplets(2, &[(1, 1, Complex::i())]),
+        Err(SparseMatrixError::NotHermitian)
+    );
+}
+
+#[test]
+fn sparse_01() {
+    // Pauli Y, with an entry split in two.
+    let i = Complex::i();
+    let mat: SparseMatrix<f64> = SparseMatrix::from_triplets(
+        2,
+        &[(1, 0, i * 0.5), (0, 1, -i), (1, 0, i * 0.5)],
+    )
+    .unwrap();
+
+    assert_eq!(mat.dim(), 2);
+    assert_eq!(mat.nnz(), 2);
+    assert_eq!(mat.get(0, 1), -i);
+    assert_eq!(mat.get(1, 0), i);
+    assert_eq!(mat.get(0, 0), Complex::from(0.));
+    assert_eq!(mat.get(2, 0), Complex::from(0.));
+    assert!(mat.is_valid_dim(2));
+    assert!(!mat.is_valid_dim(4));
+
+    let src = [Complex::from(1.), Complex::new(2., 3.)];
+    let mut dst = [Complex::from(0.); 2];
+    mat.mul_vec(&src, &mut dst);
+    assert!((dst[0] - (-i * src[1])).norm() < EPS);
+    assert!((dst[1] - i * src[0]).norm() < EPS);
+}
+
+#[test]
+fn pauli_sum_01() {
+    let mut ham = PauliSum::new();
+    ham.add_term(2., PauliString::new(&[(1, Pauli::Y)]).unwrap());
+    ham.add_term(-1., PauliString::new(&[(0, Pauli::Z)]).unwrap());
+
+    assert!(!ham.is_valid_dim(2));
+    assert!(!ham.is_valid_dim(6));
+    assert!(ham.is_valid_dim(4));
+    assert!(ham.is_valid_dim(8));
+
+    // H = 2 Y1 - Z0, basis index b0 + 2 b1
+    let src: Vec<_> = (0..4).map(|k| Complex::new(f64::from(k), 1.)).collect();
+    let mut dst = vec![Complex::from(0.); 4];
+    ham.mul_vec(&src, &mut dst);
+
+    let i = Complex::i();
+    let expected = [
+        -src[0] - i * 2. * src[2],
+        src[1] - i * 2. * src[3],
+        -src[2] + i * 2. * src[0],
+        src[3] + i * 2. * src[1],
+    ];
+    for (x, y) in dst.iter().zip(&expected) {
+        assert!((x - y).norm() < EPS);
+    }
+}
@@ -0,0 +1,71 @@
+use qn::{
+    Pauli,
+    PauliString,
+    PauliSum,
+};
+
+#[test]
+fn string_new_01() {
+    let p = PauliString::new(&[(0, Pauli::X), (2, Pauli::Y), (3, Pauli::Z)])
+        .unwrap();
+
+    assert_eq!(p.get(0), Some(Pauli::X));
+    assert_eq!(p.get(1), Some(Pauli::I));
+    assert_eq!(p.get(2), Some(Pauli::Y));
+    assert_eq!(p.get(3), Some(Pauli::Z));
+    assert_eq!(p.get(4), Some(Pauli::I));
+    assert_eq!(p.weight(), 3);
+    assert_eq!(p.num_qubits(), 4);
+}
+
+#[test]
+fn string_new_02() {
+    let p = PauliString::new(&[(5, Pauli::I)]).unwrap();
+
+    assert_eq!(p, PauliString::identity());
+    assert_eq!(p.weight(), 0);
+    assert_eq!(p.num_qubits(), 0);
+}
+
+#[test]
+fn string_invalid_01() {
+    assert!(PauliString::new(&[(1, Pauli::X), (1, Pauli::X)]).is_none());
+    assert!(PauliString::new(&[(1, Pauli::X), (1, Pauli::I)]).is_none());
+
+    let bits = u16::try_from(usize::BITS).unwrap();
+    assert!(PauliString::new(&[(bits, Pauli::X)]).is_none());
+    assert!(PauliString::identity().get(bits).is_none());
+}
+
+#[test]
+fn string_commutes_01() {
+    let x0 = PauliString::new(&[(0, Pauli::X)]).unwrap();
+    let z0 = PauliString::new(&[(0, Pauli::Z)]).unwrap();
+    let z1 = PauliString::new(&[(1, Pauli::Z)]).unwrap();
+    let xx = PauliString::new(&[(0, Pauli::X), (1, Pauli::X)]).unwrap();
+    let zz = PauliString::new(&[(0, Pauli::Z), (1, Pauli::Z)]).unwrap();
+    let yy = PauliString::new(&[(0, Pauli::Y), (1, Pauli::Y)]).unwrap();
+
+    assert!(!x0.commutes_with(&z0));
+    assert!(x0.commutes_with(&z1));
+    assert!(xx.commutes_with(&zz));
+    assert!(xx.commutes_with(&yy));
+    assert!(!xx.commutes_with(&z1));
+    assert!(x0.commutes_with(&x0));
+}
+
+#[test]
+fn sum_01() {
+    let mut ham = PauliSum::new();
+    assert_eq!(ham.num_qubits(), 0);
+    assert_eq!(ham.norm_bound(), 0.);
+
+    let zz = PauliString::new(&[(0, Pauli::Z), (3, Pauli::Z)]).unwrap();
+    let x1 = PauliString::new(&[(1, Pauli::X)]).unwrap();
+    ham.add_term(-1., zz);
+    ham.add_term(0.5, x1);
+
+    assert_eq!(ham.terms(), &[(-1., zz), (0.5, x1)]);
+    assert_eq!(ham.num_qubits(), 4);
+    assert_eq!(ham.norm_bound(), 1.5);
+}
@@ -1,6 +1,11 @@
-use std::num::NonZeroU16;
+use std::{
+    num::NonZeroU16,
+    thread,
+};
 
 use qn::{
+    Bit,
+    Gate,
     Qubit,
     System,
 };
@@ -69,3 +74,179 @@ fn get_index_01() {
     assert_eq!(qb.0.index(), 1);
     assert_eq!(qb.1.index(), 0);
 }
+
+#[test]
+fn apply_01() {
+    let num_qubits = NonZeroU16::new(2).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 1);
+    {
+        let (mut qb0, mut qb1) = stm.qubit_pair(0, 1).unwrap();
+        qb0.apply(&Gate::X(0));
+        qb1.apply(&Gate::H(1));
+        qb1.apply(&Gate::Rz(1, 0.3));
+        qb1.apply(&Gate::H(1));
+        assert_eq!(qb0.measure(), Bit::ONE);
+    }
+
+    let mut expected = System::<f64>::new(num_qubits, 1);
+    for gate in [Gate::X(0), Gate::H(1), Gate::Rz(1, 0.3), Gate::H(1)] {
+        expected.apply(&gate);
+    }
+    for (a, b) in stm.as_slice().iter().zip(expected.as_slice()) {
+        assert!((a - b).norm() < 1e-12);
+    }
+}
+
+#[test]
+#[should_panic(expected = "invalid gate")]
+fn apply_panic_01() {
+    let num_qubits = NonZeroU16::new(2).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 1);
+    let mut qubit = stm.qubit(0).unwrap();
+    qubit.apply(&Gate::X(1));
+}
+
+#[test]
+#[should_panic(expected = "invalid gate")]
+fn apply_panic_02() {
+    let num_qubits = NonZeroU16::new(3).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 1);
+    let mut qubit = stm.qubit(0).unwrap();
+    qubit.apply(&Gate::CNOT(1, 2));
+}
+
+#[test]
+#[should_panic(expected = "invalid gate")]
+fn apply_panic_03() {
+    let num_qubits = NonZeroU16::new(2).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 1);
+    let mut qubit = stm.qubit(0).unwrap();
+    qubit.apply(&Gate::CNOT(0, 2));
+}
+
+#[test]
+fn apply_two_01() {
+    // Single-qubit gates queued on both qubits are applied before the
+    // two-qubit gate, and gates queued afterwards after it.
+    let num_qubits = NonZeroU16::new(3).unwrap();
+    let gates = [
+        Gate::H(0),
+        Gate::Rx(2, 0.4),
+        Gate::CNOT(0, 2),
+        Gate::Rz(2, 0.3),
+        Gate::SWAP(1, 0),
+        Gate::H(1),
+    ];
+    let mut stm = System::<f64>::new(num_qubits, 1);
+    {
+        let mut qubits: Vec<_> = stm.qubit_iter().collect();
+        for gate in &gates {
+            let k = gate.qubits()[0];
+            qubits[usize::from(k)].apply(gate);
+        }
+    }
+
+    let mut expected = System::<f64>::new(num_qubits, 1);
+    for gate in &gates {
+        expected.apply(gate);
+    }
+    for (a, b) in stm.as_slice().iter().zip(expected.as_slice()) {
+        assert!((a - b).norm() < 1e-12);
+    }
+}
+
+#[test]
+fn apply_two_threads_01() {
+    // Bell pairs (2k, 2k + 1) prepared by different threads.
+    let num_qubits = NonZeroU16::new(8).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 1);
+    let outcomes: Vec<_> = thread::scope(|s| {
+        let mut qubits: Vec<_> = stm.qubit_iter().collect();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let mut hi = qubits.pop().unwrap();
+                let mut lo = qubits.pop().unwrap();
+                s.spawn(move || {
+                    let (k, l) = (lo.index(), hi.index());
+                    lo.apply(&Gate::H(k));
+                    hi.apply(&Gate::CNOT(k, l));
+                    (lo.measure(), hi.measure())
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert!(outcomes.iter().all(|(a, b)| a == b));
+}
+
+#[test]
+fn apply_threads_01() {
+    let num_qubits = NonZeroU16::new(10).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 1);
+    thread::scope(|s| {
+        for mut qubit in stm.qubit_iter() {
+            s.spawn(move || {
+                let k = qubit.index();
+                for _ in 0..100 {
+                    qubit.apply(&Gate::Rx(k, 0.1));
+                    qubit.apply(&Gate::Rz(k, 0.2));
+                    qubit.apply(&Gate::Rz(k, -0.2));
+                }
+                // Rx(10) on each qubit, then X on even qubits.
+                if k % 2 == 0 {
+                    qubit.apply(&Gate::X(k));
+                }
+            });
+        }
+    });
+
+    let mut expected = System::<f64>::new(num_qubits, 1);
+    for k in 0..10 {
+        expected.apply(&Gate::Rx(k, 10.));
+        if k % 2 == 0 {
+            expected.apply(&Gate::X(k));
+        }
+    }
+    for (a, b) in stm.as_slice().iter().zip(expected.as_slice()) {
+        assert!((a - b).norm() < 1e-10);
+    }
+}
+
+#[test]
+fn measure_threads_01() {
+    // GHZ state: all measurements agree, whichever thread measures first.
+    for seed in 0..10 {
+        let num_qubits = NonZeroU16::new(8).unwrap();
+        let mut stm = System::<f64>::new(num_qubits, seed);
+        stm.apply(&Gate::H(0));
+        for k in 1..8 {
+            stm.apply(&Gate::CNOT(k - 1, k));
+        }
+        let outcomes: Vec<_> = thread::scope(|s| {
+            let handles: Vec<_> = stm
+                .qubit_iter()
+                .map(|mut qubit| {
+                    s.spawn(move || {
+                        let k = qubit.index();
+                        qubit.apply(&Gate::Z(k));
+                        qubit.apply(&Gate::Z(k));
+                        qubit.measure()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert!(outcomes.iter().all(|&b| b == outcomes[0]));
+    }
+}
+
+#[test]
+fn measure_rounding_01() {
+    let num_qubits = NonZeroU16::new(1).unwrap();
+    let mut stm = System::<f64>::new(num_qubits, 1);
+    let amp = stm.as_mut_slice();
+    amp[0].re = 0.;
+    amp[1].re = 1. + f64::EPSILON;
+
+    assert_eq!(stm.qubit(0).unwrap().measure(), Bit::ONE);
+}
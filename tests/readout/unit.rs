@@ -0,0 +1,205 @@
+use std::num::NonZeroU16;
+
+use qn::{
+    Bit,
+    Gate,
+    ReadoutError,
+    ReadoutModel,
+    ReadoutModelError,
+    System,
+};
+
+const EPS: f64 = 1e-12;
+
+fn gen_stm(num_qubits: u16) -> System<f64> {
+    System::new(NonZeroU16::new(num_qubits).unwrap(), 123)
+}
+
+#[test]
+fn invalid_01() {
+    assert_eq!(
+        ReadoutError::new(1.1, 0.),
+        Err(ReadoutModelError::InvalidProbability)
+    );
+
+    let mut model = ReadoutModel::new();
+    model
+        .set_qubit(0, ReadoutError::new(0.1, 0.2).unwrap())
+        .unwrap();
+    assert_eq!(
+        model.set_correlated(&[0, 1], vec![0.25; 16]),
+        Err(ReadoutModelError::InvalidQubits)
+    );
+    assert_eq!(
+        model.set_correlated(&[1, 1], vec![0.25; 16]),
+        Err(ReadoutModelError::InvalidQubits)
+    );
+    assert_eq!(
+        model.set_correlated(&[1, 2], vec![0.25; 4]),
+        Err(ReadoutModelError::InvalidDimension)
+    );
+    assert_eq!(
+        model.set_correlated(&[1], vec![0.5, 0.5, 0.6, 0.5]),
+        Err(ReadoutModelError::NotStochastic)
+    );
+    assert_eq!(model.set_correlated(&[1, 2], vec![0.25; 16]), Ok(()));
+    assert_eq!(
+        model.set_qubit(2, ReadoutError::new(0.1, 0.2).unwrap()),
+        Err(ReadoutModelError::InvalidQubits)
+    );
+
+    assert_eq!(model.qubit(0).unwrap().p01(), 0.1);
+    assert_eq!(model.qubit(0).unwrap().p10(), 0.2);
+    assert!(model.qubit(1).is_none());
+}
+
+#[test]
+fn invalid_02() {
+    let mut stm = gen_stm(2);
+    let mut model = ReadoutModel::new();
+    model
+        .set_qubit(3, ReadoutError::new(0.1, 0.1).unwrap())
+        .unwrap();
+    assert!(!model.is_valid(2));
+    assert!(model.is_valid(4));
+    assert_eq!(
+        stm.set_readout_model(Some(model)),
+        Err(ReadoutModelError::InvalidQubits)
+    );
+    assert!(stm.readout_model().is_none());
+    assert_eq!(stm.sample_histogram(10)[0], 10);
+
+    let mut model = ReadoutModel::new();
+    model
+        .set_correlated(
+            &[1, 2],
+            vec![
+                1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 1.,
+            ],
+        )
+        .unwrap();
+    assert_eq!(
+        stm.set_readout_model(Some(model)),
+        Err(ReadoutModelError::InvalidQubits)
+    );
+}
+
+#[test]
+fn measure_01() {
+    let mut stm = gen_stm(2);
+    stm.apply(&Gate::X(1));
+    let mut model = ReadoutModel::new();
+    model
+        .set_qubit(0, ReadoutError::new(1., 0.).unwrap())
+        .unwrap();
+    model
+        .set_qubit(1, ReadoutError::new(0., 1.).unwrap())
+        .unwrap();
+    stm.set_readout_model(Some(model)).unwrap();
+    assert!(stm.readout_model().is_some());
+
+    let (mut qb0, mut qb1) = stm.qubit_pair(0, 1).unwrap();
+    assert_eq!(qb0.measure(), Bit::ONE);
+    assert_eq!(qb1.measure(), Bit::ZERO);
+
+    // the state is projected onto actual outcomes
+    assert!(stm.probability(0).unwrap().abs() < EPS);
+    assert!((stm.probability(1).unwrap() - 1.).abs() < EPS);
+
+    stm.set_readout_model(None).unwrap();
+    assert_eq!(stm.qubit(0).unwrap().measure(), Bit::ZERO);
+}
+
+#[test]
+fn sample_01() {
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::H(0));
+    stm.apply(&Gate::CNOT(0, 2));
+    let samples = stm.sample(1000);
+    assert_eq!(samples.len(), 1000);
+    assert!(samples.iter().all(|&s| s == 0b000 || s == 0b101));
+
+    let hist = stm.sample_histogram(1000);
+    assert_eq!(hist.iter().sum::<u64>(), 1000);
+    assert!(hist[0] > 400 && hist[5] > 400, "{hist:?}");
+
+    // sampling does not change the state
+    assert!((stm.probability(2).unwrap() - 0.5).abs() < EPS);
+}
+
+#[test]
+fn sample_02() {
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::X(0));
+    let mut model = ReadoutModel::new();
+    // swap reported values of qubits 0 and 2
+    let mut confusion = vec![0.; 16];
+    for (read, outcome) in [(0, 0), (1, 2), (2, 1), (3, 3)] {
+        confusion[read * 4 + outcome] = 1.;
+    }
+    model.set_correlated(&[0, 2], confusion).unwrap();
+    model
+        .set_qubit(1, ReadoutError::new(1., 1.).unwrap())
+        .unwrap();
+    stm.set_readout_model(Some(model)).unwrap();
+
+    assert!(stm.sample(100).iter().all(|&s| s == 0b110));
+}
+
+#[test]
+fn mitigate_01() {
+    let model = ReadoutModel::<f64>::new();
+    assert!(model.mitigate(&[1, 2, 3]).is_none());
+    assert!(model.mitigate(&[0, 0]).is_none());
+
+    let mut model = ReadoutModel::new();
+    model
+        .set_qubit(2, ReadoutError::new(0.1, 0.2).unwrap())
+        .unwrap();
+    assert!(model.mitigate(&[1, 2, 3, 4]).is_none());
+
+    let mut model = ReadoutModel::new();
+    model
+        .set_qubit(0, ReadoutError::new(0.5, 0.5).unwrap())
+        .unwrap();
+    assert!(model.mitigate(&[1, 2]).is_none());
+}
+
+#[test]
+fn mitigate_02() {
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::Ry(0, 1.));
+    stm.apply(&Gate::CNOT(0, 1));
+    stm.apply(&Gate::H(2));
+    let expected: Vec<f64> =
+        stm.as_slice().iter().map(|a| a.norm_sqr()).collect();
+
+    let mut model = ReadoutModel::new();
+    model
+        .set_qubit(0, ReadoutError::new(0.05, 0.1).unwrap())
+        .unwrap();
+    let confusion = vec![
+        0.9, 0.05, 0.05, 0.02, //
+        0.04, 0.9, 0.01, 0.03, //
+        0.03, 0.02, 0.9, 0.05, //
+        0.03, 0.03, 0.04, 0.9,
+    ];
+    model.set_correlated(&[2, 1], confusion).unwrap();
+    stm.set_readout_model(Some(model.clone())).unwrap();
+
+    let shots = 100_000;
+    let hist = stm.sample_histogram(shots);
+    let raw: Vec<f64> = hist.iter().map(|&x| x as f64 / shots as f64).collect();
+    let mitigated = model.mitigate(&hist).unwrap();
+
+    assert!((mitigated.iter().sum::<f64>() - 1.).abs() < 1e-9);
+    let err = |probs: &[f64]| {
+        probs
+            .iter()
+            .zip(&expected)
+            .map(|(p, q)| (p - q).abs())
+            .fold(0., f64::max)
+    };
+    assert!(err(&raw) > 0.03, "{raw:?}");
+    assert!(err(&mitigated) < 0.01, "{mitigated:?}");
+}
@@ -0,0 +1,157 @@
+use std::num::NonZeroU16;
+
+use qn::{
+    Gate,
+    Schedule,
+    System,
+};
+
+/// Random circuit, generated with a simple linear congruential generator.
+fn random_circuit(
+    num_qubits: u16,
+    len: usize,
+    seed: u64,
+) -> Vec<Gate<f64>> {
+    let mut state = seed;
+    let mut next = |m: u16| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        u16::try_from((state >> 33) % u64::from(m)).unwrap()
+    };
+    (0..len)
+        .map(|_| {
+            let a = next(num_qubits);
+            let b = (a + 1 + next(num_qubits - 1)) % num_qubits;
+            let theta = f64::from(next(1000)) / 100.;
+            match next(8) {
+                0 => Gate::H(a),
+                1 => Gate::X(a),
+                2 => Gate::Rx(a, theta),
+                3 => Gate::Phase(a, theta),
+                4 => Gate::Ry(a, theta),
+                5 => Gate::CNOT(a, b),
+                6 => Gate::CZ(a, b),
+                _ => Gate::SWAP(a, b),
+            }
+        })
+        .collect()
+}
+
+fn assert_scheduled(
+    num_qubits: u16,
+    block_qubits: u16,
+    gates: &[Gate<f64>],
+) -> Schedule<f64> {
+    let num_qubits = NonZeroU16::new(num_qubits).unwrap();
+    let schedule = Schedule::with_block_qubits(num_qubits, block_qubits, gates);
+    let mut stm = System::new(num_qubits, 1);
+    stm.apply_schedule(&schedule);
+    let mut expected = System::new(num_qubits, 1);
+    for gate in gates {
+        expected.apply(gate);
+    }
+    for (a, b) in stm.as_slice().iter().zip(expected.as_slice()) {
+        assert!((a - b).norm() < 1e-12, "{a} != {b}");
+    }
+    schedule
+}
+
+#[test]
+fn new_01() {
+    let num_qubits = NonZeroU16::new(20).unwrap();
+    let schedule = Schedule::<f64>::new(num_qubits, &[]);
+    assert_eq!(schedule.num_qubits(), num_qubits);
+    assert_eq!(schedule.block_qubits(), 14);
+    assert_eq!(schedule.num_sweeps(), 0);
+}
+
+#[test]
+fn new_02() {
+    let num_qubits = NonZeroU16::new(5).unwrap();
+    let schedule = Schedule::<f64>::new(num_qubits, &[]);
+    assert_eq!(schedule.block_qubits(), 5);
+}
+
+#[test]
+#[should_panic(expected = "invalid block size")]
+fn new_panic_01() {
+    let num_qubits = NonZeroU16::new(5).unwrap();
+    let _ = Schedule::<f64>::with_block_qubits(num_qubits, 1, &[]);
+}
+
+#[test]
+#[should_panic(expected = "invalid gate")]
+fn new_panic_02() {
+    let num_qubits = NonZeroU16::new(5).unwrap();
+    let _ = Schedule::<f64>::new(num_qubits, &[Gate::H(5)]);
+}
+
+#[test]
+fn low_qubits_01() {
+    let gates: Vec<_> = (0..4)
+        .map(Gate::H)
+        .chain((1..4).map(|k| Gate::CNOT(k - 1, k)))
+        .collect();
+    let schedule = assert_scheduled(8, 4, &gates);
+    assert_eq!(schedule.num_swaps(), 0);
+    assert_eq!(schedule.num_sweeps(), 1);
+}
+
+#[test]
+fn high_qubits_01() {
+    let gates = [Gate::H(7), Gate::Rx(7, 0.3), Gate::CNOT(7, 6)];
+    let schedule = assert_scheduled(8, 3, &gates);
+    // Two swaps to bring qubits low, two stages of gates, and two swaps to
+    // restore the order.
+    assert_eq!(schedule.num_swaps(), 4);
+    assert_eq!(schedule.num_sweeps(), 6);
+}
+
+#[test]
+fn random_circuit_01() {
+    for seed in 0..5 {
+        let gates = random_circuit(9, 150, seed);
+        for block_qubits in [2, 3, 5, 9, 12] {
+            assert_scheduled(9, block_qubits, &gates);
+        }
+    }
+}
+
+#[test]
+fn parallel_01() {
+    // Swaps with the highest qubit, processed in parallel.
+    let num_qubits = NonZeroU16::new(8).unwrap();
+    let gates: Vec<_> = (0..8)
+        .map(|k| Gate::Ry(k, 0.3 * f64::from(k + 1)))
+        .chain((1..8).map(|k| Gate::CNOT(k - 1, k)))
+        .chain([Gate::H(7), Gate::CZ(7, 0)])
+        .collect();
+    let schedule = Schedule::with_block_qubits(num_qubits, 3, &gates);
+    assert!(schedule.num_swaps() > 0);
+
+    let mut stm = System::new(num_qubits, 1);
+    stm.set_sequential_threshold(0);
+    stm.apply_schedule(&schedule);
+    let mut expected = System::new(num_qubits, 1);
+    expected.set_sequential_threshold(usize::MAX);
+    expected.apply_schedule(&schedule);
+    for (a, b) in stm.as_slice().iter().zip(expected.as_slice()) {
+        assert_eq!(a, b);
+    }
+    let mut direct = System::new(num_qubits, 1);
+    for gate in &gates {
+        direct.apply(gate);
+    }
+    for (a, b) in stm.as_slice().iter().zip(direct.as_slice()) {
+        assert!((a - b).norm() < 1e-12, "{a} != {b}");
+    }
+}
+
+#[test]
+#[should_panic(expected = "schedule does not fit in the system")]
+fn apply_schedule_panic_01() {
+    let schedule = Schedule::new(NonZeroU16::new(3).unwrap(), &[Gate::H(0)]);
+    let mut stm = System::<f64>::new(NonZeroU16::new(4).unwrap(), 1);
+    stm.apply_schedule(&schedule);
+}
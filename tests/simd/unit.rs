@@ -0,0 +1,201 @@
+use std::num::NonZeroU16;
+
+use num::Complex;
+use qn::{
+    Float,
+    Gate,
+    Kernels,
+    Matrix2,
+    SoaSystem,
+    System,
+};
+
+/// System in a pseudo-random, unnormalized state.
+fn gen_stm<T>(num_qubits: u16) -> System<T>
+where
+    T: Float,
+{
+    let mut stm = System::new(NonZeroU16::new(num_qubits).unwrap(), 1);
+    let mut state = 123u64;
+    for a in stm.as_mut_slice() {
+        let mut next = || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            T::from((state >> 33) % 2000).unwrap() / T::from(1000).unwrap()
+                - T::one()
+        };
+        *a = Complex::new(next(), next());
+    }
+    stm
+}
+
+fn apply_scalar<T>(
+    amp: &mut [Complex<T>],
+    target: u16,
+    m: &Matrix2<T>,
+) where
+    T: Float,
+{
+    let half = 1usize << target;
+    for chunk in amp.chunks_mut(half << 1) {
+        let (lo, hi) = chunk.split_at_mut(half);
+        for (a0, a1) in lo.iter_mut().zip(hi) {
+            let (x0, x1) = (*a0, *a1);
+            *a0 = m[0][0] * x0 + m[0][1] * x1;
+            *a1 = m[1][0] * x0 + m[1][1] * x1;
+        }
+    }
+}
+
+fn matrices<T>() -> Vec<Matrix2<T>>
+where
+    T: Float,
+{
+    let c = |re: f64, im: f64| {
+        Complex::new(T::from(re).unwrap(), T::from(im).unwrap())
+    };
+    let zero = c(0., 0.);
+    vec![
+        [[c(0.6, 0.), c(0., 0.8)], [c(0., 0.8), c(0.6, 0.)]],
+        [[c(0.3, -0.1), c(0.2, 0.9)], [c(-0.7, 0.4), c(0.1, 0.5)]],
+        [[c(0.6, 0.8), zero], [zero, c(0.6, -0.8)]],
+        [[c(1., 0.), zero], [zero, c(0., 1.)]],
+        [[c(-0.28, 0.96), zero], [zero, c(1., 0.)]],
+    ]
+}
+
+fn check_apply<T>()
+where
+    T: Float,
+{
+    for num_qubits in 1..=7 {
+        for target in 0..num_qubits {
+            for m in matrices::<T>() {
+                let mut stm = gen_stm::<T>(num_qubits);
+                let mut expected = stm.as_slice().to_vec();
+                stm.apply(&Gate::U(target, m));
+                apply_scalar(&mut expected, target, &m);
+                assert_eq!(stm.as_slice(), expected);
+            }
+        }
+    }
+}
+
+fn check_apply_split<T>()
+where
+    T: Float,
+{
+    for num_qubits in 1..=7 {
+        for target in 0..num_qubits {
+            for m in matrices::<T>() {
+                let stm = gen_stm::<T>(num_qubits);
+                let mut expected = stm.as_slice().to_vec();
+                let mut soa = SoaSystem::from_system(&stm);
+                soa.apply(&Gate::U(target, m));
+                apply_scalar(&mut expected, target, &m);
+                assert!(soa.amplitudes().eq(expected));
+            }
+        }
+    }
+}
+
+fn check_probability<T>()
+where
+    T: Float,
+{
+    for num_qubits in 1..=7 {
+        let stm = gen_stm::<T>(num_qubits);
+        for index in 0..num_qubits {
+            let mask = 1usize << index;
+            let expected = stm
+                .as_slice()
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| i & mask == mask)
+                .map(|(_, a)| a.norm_sqr())
+                .sum::<T>();
+            let tol = expected * T::epsilon() * T::from(64).unwrap();
+            let p = stm.probability(index).unwrap();
+            assert!((p - expected).abs() <= tol);
+            let p = SoaSystem::from_system(&stm).probability(index).unwrap();
+            assert!((p - expected).abs() <= tol);
+        }
+    }
+}
+
+#[test]
+fn apply_01() {
+    check_apply::<f32>();
+}
+
+#[test]
+fn apply_02() {
+    check_apply::<f64>();
+}
+
+#[test]
+fn apply_split_01() {
+    check_apply_split::<f32>();
+}
+
+#[test]
+fn apply_split_02() {
+    check_apply_split::<f64>();
+}
+
+#[test]
+fn probability_01() {
+    check_probability::<f32>();
+}
+
+#[test]
+fn probability_02() {
+    check_probability::<f64>();
+}
+
+#[test]
+fn phase_01() {
+    let mut stm = gen_stm::<f64>(6);
+    let mut expected = stm.as_slice().to_vec();
+    for target in 0..6 {
+        stm.apply(&Gate::Z(target));
+        stm.apply(&Gate::S(target));
+        stm.apply(&Gate::Rz(target, 0.3));
+        stm.apply(&Gate::Phase(target, -0.4));
+    }
+    let (s, c) = (0.15f64.sin(), 0.15f64.cos());
+    let phase = Complex::from_polar(1., -0.4);
+    let one = Complex::new(1., 0.);
+    for target in 0..6 {
+        let zero = Complex::new(0., 0.);
+        let m = [[one, zero], [zero, -one]];
+        apply_scalar(&mut expected, target, &m);
+        let m = [[one, zero], [zero, Complex::i()]];
+        apply_scalar(&mut expected, target, &m);
+        let m = [[Complex::new(c, -s), zero], [zero, Complex::new(c, s)]];
+        apply_scalar(&mut expected, target, &m);
+        let m = [[one, zero], [zero, phase]];
+        apply_scalar(&mut expected, target, &m);
+    }
+    for (a, b) in stm.as_slice().iter().zip(&expected) {
+        assert!((a - b).norm() < 1e-14);
+    }
+}
+
+#[test]
+fn kernels_01() {
+    // The kernels are public, and agree with scalar arithmetic.
+    let stm = gen_stm::<f64>(5);
+    let amp = stm.as_slice();
+    let expected = amp.iter().map(Complex::norm_sqr).sum::<f64>();
+    let sum = <f64 as Kernels>::norm_sqr_sum(amp);
+    assert!((sum - expected).abs() <= expected * f64::EPSILON * 64.);
+
+    let c = Complex::new(0.6, -0.8);
+    let mut scaled = amp.to_vec();
+    <f64 as Kernels>::scale(&mut scaled, c);
+    for (x, y) in scaled.iter().zip(amp) {
+        assert_eq!(*x, y * c);
+    }
+}
@@ -0,0 +1,272 @@
+use std::num::NonZeroU16;
+
+use qn::{
+    Backend,
+    Bit,
+    Channel,
+    EventKind,
+    Gate,
+    ReadoutError,
+    ReadoutModel,
+    Schedule,
+    SoaSystem,
+    Split,
+    System,
+};
+
+fn gen_stm(num_qubits: u16) -> SoaSystem<f64> {
+    System::with_layout(NonZeroU16::new(num_qubits).unwrap(), 123, Split)
+}
+
+/// Random circuit, generated with a simple linear congruential generator.
+fn random_circuit(
+    num_qubits: u16,
+    len: usize,
+    seed: u64,
+) -> Vec<Gate<f64>> {
+    let mut state = seed;
+    let mut next = |m: u16| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        u16::try_from((state >> 33) % u64::from(m)).unwrap()
+    };
+    (0..len)
+        .map(|_| {
+            let a = next(num_qubits);
+            let b = (a + 1 + next(num_qubits - 1)) % num_qubits;
+            let theta = f64::from(next(1000)) / 100.;
+            match next(8) {
+                0 => Gate::H(a),
+                1 => Gate::Y(a),
+                2 => Gate::Rx(a, theta),
+                3 => Gate::Phase(a, theta),
+                4 => Gate::Rz(a, theta),
+                5 => Gate::CNOT(a, b),
+                6 => Gate::CZ(a, b),
+                _ => Gate::SWAP(a, b),
+            }
+        })
+        .collect()
+}
+
+fn assert_matches(
+    soa: &SoaSystem<f64>,
+    stm: &System<f64>,
+) {
+    assert_eq!(soa.amplitudes().count(), stm.as_slice().len());
+    for (a, b) in soa.amplitudes().zip(stm.as_slice()) {
+        assert!((a - b).norm() < 1e-12, "{a} != {b}");
+    }
+}
+
+#[test]
+fn new_01() {
+    let stm = gen_stm(3);
+    assert_eq!(stm.num_qubits().get(), 3);
+    assert_eq!(stm.re().len(), 8);
+    assert_eq!(stm.im().len(), 8);
+    assert_eq!(stm.amplitude(0).unwrap().re, 1.);
+    assert!(stm.amplitudes().skip(1).all(|a| a.norm() == 0.));
+}
+
+#[test]
+fn amplitude_01() {
+    let stm = gen_stm(3);
+    assert!(stm.amplitude(7).is_some());
+    assert!(stm.amplitude(8).is_none());
+}
+
+#[test]
+fn random_circuit_01() {
+    for seed in 0..5 {
+        let num_qubits = 7;
+        let mut soa = gen_stm(num_qubits);
+        let mut stm = System::new(NonZeroU16::new(num_qubits).unwrap(), 1);
+        for gate in random_circuit(num_qubits, 150, seed) {
+            soa.apply(&gate);
+            stm.apply(&gate);
+        }
+        assert_matches(&soa, &stm);
+        for k in 0..num_qubits {
+            let p = Backend::probability(&soa, k).unwrap();
+            assert!((p - stm.probability(k).unwrap()).abs() < 1e-12);
+        }
+        assert!(Backend::probability(&soa, num_qubits).is_none());
+    }
+}
+
+#[test]
+fn measure_01() {
+    let num_qubits = NonZeroU16::new(6).unwrap();
+    let gates = random_circuit(6, 60, 7);
+    let mut soa = System::with_layout(num_qubits, 42, Split);
+    let mut stm = System::new(num_qubits, 42);
+    for gate in &gates {
+        soa.apply(gate);
+        stm.apply(gate);
+    }
+    for k in 0..6 {
+        assert_eq!(
+            Backend::measure(&mut soa, k),
+            Backend::measure(&mut stm, k)
+        );
+        assert_matches(&soa, &stm);
+    }
+    assert!(Backend::measure(&mut soa, 6).is_none());
+}
+
+#[test]
+fn convert_01() {
+    let num_qubits = NonZeroU16::new(5).unwrap();
+    let mut stm = System::new(num_qubits, 9);
+    for gate in random_circuit(5, 40, 3) {
+        stm.apply(&gate);
+    }
+    let soa = SoaSystem::from_system(&stm);
+    assert_matches(&soa, &stm);
+
+    let mut back = soa.into_system();
+    assert_eq!(back.as_slice(), stm.as_slice());
+    for k in 0..5 {
+        assert_eq!(
+            Backend::measure(&mut back, k),
+            Backend::measure(&mut stm, k)
+        );
+    }
+}
+
+#[test]
+fn reset_01() {
+    let mut stm = gen_stm(4);
+    stm.apply(&Gate::H(2));
+    stm.apply(&Gate::CNOT(2, 3));
+    stm.reset();
+    assert_eq!(stm.amplitude(0).unwrap().re, 1.);
+    assert!(stm.amplitudes().skip(1).all(|a| a.norm() == 0.));
+}
+
+#[test]
+#[should_panic(expected = "invalid gate")]
+fn apply_panic_01() {
+    gen_stm(2).apply(&Gate::CNOT(0, 2));
+}
+
+#[test]
+fn qubit_01() {
+    let mut stm = gen_stm(6);
+    std::thread::scope(|s| {
+        for mut qubit in stm.qubit_iter() {
+            s.spawn(move || {
+                let k = qubit.index();
+                qubit.apply(&Gate::H(k));
+                qubit.apply(&Gate::H(k));
+                qubit.apply(&Gate::X(k));
+                assert_eq!(qubit.measure(), Bit::ONE);
+            });
+        }
+    });
+    assert!((stm.amplitude(63).unwrap().re - 1.).abs() < 1e-12);
+}
+
+#[test]
+fn schedule_01() {
+    let num_qubits = NonZeroU16::new(7).unwrap();
+    let gates = random_circuit(7, 100, 11);
+    let schedule = Schedule::with_block_qubits(num_qubits, 3, &gates);
+    let mut soa = gen_stm(7);
+    soa.apply_schedule(&schedule);
+    let mut stm = System::new(num_qubits, 1);
+    for gate in &gates {
+        stm.apply(gate);
+    }
+    assert_matches(&soa, &stm);
+}
+
+#[test]
+fn parallel_01() {
+    let num_qubits = NonZeroU16::new(8).unwrap();
+    let gates = random_circuit(8, 100, 5);
+    let mut soa = gen_stm(8);
+    soa.set_sequential_threshold(0);
+    let mut stm = System::new(num_qubits, 1);
+    stm.set_sequential_threshold(usize::MAX);
+    for gate in &gates {
+        soa.apply(gate);
+        stm.apply(gate);
+    }
+    assert_matches(&soa, &stm);
+    for k in 0..8 {
+        let p = soa.probability(k).unwrap();
+        assert!((p - stm.probability(k).unwrap()).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn reproducible_01() {
+    let num_qubits = NonZeroU16::new(14).unwrap();
+    let mut soa = System::with_layout(num_qubits, 1, Split);
+    soa.set_reproducible(true);
+    for k in 0..14 {
+        soa.apply(&Gate::Rx(k, 0.1 * f64::from(k)));
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+    for k in 0..14 {
+        let p = pool.install(|| soa.probability(k).unwrap());
+        assert_eq!(p.to_bits(), soa.probability(k).unwrap().to_bits());
+    }
+}
+
+#[test]
+fn apply_channel_01() {
+    let mut stm = gen_stm(2);
+    stm.set_journal(true);
+    stm.apply(&Gate::X(0));
+    stm.apply(&Gate::H(1));
+    stm.apply_channel(&Channel::amplitude_damping(1.).unwrap(), &[0]);
+    assert!((stm.probability(0).unwrap()).abs() < 1e-12);
+    assert!((stm.probability(1).unwrap() - 0.5).abs() < 1e-12);
+    let norm: f64 = stm.amplitudes().map(|a| a.norm_sqr()).sum();
+    assert!((norm - 1.).abs() < 1e-12);
+    assert!(matches!(
+        stm.journal().unwrap()[2].kind,
+        EventKind::Channel { .. }
+    ));
+}
+
+#[test]
+fn readout_01() {
+    let mut stm = gen_stm(2);
+    let mut model = ReadoutModel::new();
+    model
+        .set_qubit(0, ReadoutError::new(1., 0.).unwrap())
+        .unwrap();
+    stm.set_readout_model(Some(model)).unwrap();
+    stm.set_journal(true);
+    assert_eq!(stm.qubit(0).unwrap().measure(), Bit::ONE);
+    assert!(stm.sample(10).iter().all(|&s| s == 0b01));
+    assert!(matches!(
+        stm.journal().unwrap()[1].kind,
+        EventKind::Readout {
+            index:    0,
+            outcome:  Bit::ZERO,
+            reported: Bit::ONE,
+        }
+    ));
+}
+
+#[test]
+fn sample_01() {
+    let num_qubits = NonZeroU16::new(5).unwrap();
+    let gates = random_circuit(5, 40, 2);
+    let mut soa = System::with_layout(num_qubits, 8, Split);
+    let mut stm = System::new(num_qubits, 8);
+    for gate in &gates {
+        soa.apply(gate);
+        stm.apply(gate);
+    }
+    assert_eq!(soa.sample_histogram(100), stm.sample_histogram(100));
+}
@@ -0,0 +1,245 @@
+use std::num::NonZeroU16;
+
+use qn::{
+    Backend,
+    Gate,
+    SparseSystem,
+    System,
+};
+
+fn gen_stm(num_qubits: u16) -> SparseSystem<f64> {
+    SparseSystem::new(NonZeroU16::new(num_qubits).unwrap(), 123)
+}
+
+/// Random circuit, generated with a simple linear congruential generator.
+fn random_circuit(
+    num_qubits: u16,
+    len: usize,
+    seed: u64,
+) -> Vec<Gate<f64>> {
+    let mut state = seed;
+    let mut next = |m: u16| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        u16::try_from((state >> 33) % u64::from(m)).unwrap()
+    };
+    (0..len)
+        .map(|_| {
+            let a = next(num_qubits);
+            let b = (a + 1 + next(num_qubits - 1)) % num_qubits;
+            let theta = f64::from(next(1000)) / 100.;
+            match next(8) {
+                0 => Gate::H(a),
+                1 => Gate::X(a),
+                2 => Gate::Rx(a, theta),
+                3 => Gate::Phase(a, theta),
+                4 => Gate::Rz(a, theta),
+                5 => Gate::CNOT(a, b),
+                6 => Gate::CZ(a, b),
+                _ => Gate::SWAP(a, b),
+            }
+        })
+        .collect()
+}
+
+fn assert_matches(
+    sparse: &SparseSystem<f64>,
+    dense: &System<f64>,
+) {
+    for (i, a) in dense.as_slice().iter().enumerate() {
+        let b = sparse.amplitude(i).unwrap();
+        assert!((a - b).norm() < 1e-12, "index {i}: {a} != {b}");
+    }
+}
+
+#[test]
+fn new_01() {
+    let stm = gen_stm(10);
+    assert_eq!(stm.num_qubits().get(), 10);
+    assert_eq!(stm.num_nonzero(), 1);
+    assert_eq!(stm.dense_threshold(), 16);
+    assert!(!stm.is_dense());
+    assert_eq!(stm.amplitude(0).unwrap().re, 1.);
+    assert_eq!(stm.amplitude(1).unwrap().re, 0.);
+    assert_eq!(stm.amplitude(1 << 10), None);
+    assert_eq!(stm.probability(9), Some(0.));
+    assert_eq!(stm.probability(10), None);
+}
+
+#[test]
+fn new_02() {
+    let stm = gen_stm(63);
+    assert_eq!(stm.num_nonzero(), 1);
+    assert_eq!(stm.amplitude(usize::MAX >> 1).unwrap().re, 0.);
+}
+
+#[test]
+#[should_panic(expected = "invalid gate")]
+fn apply_invalid_01() {
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::CZ(1, 1));
+}
+
+#[test]
+fn apply_01() {
+    let mut stm = gen_stm(50);
+    stm.apply(&Gate::X(49));
+    stm.apply(&Gate::H(3));
+    stm.apply(&Gate::CNOT(3, 20));
+    assert_eq!(stm.num_nonzero(), 2);
+    let a = stm.amplitude(1 << 49).unwrap();
+    assert!((a.re - 0.5f64.sqrt()).abs() < 1e-12);
+    let a = stm.amplitude((1 << 49) | (1 << 20) | (1 << 3)).unwrap();
+    assert!((a.re - 0.5f64.sqrt()).abs() < 1e-12);
+
+    // H H = I: amplitudes cancel and are removed.
+    stm.apply(&Gate::CNOT(3, 20));
+    stm.apply(&Gate::H(3));
+    assert_eq!(stm.num_nonzero(), 1);
+}
+
+#[test]
+fn random_circuit_01() {
+    for seed in 0..5 {
+        let num_qubits = 7;
+        let mut sparse = gen_stm(num_qubits);
+        sparse.set_dense_threshold(usize::MAX);
+        let mut dense = System::new(NonZeroU16::new(num_qubits).unwrap(), 1);
+        for gate in random_circuit(num_qubits, 80, seed) {
+            sparse.apply(&gate);
+            dense.apply(&gate);
+        }
+        assert!(!sparse.is_dense());
+        assert_matches(&sparse, &dense);
+        for k in 0..num_qubits {
+            let p = sparse.probability(k).unwrap();
+            assert!((p - dense.probability(k).unwrap()).abs() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn dense_threshold_01() {
+    let mut stm = gen_stm(6);
+    stm.set_dense_threshold(4);
+    stm.apply(&Gate::H(0));
+    stm.apply(&Gate::H(1));
+    assert_eq!(stm.num_nonzero(), 4);
+    assert!(!stm.is_dense());
+
+    stm.apply(&Gate::H(2));
+    assert!(stm.is_dense());
+    assert_eq!(stm.num_nonzero(), 64);
+    assert!((stm.amplitude(0b111).unwrap().re - 0.125f64.sqrt()).abs() < 1e-12);
+    assert!((stm.probability(2).unwrap() - 0.5).abs() < 1e-12);
+
+    // Stays dense until reset.
+    stm.apply(&Gate::H(2));
+    assert!(stm.is_dense());
+    stm.reset();
+    assert!(!stm.is_dense());
+    assert_eq!(stm.num_nonzero(), 1);
+    assert_eq!(stm.dense_threshold(), 4);
+}
+
+#[test]
+fn dense_threshold_02() {
+    let mut stm = gen_stm(12);
+    stm.apply(&Gate::H(0));
+    stm.apply(&Gate::H(1));
+    assert!(!stm.is_dense());
+    stm.set_dense_threshold(3);
+    assert!(stm.is_dense());
+}
+
+#[test]
+fn random_circuit_02() {
+    // Conversion in the middle of the circuit.
+    let num_qubits = 7;
+    let mut sparse = gen_stm(num_qubits);
+    sparse.set_dense_threshold(10);
+    let mut dense = System::new(NonZeroU16::new(num_qubits).unwrap(), 1);
+    for gate in random_circuit(num_qubits, 80, 11) {
+        sparse.apply(&gate);
+        dense.apply(&gate);
+    }
+    assert!(sparse.is_dense());
+    assert_matches(&sparse, &dense);
+}
+
+#[test]
+fn measure_01() {
+    let mut stm = gen_stm(30);
+    stm.apply(&Gate::H(0));
+    for k in 1..30 {
+        stm.apply(&Gate::CNOT(k - 1, k));
+    }
+    let outcome = stm.measure(29).unwrap();
+    assert_eq!(stm.num_nonzero(), 1);
+    for k in 0..30 {
+        assert_eq!(stm.measure(k), Some(outcome));
+    }
+    assert_eq!(stm.measure(30), None);
+}
+
+#[test]
+fn measure_02() {
+    // Same outcomes as a dense system with the same seed, before and after
+    // conversion.
+    let num_qubits = 6;
+    let gates = random_circuit(num_qubits, 40, 5);
+    for threshold in [usize::MAX, 8] {
+        let mut sparse = gen_stm(num_qubits);
+        sparse.set_dense_threshold(threshold);
+        let mut dense = System::new(NonZeroU16::new(num_qubits).unwrap(), 123);
+        for (i, gate) in gates.iter().enumerate() {
+            sparse.apply(gate);
+            dense.apply(gate);
+            if i % 5 == 4 {
+                let k = u16::try_from(i % 6).unwrap();
+                assert_eq!(sparse.measure(k), dense.measure(k));
+            }
+        }
+        assert_matches(&sparse, &dense);
+    }
+}
+
+#[test]
+fn probability_order_01() {
+    // Probabilities are summed in the same order by every instance.
+    let num_qubits = 8;
+    let gates = random_circuit(num_qubits, 120, 7);
+    let mut stms: Vec<_> = (0..4)
+        .map(|_| {
+            let mut stm = gen_stm(num_qubits);
+            stm.set_dense_threshold(usize::MAX);
+            for gate in &gates {
+                stm.apply(gate);
+            }
+            stm
+        })
+        .collect();
+    for k in 0..num_qubits {
+        let p = stms[0].probability(k).unwrap().to_bits();
+        for stm in &stms[1..] {
+            assert_eq!(stm.probability(k).unwrap().to_bits(), p);
+        }
+    }
+    let outcomes: Vec<Vec<_>> = stms
+        .iter_mut()
+        .map(|stm| (0..num_qubits).map(|k| stm.measure(k)).collect())
+        .collect();
+    assert!(outcomes.iter().all(|o| *o == outcomes[0]));
+}
+
+#[test]
+fn into_system_01() {
+    let mut stm = gen_stm(4);
+    stm.apply(&Gate::H(2));
+    stm.apply(&Gate::CNOT(2, 3));
+    let dense = stm.into_system();
+    assert!((dense.as_slice()[0b1100].re - 0.5f64.sqrt()).abs() < 1e-12);
+    assert!((dense.as_slice()[0].re - 0.5f64.sqrt()).abs() < 1e-12);
+    assert!((dense.probability(3).unwrap() - 0.5).abs() < 1e-12);
+}
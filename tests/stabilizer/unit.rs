@@ -0,0 +1,247 @@
+use std::num::NonZeroU16;
+
+use qn::{
+    Bit,
+    Gate,
+    GateKind,
+    StabilizerSystem,
+    System,
+};
+
+fn gen_stm(num_qubits: u16) -> StabilizerSystem<f64> {
+    StabilizerSystem::new(NonZeroU16::new(num_qubits).unwrap(), 123)
+}
+
+/// Random Clifford circuit, generated with a simple linear congruential
+/// generator.
+fn random_clifford(
+    num_qubits: u16,
+    len: usize,
+    seed: u64,
+) -> Vec<Gate<f64>> {
+    let mut state = seed;
+    let mut next = |m: u16| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        u16::try_from((state >> 33) % u64::from(m)).unwrap()
+    };
+    (0..len)
+        .map(|_| {
+            let a = next(num_qubits);
+            let b = (a + 1 + next(num_qubits - 1)) % num_qubits;
+            match next(8) {
+                0 => Gate::H(a),
+                1 => Gate::S(a),
+                2 => Gate::X(a),
+                3 => Gate::Y(a),
+                4 => Gate::Z(a),
+                5 => Gate::CNOT(a, b),
+                6 => Gate::CZ(a, b),
+                _ => Gate::SWAP(a, b),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn new_01() {
+    let mut stm = gen_stm(70);
+    assert_eq!(stm.num_qubits().get(), 70);
+    for k in 0..70 {
+        assert_eq!(stm.is_deterministic(k), Some(true));
+        assert_eq!(stm.probability(k), Some(0.));
+    }
+    assert_eq!(stm.is_deterministic(70), None);
+    assert_eq!(stm.probability(70), None);
+    for mut qubit in stm.qubit_iter() {
+        assert_eq!(qubit.measure(), Bit::ZERO);
+    }
+}
+
+#[test]
+fn single_qubit_01() {
+    let mut stm = gen_stm(5);
+    stm.apply(&Gate::X(0));
+    stm.apply(&Gate::Y(1));
+    stm.apply(&Gate::Z(2));
+    stm.apply(&Gate::H(3));
+    stm.apply(&Gate::S(4));
+
+    assert_eq!(stm.probability(0), Some(1.));
+    assert_eq!(stm.probability(1), Some(1.));
+    assert_eq!(stm.probability(2), Some(0.));
+    assert_eq!(stm.probability(3), Some(0.5));
+    assert_eq!(stm.probability(4), Some(0.));
+}
+
+#[test]
+fn single_qubit_02() {
+    // H S S H = H Z H = X
+    let mut stm = gen_stm(1);
+    for gate in [Gate::H(0), Gate::S(0), Gate::S(0), Gate::H(0)] {
+        stm.apply(&gate);
+    }
+    assert_eq!(stm.probability(0), Some(1.));
+
+    // H S H S H S = e^(i pi/4) I
+    let mut stm = gen_stm(1);
+    for _ in 0..3 {
+        stm.apply(&Gate::H(0));
+        stm.apply(&Gate::S(0));
+    }
+    assert_eq!(stm.probability(0), Some(0.));
+}
+
+#[test]
+fn two_qubit_01() {
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::X(0));
+    stm.apply(&Gate::CNOT(0, 1));
+    assert_eq!(stm.probability(1), Some(1.));
+
+    stm.apply(&Gate::SWAP(1, 2));
+    assert_eq!(stm.probability(1), Some(0.));
+    assert_eq!(stm.probability(2), Some(1.));
+
+    // CZ phase kickback: |+>|1> -> |->|1>
+    stm.apply(&Gate::H(1));
+    stm.apply(&Gate::CZ(2, 1));
+    stm.apply(&Gate::H(1));
+    assert_eq!(stm.probability(1), Some(1.));
+}
+
+#[test]
+fn bell_01() {
+    let mut ones = 0;
+    for seed in 0..50 {
+        let mut stm: StabilizerSystem<f64> =
+            StabilizerSystem::new(NonZeroU16::new(2).unwrap(), seed);
+        stm.apply(&Gate::H(0));
+        stm.apply(&Gate::CNOT(0, 1));
+        assert_eq!(stm.is_deterministic(0), Some(false));
+        assert_eq!(stm.probability(1), Some(0.5));
+
+        let (mut qb0, mut qb1) = stm.qubit_pair(0, 1).unwrap();
+        assert!(qb0.is_from_same_stm(&qb1));
+        let outcome = qb1.measure();
+        assert_eq!(qb0.measure(), outcome);
+        assert_eq!(qb1.measure(), outcome);
+        if outcome == Bit::ONE {
+            ones += 1;
+        }
+    }
+    assert!(ones > 10 && ones < 40);
+}
+
+#[test]
+fn ghz_large() {
+    let n = 500;
+    let mut stm = gen_stm(n);
+    stm.apply(&Gate::H(0));
+    for k in 1..n {
+        stm.apply(&Gate::CNOT(k - 1, k));
+    }
+    let outcome = stm.qubit(n / 2).unwrap().measure();
+    for k in 0..n {
+        assert_eq!(stm.is_deterministic(k), Some(true));
+        assert_eq!(stm.qubit(k).unwrap().measure(), outcome);
+    }
+}
+
+#[test]
+fn reproducible_01() {
+    let measure_all = |seed| {
+        let mut stm: StabilizerSystem<f64> =
+            StabilizerSystem::new(NonZeroU16::new(20).unwrap(), seed);
+        for k in 0..20 {
+            stm.apply(&Gate::H(k));
+        }
+        stm.qubit_iter()
+            .map(|mut q| q.measure())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(measure_all(7), measure_all(7));
+    assert_ne!(measure_all(7), measure_all(8));
+}
+
+#[test]
+fn random_circuits_agree() {
+    let n = 5;
+    for seed in 0..20 {
+        let circuit = random_clifford(n, 60, seed);
+        let mut stab: StabilizerSystem<f64> =
+            StabilizerSystem::new(NonZeroU16::new(n).unwrap(), seed);
+        let mut dense: System<f64> =
+            System::new(NonZeroU16::new(n).unwrap(), seed);
+        for gate in &circuit {
+            assert!(gate.kind().is_clifford());
+            stab.apply(gate);
+            dense.apply(gate);
+        }
+
+        for k in 0..n {
+            let p = dense.probability(k).unwrap();
+            assert!((stab.probability(k).unwrap() - p).abs() < 1e-12);
+            assert_eq!(stab.is_deterministic(k), Some(p.min(1. - p) < 1e-12));
+        }
+
+        // Measured bitstring has non-zero probability.
+        let index = stab
+            .qubit_iter()
+            .enumerate()
+            .map(|(k, mut q)| usize::from(q.measure() == Bit::ONE) << k)
+            .sum::<usize>();
+        assert!(dense.as_slice()[index].norm_sqr() > 1e-12);
+    }
+}
+
+#[test]
+fn is_clifford_01() {
+    for kind in [
+        GateKind::H,
+        GateKind::X,
+        GateKind::Y,
+        GateKind::Z,
+        GateKind::S,
+        GateKind::CNOT,
+        GateKind::CZ,
+        GateKind::SWAP,
+    ] {
+        assert!(kind.is_clifford());
+    }
+    for kind in [
+        GateKind::Phase,
+        GateKind::Rx,
+        GateKind::Ry,
+        GateKind::Rz,
+        GateKind::U,
+        GateKind::U2,
+    ] {
+        assert!(!kind.is_clifford());
+    }
+}
+
+#[test]
+#[should_panic(expected = "non-Clifford gate")]
+fn non_clifford() {
+    let mut stm = gen_stm(2);
+    stm.apply(&Gate::Rx(0, 0.1));
+}
+
+#[test]
+#[should_panic(expected = "invalid gate")]
+fn invalid_gate() {
+    let mut stm = gen_stm(2);
+    stm.apply(&Gate::CNOT(0, 0));
+}
+
+#[test]
+fn qubit_01() {
+    let mut stm = gen_stm(3);
+    assert!(stm.qubit(3).is_none());
+    assert!(stm.qubit_pair(1, 1).is_none());
+    assert!(stm.qubit_pair(0, 3).is_none());
+    assert_eq!(stm.qubit(2).unwrap().index(), 2);
+    assert_eq!(stm.qubit_iter().count(), 3);
+}
@@ -1,7 +1,16 @@
-use std::num::NonZeroU16;
+use std::{
+    num::NonZeroU16,
+    sync::Arc,
+};
 
 use num::Complex;
-use qn::System;
+use qn::{
+    Backend,
+    Channel,
+    Gate,
+    Parallelism,
+    System,
+};
 
 #[test]
 fn init_01() {
@@ -48,3 +57,137 @@ fn as_mut_slice_01() {
     assert_eq!(stm.as_slice()[2], Complex::from(6.));
     assert_eq!(stm.as_slice()[3], Complex::from(7.));
 }
+
+/// Run `f` in thread pools of different sizes.
+fn in_pools<R>(f: impl Fn() -> R + Send + Sync) -> Vec<R>
+where
+    R: Send,
+{
+    [1, 2, 3, 8]
+        .into_iter()
+        .map(|num_threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap()
+                .install(&f)
+        })
+        .collect()
+}
+
+fn gen_entangled(num_qubits: u16) -> System<f64> {
+    let mut stm = System::new(NonZeroU16::new(num_qubits).unwrap(), 1);
+    stm.set_reproducible(true);
+    for k in 0..num_qubits {
+        stm.apply(&Gate::Ry(k, 0.3 + 0.17 * f64::from(k)));
+    }
+    for k in 1..num_qubits {
+        stm.apply(&Gate::CNOT(k - 1, k));
+        stm.apply(&Gate::Rx(k, 0.2));
+    }
+    stm
+}
+
+#[test]
+fn reproducible_01() {
+    let mut stm = System::<f64>::new(NonZeroU16::new(2).unwrap(), 1);
+    assert!(!stm.reproducible());
+    stm.set_reproducible(true);
+    assert!(stm.reproducible());
+    stm.reset();
+    assert!(stm.reproducible());
+}
+
+#[test]
+fn reproducible_02() {
+    let num_qubits = 15;
+    let mut stm = gen_entangled(num_qubits);
+    for k in 0..num_qubits {
+        let p = in_pools(|| stm.probability(k).unwrap().to_bits());
+        assert!(p.iter().all(|&x| x == p[0]), "qubit {k}: {p:?}");
+    }
+
+    let expected: Vec<_> = (0..num_qubits)
+        .map(|k| stm.probability(k).unwrap())
+        .collect();
+    stm.set_reproducible(false);
+    for k in 0..num_qubits {
+        let p = stm.probability(k).unwrap();
+        assert!((p - expected[usize::from(k)]).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn reproducible_03() {
+    let num_qubits = 14;
+    let runs = in_pools(|| {
+        let mut stm = gen_entangled(num_qubits);
+        let channel = Channel::depolarizing(0.3).unwrap();
+        let mut outcomes = Vec::new();
+        for k in 0..num_qubits {
+            stm.apply_channel(&channel, &[k]);
+            outcomes.push(stm.measure(k).unwrap());
+        }
+        let bits: Vec<_> = stm
+            .as_slice()
+            .iter()
+            .map(|a| (a.re.to_bits(), a.im.to_bits()))
+            .collect();
+        (outcomes, bits)
+    });
+    for run in &runs[1..] {
+        assert!(run == &runs[0]);
+    }
+}
+
+#[test]
+fn parallelism_01() {
+    let mut stm = System::<f64>::new(NonZeroU16::new(2).unwrap(), 1);
+    assert!(matches!(stm.parallelism(), Parallelism::Global));
+    assert_eq!(stm.sequential_threshold(), 1 << 12);
+
+    stm.set_parallelism(Parallelism::Sequential);
+    stm.set_sequential_threshold(0);
+    assert!(matches!(stm.parallelism(), Parallelism::Sequential));
+    assert_eq!(stm.sequential_threshold(), 0);
+}
+
+#[test]
+fn parallelism_02() {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(3)
+        .build()
+        .unwrap();
+    let configs = [
+        (Parallelism::Global, 0),
+        (Parallelism::Global, usize::MAX),
+        (Parallelism::Pool(Arc::new(pool)), 0),
+        (Parallelism::Sequential, 0),
+    ];
+    let runs: Vec<_> = configs
+        .into_iter()
+        .map(|(parallelism, threshold)| {
+            let mut stm = gen_entangled(13);
+            stm.set_parallelism(parallelism);
+            stm.set_sequential_threshold(threshold);
+            let channel = Channel::amplitude_damping(0.4).unwrap();
+            let mut outcomes = Vec::new();
+            for k in 0..13 {
+                stm.apply(&Gate::H(k));
+                stm.apply_channel(&channel, &[k]);
+                outcomes.push(stm.measure(k).unwrap());
+            }
+            stm.reset();
+            stm.apply(&Gate::Rx(12, 1.));
+            let bits: Vec<_> = stm
+                .as_slice()
+                .iter()
+                .map(|a| (a.re.to_bits(), a.im.to_bits()))
+                .collect();
+            (outcomes, bits)
+        })
+        .collect();
+    for run in &runs[1..] {
+        assert!(run == &runs[0]);
+    }
+}
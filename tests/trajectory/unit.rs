@@ -0,0 +1,126 @@
+use std::num::NonZeroU16;
+
+use num::{
+    Complex,
+    One,
+    Zero,
+};
+use qn::{
+    trajectory,
+    Channel,
+    DensityMatrixSystem,
+    Gate,
+    System,
+};
+
+const EPS: f64 = 1e-12;
+
+fn gen_stm(num_qubits: u16) -> System<f64> {
+    System::new(NonZeroU16::new(num_qubits).unwrap(), 123)
+}
+
+fn norm_sqr(stm: &System<f64>) -> f64 {
+    stm.as_slice().iter().map(|a| a.norm_sqr()).sum()
+}
+
+#[test]
+#[should_panic(expected = "invalid channel qubits")]
+fn apply_channel_invalid_01() {
+    let channel = Channel::bit_flip(0.1).unwrap();
+    gen_stm(2).apply_channel(&channel, &[0, 1]);
+}
+
+#[test]
+fn apply_channel_01() {
+    let mut stm = gen_stm(2);
+    stm.apply_channel(&Channel::bit_flip(1.).unwrap(), &[1]);
+    assert!((stm.probability(1).unwrap() - 1.).abs() < EPS);
+    assert!(stm.probability(0).unwrap().abs() < EPS);
+
+    stm.apply_channel(&Channel::amplitude_damping(1.).unwrap(), &[1]);
+    assert!(stm.probability(1).unwrap().abs() < EPS);
+}
+
+#[test]
+fn apply_channel_02() {
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::H(0));
+    stm.apply(&Gate::CNOT(0, 2));
+    let channel = Channel::depolarizing2(0.5).unwrap();
+    let damping = Channel::amplitude_damping(0.4).unwrap();
+    for _ in 0..20 {
+        stm.apply_channel(&channel, &[2, 0]);
+        stm.apply_channel(&damping, &[1]);
+        assert!((norm_sqr(&stm) - 1.).abs() < EPS);
+    }
+}
+
+#[test]
+fn apply_channel_03() {
+    // K_0 = |b0 b1 = 11><10|, K_1 = 1 - |10><10| on qubits (2, 0)
+    let mut ops = vec![[[Complex::zero(); 4]; 4]; 2];
+    ops[0][3][1] = Complex::one();
+    for i in [0, 2, 3] {
+        ops[1][i][i] = Complex::one();
+    }
+    let channel = Channel::from_kraus2(ops).unwrap();
+
+    let mut stm = gen_stm(3);
+    stm.apply(&Gate::X(2));
+    stm.apply(&Gate::H(1));
+    stm.apply_channel(&channel, &[2, 0]);
+    assert!((norm_sqr(&stm) - 1.).abs() < EPS);
+    assert!((stm.probability(0).unwrap() - 1.).abs() < EPS);
+    assert!((stm.probability(1).unwrap() - 0.5).abs() < EPS);
+    assert!((stm.probability(2).unwrap() - 1.).abs() < EPS);
+}
+
+#[test]
+fn average_01() {
+    let num_qubits = NonZeroU16::new(1).unwrap();
+    assert!(
+        trajectory::average(num_qubits, 0, 1, |_: &mut System<f64>| 0.)
+            .is_none()
+    );
+
+    let est = trajectory::average(num_qubits, 1, 1, |_: &mut System<f64>| 0.5)
+        .unwrap();
+    assert!((est.mean - 0.5).abs() < EPS);
+    assert!(est.std_error.abs() < EPS);
+}
+
+#[test]
+fn average_02() {
+    let num_qubits = NonZeroU16::new(2).unwrap();
+    let channel = Channel::depolarizing(0.3).unwrap();
+    let damping = Channel::amplitude_damping(0.2).unwrap();
+    let gates = [Gate::Ry(0, 1.2), Gate::CNOT(0, 1), Gate::H(1)];
+
+    let mut dm = DensityMatrixSystem::new(num_qubits, 1);
+    for gate in &gates {
+        dm.apply(gate);
+    }
+    dm.apply_channel(&channel, &[1]);
+    dm.apply_channel(&damping, &[0]);
+    let expected = dm.probability(1).unwrap();
+
+    let run = |stm: &mut System<f64>| {
+        for gate in &gates {
+            stm.apply(gate);
+        }
+        stm.apply_channel(&channel, &[1]);
+        stm.apply_channel(&damping, &[0]);
+        stm.probability(1).unwrap()
+    };
+    let est = trajectory::average(num_qubits, 2000, 123, run).unwrap();
+    assert!(
+        (est.mean - expected).abs() < 4. * est.std_error,
+        "{est:?}, expected: {expected}"
+    );
+
+    // reproducible for the same seed
+    assert_eq!(
+        trajectory::average(num_qubits, 2000, 123, run).unwrap(),
+        est
+    );
+}
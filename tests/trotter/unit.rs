@@ -0,0 +1,226 @@
+use std::num::NonZeroU16;
+
+use qn::{
+    trotter::{
+        self,
+        Order,
+    },
+    DensityMatrixSystem,
+    Gate,
+    Lindbladian,
+    Pauli,
+    PauliString,
+    PauliSum,
+    System,
+};
+
+const EPS: f64 = 1e-12;
+
+fn gen_stm(num_qubits: u16) -> System<f64> {
+    let mut stm = System::new(NonZeroU16::new(num_qubits).unwrap(), 123);
+    for k in 0..num_qubits {
+        stm.apply(&Gate::Ry(k, 0.3 + f64::from(k)));
+        stm.apply(&Gate::Rz(k, 0.7 * f64::from(k)));
+    }
+    stm
+}
+
+fn string(ops: &[(u16, Pauli)]) -> PauliString {
+    PauliString::new(ops).unwrap()
+}
+
+fn chain(num_qubits: u16) -> PauliSum<f64> {
+    // Heisenberg-like chain with a field: terms do not commute.
+    let mut ham = PauliSum::new();
+    for k in 0..num_qubits - 1 {
+        ham.add_term(1., string(&[(k, Pauli::X), (k + 1, Pauli::X)]));
+        ham.add_term(0.8, string(&[(k, Pauli::Y), (k + 1, Pauli::Y)]));
+        ham.add_term(0.6, string(&[(k, Pauli::Z), (k + 1, Pauli::Z)]));
+    }
+    for k in 0..num_qubits {
+        ham.add_term(0.4, string(&[(k, Pauli::X)]));
+    }
+    ham
+}
+
+fn distance(
+    a: &System<f64>,
+    b: &System<f64>,
+) -> f64 {
+    a.as_slice()
+        .iter()
+        .zip(b.as_slice())
+        .map(|(x, y)| (x - y).norm_sqr())
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[test]
+fn apply_exp_01() {
+    let theta = 0.37;
+    for (op, gate) in [
+        (Pauli::X, Gate::Rx(1, 2. * theta)),
+        (Pauli::Y, Gate::Ry(1, 2. * theta)),
+        (Pauli::Z, Gate::Rz(1, 2. * theta)),
+    ] {
+        let mut stm = gen_stm(3);
+        trotter::apply_exp(&mut stm, &string(&[(1, op)]), theta);
+        let mut expected = gen_stm(3);
+        expected.apply(&gate);
+
+        assert!(distance(&stm, &expected) < EPS);
+    }
+}
+
+#[test]
+fn apply_exp_02() {
+    // exp(-i theta Z0 Z2) = CNOT(0, 2) Rz(2, 2 theta) CNOT(0, 2)
+    let theta = 0.91;
+    let mut stm = gen_stm(3);
+    trotter::apply_exp(
+        &mut stm,
+        &string(&[(0, Pauli::Z), (2, Pauli::Z)]),
+        theta,
+    );
+
+    let mut expected = gen_stm(3);
+    expected.apply(&Gate::CNOT(0, 2));
+    expected.apply(&Gate::Rz(2, 2. * theta));
+    expected.apply(&Gate::CNOT(0, 2));
+
+    assert!(distance(&stm, &expected) < EPS);
+}
+
+#[test]
+fn apply_exp_03() {
+    // exp(-i theta X0 Y2): rotate into the Z basis.
+    let theta = -0.42;
+    let mut stm = gen_stm(3);
+    trotter::apply_exp(
+        &mut stm,
+        &string(&[(0, Pauli::X), (2, Pauli::Y)]),
+        theta,
+    );
+
+    let mut expected = gen_stm(3);
+    expected.apply(&Gate::H(0));
+    expected.apply(&Gate::Rx(2, std::f64::consts::FRAC_PI_2));
+    expected.apply(&Gate::CNOT(0, 2));
+    expected.apply(&Gate::Rz(2, 2. * theta));
+    expected.apply(&Gate::CNOT(0, 2));
+    expected.apply(&Gate::Rx(2, -std::f64::consts::FRAC_PI_2));
+    expected.apply(&Gate::H(0));
+
+    assert!(distance(&stm, &expected) < EPS);
+}
+
+#[test]
+#[should_panic(expected = "Pauli string does not fit in the system")]
+fn apply_exp_invalid() {
+    let mut stm = gen_stm(2);
+    trotter::apply_exp(&mut stm, &string(&[(2, Pauli::X)]), 0.1);
+}
+
+#[test]
+#[should_panic(expected = "Hamiltonian does not fit in the system")]
+fn evolve_invalid() {
+    let mut stm = gen_stm(2);
+    trotter::evolve(&mut stm, &chain(3), 1., 10, Order::First);
+}
+
+#[test]
+fn evolve_zero_steps() {
+    let mut stm = gen_stm(3);
+    trotter::evolve(&mut stm, &chain(3), 1., 0, Order::Fourth);
+
+    assert!(distance(&stm, &gen_stm(3)) < EPS);
+}
+
+#[test]
+fn evolve_commuting() {
+    // For commuting terms, any product formula is exact.
+    let mut ham = PauliSum::new();
+    ham.add_term(0.5, string(&[(0, Pauli::Z), (1, Pauli::Z)]));
+    ham.add_term(-0.3, string(&[(0, Pauli::Y), (1, Pauli::Y)]));
+    ham.add_term(0.2, string(&[(0, Pauli::X), (1, Pauli::X)]));
+
+    let mut reference = gen_stm(2);
+    trotter::evolve(&mut reference, &ham, 1.3, 1, Order::First);
+    for order in [Order::First, Order::Second, Order::Fourth] {
+        let mut stm = gen_stm(2);
+        trotter::evolve(&mut stm, &ham, 1.3, 7, order);
+        assert!(distance(&stm, &reference) < 1e-10);
+    }
+}
+
+#[test]
+fn evolve_unitary() {
+    let mut stm = gen_stm(4);
+    trotter::evolve(&mut stm, &chain(4), 2., 5, Order::Second);
+    let norm: f64 = stm.as_slice().iter().map(|z| z.norm_sqr()).sum();
+
+    assert!((norm - 1.).abs() < EPS);
+}
+
+#[test]
+fn evolve_convergence() {
+    let (n, time) = (4, 1.);
+    let ham = chain(n);
+    let mut reference = gen_stm(n);
+    trotter::evolve(&mut reference, &ham, time, 400, Order::Fourth);
+
+    for order in [Order::First, Order::Second, Order::Fourth] {
+        let error = |steps| {
+            let mut stm = gen_stm(n);
+            trotter::evolve(&mut stm, &ham, time, steps, order);
+            distance(&stm, &reference)
+        };
+        let expected = 2f64.powi(i32::try_from(order.as_u32()).unwrap());
+        let ratio = error(8) / error(16);
+        assert!(
+            (ratio / expected - 1.).abs() < 0.2,
+            "{order:?}: ratio {ratio}, expected {expected}"
+        );
+    }
+}
+
+#[test]
+fn error_estimate_01() {
+    let (n, time) = (3, 1.5);
+    let ham = chain(n);
+    let stm = gen_stm(n);
+    let mut reference = gen_stm(n);
+    trotter::evolve(&mut reference, &ham, time, 400, Order::Fourth);
+
+    for (order, steps) in
+        [(Order::First, 64), (Order::Second, 16), (Order::Fourth, 8)]
+    {
+        let estimate = trotter::error_estimate(&stm, &ham, time, steps, order);
+        let mut evolved = gen_stm(n);
+        trotter::evolve(&mut evolved, &ham, time, steps, order);
+        let error = distance(&evolved, &reference);
+
+        assert!(
+            (estimate / error - 1.).abs() < 0.2,
+            "{order:?}: estimate {estimate}, error {error}"
+        );
+    }
+}
+
+#[test]
+fn lindblad_agrees() {
+    let (n, time) = (3, 0.8);
+    let ham = chain(n);
+    let mut stm = gen_stm(n);
+    let mut rho = DensityMatrixSystem::from(&stm);
+
+    trotter::evolve(&mut stm, &ham, time, 100, Order::Fourth);
+    rho.evolve(&Lindbladian::new(ham), time, 1e-12).unwrap();
+
+    let psi = stm.as_slice();
+    let dim = psi.len();
+    for (i, x) in rho.as_slice().iter().enumerate() {
+        let y = psi[i / dim] * psi[i % dim].conj();
+        assert!((x - y).norm() < 1e-8);
+    }
+}